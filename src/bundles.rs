@@ -0,0 +1,341 @@
+//! Fingerprinted CSS bundles in place of inline styles.
+//!
+//! Every page links two stylesheets: the shared bundle, which is the site's
+//! theme followed by the layout's `base.css` and the content's `styles.css`,
+//! and a bundle of the page's theme override and its own `<style>` blocks.
+//! A `<style>` element with attributes, like the modeling page's
+//! `#dynamicBackground` that its script rewrites, stays where it is.
+//!
+//! Rules are minified, and a rule is dropped when an identical one follows
+//! it. A page bundle also leaves out rules the shared bundle already ends
+//! with for the same selector, and rules whose selectors all name a class
+//! or id that appears nowhere in the page, scripts included. The shared
+//! bundle serves every page, including ones the server renders later, so
+//! it keeps every rule.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::minify;
+use crate::output;
+use crate::render;
+use crate::theme;
+
+/// Folder bundles are served from, under the site's base path.
+pub const CSS_DIR: &str = "css";
+
+/// At-rules whose blocks hold style rules that can be stripped.
+const CONDITIONAL_RULES: &[&str] = &["@media", "@supports"];
+
+/// A stylesheet named after its contents, so it can be cached for good.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bundle {
+    /// File name, e.g. `shared.0123456789abcdef.css`.
+    pub name: String,
+    pub css: String,
+}
+
+impl Bundle {
+    fn new(kind: &str, css: String) -> Self {
+        let name = format!("{}.{}.css", kind, output::hash(css.as_bytes()));
+        Bundle { name, css }
+    }
+}
+
+/// A rule: a selector list or at-rule prelude, and its block, if any.
+type Rule = (String, Option<String>);
+
+/// Splits minified CSS into its top-level rules.
+fn parse(css: &str) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    let mut block = None;
+    for (index, c) in css.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, c) {
+            (_, '\\') => escaped = true,
+            (Some(open), _) if c == open => quote = None,
+            (Some(_), _) => {},
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{') => {
+                if depth == 0 {
+                    block = Some(index);
+                }
+                depth += 1;
+            },
+            (None, '}') if depth > 0 => {
+                depth -= 1;
+                if depth == 0
+                    && let Some(open) = block.take()
+                {
+                    rules.push((css[start..open].to_string(), Some(css[open + 1..index].to_string())));
+                    start = index + 1;
+                }
+            },
+            (None, ';') if depth == 0 => {
+                rules.push((css[start..index].to_string(), None));
+                start = index + 1;
+            },
+            _ => {},
+        }
+    }
+    if !css[start..].trim().is_empty() {
+        rules.push((css[start..].to_string(), None));
+    }
+    rules
+}
+
+fn print(rules: &[Rule]) -> String {
+    rules
+        .iter()
+        .map(|(prelude, block)| match block {
+            Some(block) => format!("{}{{{}}}", prelude, block),
+            None => format!("{};", prelude),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Splits a selector list at its top-level commas.
+fn split_selectors(selectors: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in selectors.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&selectors[start..index]);
+                start = index + 1;
+            },
+            _ => {},
+        }
+    }
+    parts.push(&selectors[start..]);
+    parts
+}
+
+/// Whether every class and id `selector` requires is in `used`. Names inside
+/// brackets and parentheses, as in `:not(.active)`, are not requirements, and
+/// selectors with escapes are always kept.
+fn is_used(selector: &str, used: &HashSet<String>) -> bool {
+    if selector.contains('\\') {
+        return true;
+    }
+    let chars: Vec<char> = selector.chars().collect();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            '"' | '\'' => {
+                let quote = chars[i];
+                i += 1;
+                while i < chars.len() && chars[i] != quote {
+                    i += 1;
+                }
+            },
+            '.' | '#' if depth == 0 => {
+                let name: String = chars[i + 1..].iter().take_while(|&&c| is_name_char(c)).collect();
+                if !name.is_empty() && !used.contains(&name) {
+                    return false;
+                }
+                i += name.chars().count();
+            },
+            _ => {},
+        }
+        i += 1;
+    }
+    true
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+/// Every word that could be a class or id in `markup`.
+fn words(markup: &str) -> HashSet<String> {
+    markup.split(|c: char| !is_name_char(c)).filter(|word| !word.is_empty()).map(str::to_string).collect()
+}
+
+/// Drops the selectors `used` has no names for, and rules left with none.
+fn strip(rules: Vec<Rule>, used: &HashSet<String>) -> Vec<Rule> {
+    rules
+        .into_iter()
+        .filter_map(|(prelude, block)| {
+            let block = block?;
+            if prelude.starts_with('@') {
+                if !CONDITIONAL_RULES.iter().any(|rule| prelude.starts_with(rule)) {
+                    return Some((prelude, Some(block)));
+                }
+                let inner = strip(parse(&block), used);
+                return (!inner.is_empty()).then(|| (prelude, Some(print(&inner))));
+            }
+            let kept: Vec<&str> = split_selectors(&prelude).into_iter().filter(|selector| is_used(selector, used)).collect();
+            (!kept.is_empty()).then(|| (kept.join(","), Some(block)))
+        })
+        .collect()
+}
+
+/// Keeps statements like `@import` and `@charset` that [`strip`] leaves out.
+fn statements(rules: &[Rule]) -> Vec<Rule> {
+    rules.iter().filter(|(_, block)| block.is_none()).cloned().collect()
+}
+
+/// Drops each rule that an identical rule follows.
+fn dedupe(rules: Vec<Rule>) -> Vec<Rule> {
+    let mut seen = HashSet::new();
+    let mut kept: Vec<Rule> = rules.into_iter().rev().filter(|rule| seen.insert(rule.clone())).collect();
+    kept.reverse();
+    kept
+}
+
+/// Minifies, strips and dedupes a stylesheet against `used`.
+fn optimize(css: &str, used: &HashSet<String>) -> Vec<Rule> {
+    let rules = parse(&minify::css(css));
+    let mut optimized = statements(&rules);
+    optimized.extend(strip(rules, used));
+    dedupe(optimized)
+}
+
+/// Cuts the `<style>` elements without attributes out of `html`, returning
+/// the rest of the page and their contents.
+pub fn extract_styles(html: &str) -> (String, String) {
+    let mut page = String::with_capacity(html.len());
+    let mut css = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<style>") {
+        let Some(end) = rest[start..].find("</style>").map(|end| start + end) else {
+            break;
+        };
+        page.push_str(&rest[..start]);
+        css.push_str(&rest[start + "<style>".len()..end]);
+        css.push('\n');
+        rest = &rest[end + "</style>".len()..];
+        rest = rest.strip_prefix('\n').unwrap_or(rest);
+    }
+    page.push_str(rest);
+    (page, css)
+}
+
+/// Every source [`Bundler::load`] reads under `dir` and when it was last
+/// changed: stylesheets and themes.
+fn collect_stamps(dir: &Path, stamps: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        if path.is_dir() {
+            if !name.starts_with('.') {
+                collect_stamps(&path, stamps);
+            }
+        } else if name == theme::THEME_NAME || path.extension().is_some_and(|extension| extension == "css") {
+            let metadata = fs::metadata(&path).ok();
+            let modified = metadata.as_ref().and_then(|metadata| metadata.modified().ok());
+            stamps.push(format!("{}:{:?}:{:?}", path.display(), metadata.map(|metadata| metadata.len()), modified));
+        }
+    }
+}
+
+/// A fingerprint of the content's styles and themes that changes
+/// whenever one of them is edited, added or removed, without reading them.
+pub fn sources_fingerprint(content_dir: &Path) -> String {
+    let mut stamps = Vec::new();
+    collect_stamps(content_dir, &mut stamps);
+    output::hash(stamps.join("\n").as_bytes())
+}
+
+/// Builds the bundles for a set of pages.
+pub struct Bundler {
+    shared: Bundle,
+    /// The last block the shared bundle gives each selector.
+    shared_blocks: HashMap<String, String>,
+    /// Theme overrides of pages, by route.
+    page_themes: BTreeMap<String, String>,
+    /// Page bundles handed out so far, by name.
+    pages: Mutex<BTreeMap<String, Bundle>>,
+}
+
+impl Bundler {
+    /// Bundles `shared_css`.
+    pub fn new(shared_css: &str) -> Self {
+        let rules = dedupe(parse(&minify::css(shared_css)));
+        let shared_blocks = rules.iter().filter_map(|(prelude, block)| Some((prelude.clone(), block.clone()?))).collect();
+        Bundler {
+            shared: Bundle::new("shared", print(&rules)),
+            shared_blocks,
+            page_themes: BTreeMap::new(),
+            pages: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Reads the content's theme and `styles.css`, if any.
+    pub fn load(content_dir: &Path) -> Result<Self, String> {
+        let themes = theme::read_theme(content_dir)?.stylesheets()?;
+        let styles_path = content_dir.join("styles.css");
+        let styles = if styles_path.exists() {
+            fs::read_to_string(&styles_path).map_err(|e| format!("failed to read {}: {}", styles_path.display(), e))?
+        } else {
+            String::new()
+        };
+        let mut bundler = Bundler::new(&format!("{}\n{}\n{}", themes.site, render::BASE_STYLES, styles));
+        bundler.page_themes = themes.pages;
+        Ok(bundler)
+    }
+
+    pub fn shared(&self) -> &Bundle {
+        &self.shared
+    }
+
+    /// Moves the page's `<style>` blocks, after the theme override of its
+    /// `route`, into its bundle and links that and the shared bundle from
+    /// the head, with `css_url` the URL of [`CSS_DIR`].
+    pub fn apply(&self, html: &str, route: Option<&str>, css_url: &str) -> String {
+        let (page, styles) = extract_styles(html);
+        let theme = route.and_then(|route| self.page_themes.get(route)).map_or("", String::as_str);
+        // At-rules are kept, as the page's dark theme has to follow its light one
+        let rules: Vec<Rule> = optimize(&format!("{}{}", theme, styles), &words(&page))
+            .into_iter()
+            .filter(|(prelude, block)| block.is_none() || prelude.starts_with('@') || self.shared_blocks.get(prelude) != block.as_ref())
+            .collect();
+
+        let mut links = format!(r#"<link rel="stylesheet" href="{}/{}">"#, css_url, self.shared.name);
+        if !rules.is_empty() {
+            let bundle = Bundle::new("page", print(&rules));
+            links.push_str(&format!("\n    <link rel=\"stylesheet\" href=\"{}/{}\">", css_url, bundle.name));
+            self.pages.lock().unwrap().insert(bundle.name.clone(), bundle);
+        }
+        match page.find("</head>") {
+            Some(head_end) => format!("{}    {}\n{}", &page[..head_end], links, &page[head_end..]),
+            None => format!("{}\n{}", links, page),
+        }
+    }
+
+    /// The shared bundle or a page bundle handed out so far, by file name.
+    pub fn bundle(&self, name: &str) -> Option<Bundle> {
+        if name == self.shared.name {
+            return Some(self.shared.clone());
+        }
+        self.pages.lock().unwrap().get(name).cloned()
+    }
+
+    /// The shared bundle and every page bundle handed out so far.
+    pub fn bundles(&self) -> Vec<Bundle> {
+        let mut bundles = vec![self.shared.clone()];
+        bundles.extend(self.pages.lock().unwrap().values().cloned());
+        bundles
+    }
+}
@@ -0,0 +1,220 @@
+//! Command line of `generate-static`.
+
+use std::path::PathBuf;
+
+use crate::content::DEFAULT_CONTENT_DIR;
+use crate::output::Verbosity;
+use crate::static_site::{BuildOptions, DEFAULT_BASE_PATH, DEFAULT_OUT_DIR, get_git_hash};
+
+pub const USAGE: &str = "\
+usage: generate-static [command] [options]
+
+commands:
+  build            build the site into the output directory (the default)
+  clean            remove the output directory and what interrupted builds left
+  check            check the content for problems without building
+  serve            preview the output directory locally
+  new post TITLE   start a draft news post
+  new category NAME
+                   start a modeling category
+  --check          same as check, kept for older scripts
+
+options:
+  --content DIR    content directory (build, check, new; default templates)
+  --out DIR        output directory (build, clean, serve; default docs)
+  --base-url URL   path, or absolute URL, the site is published at
+                   (build, serve; default /Website-test)
+  --version TEXT   version to report (build; default the git commit)
+  --drafts         publish draft posts (build)
+  --minify         minify generated pages (build)
+  --shop-url URL   server running the shop's cart and checkout (build;
+                   default shop_url in site.yaml, without it no cart)
+  --port N         port to preview on (serve; default 4000)
+  -q, --quiet      print errors only
+  -v, --verbose    also print every file written or copied
+  -h, --help       print this help
+
+exit status: 0 on success, 1 when the build, check or command fails,
+2 when the arguments are wrong";
+
+/// Exit status for arguments that cannot be parsed.
+pub const EXIT_USAGE: u8 = 2;
+
+/// Port `serve` listens on unless `--port` is given.
+pub const DEFAULT_PREVIEW_PORT: u16 = 4000;
+
+/// What `generate-static new` creates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NewItem {
+    /// A draft news post with this title.
+    Post(String),
+    /// A modeling category with this folder name.
+    Category(String),
+}
+
+#[derive(Clone, Debug)]
+pub enum Command {
+    Build(BuildOptions),
+    Clean { out_dir: PathBuf },
+    Check { content_dir: PathBuf },
+    Serve { out_dir: PathBuf, base_path: String, port: u16 },
+    New { content_dir: PathBuf, item: NewItem },
+    Help,
+}
+
+/// Parsed command line.
+#[derive(Clone, Debug)]
+pub struct Cli {
+    pub command: Command,
+    pub verbosity: Verbosity,
+}
+
+/// Flags followed by a value, as `--out docs` or `--out=docs`.
+const VALUE_FLAGS: &[&str] = &["--content", "--out", "--base-url", "--version", "--shop-url", "--port"];
+
+/// Flags and their values, and the remaining words.
+#[derive(Default)]
+struct Args {
+    flags: Vec<(String, Option<String>)>,
+    positional: Vec<String>,
+}
+
+impl Args {
+    /// Fails on any flag the command does not take.
+    fn allow(&self, command: &str, allowed: &[&str]) -> Result<(), String> {
+        match self.flags.iter().find(|(flag, _)| !allowed.contains(&flag.as_str())) {
+            Some((flag, _)) => Err(format!("{} does not take {}", command, flag)),
+            None => Ok(()),
+        }
+    }
+
+    /// Value of the last `flag` given.
+    fn value(&self, flag: &str) -> Option<&str> {
+        self.flags.iter().rev().find(|(name, _)| name == flag).and_then(|(_, value)| value.as_deref())
+    }
+
+    fn has(&self, flag: &str) -> bool {
+        self.flags.iter().any(|(name, _)| name == flag)
+    }
+}
+
+fn split_args(args: &[String]) -> Result<(Args, Verbosity, bool), String> {
+    let mut parsed = Args::default();
+    let mut verbosity = Verbosity::Normal;
+    let mut help = false;
+    let mut words = args.iter();
+    while let Some(arg) = words.next() {
+        match arg.as_str() {
+            "-q" | "--quiet" => verbosity = Verbosity::Quiet,
+            "-v" | "--verbose" => verbosity = Verbosity::Verbose,
+            "-h" | "--help" => help = true,
+            flag if flag.starts_with('-') => {
+                let (name, inline) = match flag.split_once('=') {
+                    Some((name, value)) => (name, Some(value.to_string())),
+                    None => (flag, None),
+                };
+                let value = if VALUE_FLAGS.contains(&name) {
+                    match inline {
+                        Some(value) => Some(value),
+                        None => Some(words.next().ok_or_else(|| format!("{} needs a value", name))?.clone()),
+                    }
+                } else if inline.is_some() {
+                    return Err(format!("{} does not take a value", name));
+                } else {
+                    None
+                };
+                parsed.flags.push((name.to_string(), value));
+            },
+            word => parsed.positional.push(word.to_string()),
+        }
+    }
+    Ok((parsed, verbosity, help))
+}
+
+/// Splits a `--base-url` into the path links start with and, for an
+/// absolute URL, the site URL it stands for. `/` is the root of the host.
+pub fn parse_base_url(value: &str) -> Result<(String, Option<String>), String> {
+    let value = value.trim();
+    let (path, site_url) = match value.strip_prefix("https://").or_else(|| value.strip_prefix("http://")) {
+        Some(rest) => {
+            let path = rest.find('/').map_or("", |start| &rest[start..]);
+            (path, Some(value.trim_end_matches('/').to_string()))
+        },
+        None if value.starts_with('/') => (value, None),
+        None => return Err(format!("--base-url must be a path starting with / or an http(s) URL, not {:?}", value)),
+    };
+    Ok((path.trim_end_matches('/').to_string(), site_url))
+}
+
+/// Parses the arguments after the program name. Without a command the site
+/// is built, or checked with `--check`, as before there were commands.
+pub fn parse(args: &[String]) -> Result<Cli, String> {
+    let (mut args, verbosity, help) = split_args(args)?;
+    if help || args.positional.first().is_some_and(|word| word == "help") {
+        return Ok(Cli { command: Command::Help, verbosity });
+    }
+    let command = match args.positional.is_empty() {
+        true if args.has("--check") => "check".to_string(),
+        true => "build".to_string(),
+        false => args.positional.remove(0),
+    };
+    let content_dir = args.value("--content").map_or_else(|| PathBuf::from(DEFAULT_CONTENT_DIR), PathBuf::from);
+    let out_dir = args.value("--out").map_or_else(|| PathBuf::from(DEFAULT_OUT_DIR), PathBuf::from);
+    let (base_path, site_url) = match args.value("--base-url") {
+        Some(value) => parse_base_url(value)?,
+        None => (DEFAULT_BASE_PATH.to_string(), None),
+    };
+
+    let command = match command.as_str() {
+        "build" => {
+            args.allow("build", &["--content", "--out", "--base-url", "--version", "--drafts", "--minify", "--shop-url"])?;
+            Command::Build(BuildOptions {
+                content_dir,
+                out_dir,
+                // Only asks git when no version is given
+                version: args.value("--version").map_or_else(get_git_hash, str::to_string),
+                drafts: args.has("--drafts"),
+                base_path,
+                site_url,
+                minify: args.has("--minify"),
+                shop_url: args.value("--shop-url").map(str::to_string),
+                verbosity,
+                ..BuildOptions::default()
+            })
+        },
+        "clean" => {
+            args.allow("clean", &["--out"])?;
+            Command::Clean { out_dir }
+        },
+        "check" => {
+            args.allow("check", &["--content", "--check"])?;
+            Command::Check { content_dir }
+        },
+        "serve" => {
+            args.allow("serve", &["--out", "--base-url", "--port"])?;
+            let port = match args.value("--port") {
+                Some(port) => port.parse().map_err(|_| format!("--port must be a port number, not {:?}", port))?,
+                None => DEFAULT_PREVIEW_PORT,
+            };
+            Command::Serve { out_dir, base_path, port }
+        },
+        "new" => {
+            args.allow("new", &["--content"])?;
+            let (kind, name) = match args.positional.split_first() {
+                Some((kind, words)) if !words.is_empty() => (kind.as_str(), words.join(" ")),
+                _ => return Err("new needs what to create and its name, e.g. new post \"My First Post\"".to_string()),
+            };
+            let item = match kind {
+                "post" => NewItem::Post(name),
+                "category" => NewItem::Category(name),
+                other => return Err(format!("cannot create {:?}; use post or category", other)),
+            };
+            return Ok(Cli { command: Command::New { content_dir, item }, verbosity });
+        },
+        other => return Err(format!("unknown command {:?}", other)),
+    };
+    if let Some(extra) = args.positional.first() {
+        return Err(format!("unexpected argument {:?}", extra));
+    }
+    Ok(Cli { command, verbosity })
+}
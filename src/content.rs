@@ -0,0 +1,324 @@
+//! Readers for the site content directory (`templates/` by default).
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::discography::slugify;
+use crate::render::url_encode;
+
+/// Content directory used by both binaries unless told otherwise.
+pub const DEFAULT_CONTENT_DIR: &str = "templates";
+
+/// File in `reviews/` that approved visitor reviews are written to.
+pub const APPROVED_REVIEWS_NAME: &str = "approved.yaml";
+
+/// Routes with a page template: (route, template path segments, page title).
+pub const PAGES: &[(&str, &[&str], &str)] = &[
+    ("/", &["index.html"], "Home"),
+    ("/contact/", &["contact", "contact.html"], "Contact"),
+    ("/modeling/", &["modeling", "modeling.html"], "Modeling Portfolio"),
+    ("/bio/", &["bio", "bio.html"], "Bio"),
+    ("/music/", &["music", "music.html"], "Music"),
+    ("/acting/", &["acting", "acting.html"], "Acting"),
+    ("/reviews/", &["reviews", "reviews.html"], "Reviews"),
+    ("/events/", &["events", "events.html"], "Events"),
+    ("/news/", &["news", "news.html"], "News"),
+    ("/behind-the-scenes/", &["Behind the scenes", "behind-the-scenes.html"], "Behind the Scenes"),
+    ("/shop/", &["shop", "shop.html"], "Shop"),
+];
+
+/// A quote from `reviews/reviews.yaml`, or a visitor's review approved into
+/// `reviews/approved.yaml`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Testimonial {
+    pub quote: String,
+    pub author: String,
+    /// The author's role or company.
+    pub title: String,
+    #[serde(default, deserialize_with = "optional_date", serialize_with = "serialize_date", skip_serializing_if = "Option::is_none")]
+    pub date: Option<NaiveDate>,
+    /// Stars out of five.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+    /// Where the review was first published.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Photo of the author, relative to the content directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub photo: Option<String>,
+    /// Photographer, fan, director, venue, ...
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Shown in the home page carousel.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub featured: bool,
+    /// Position on the page, lowest first. Testimonials without one follow
+    /// in file order.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<i32>,
+}
+
+impl Testimonial {
+    /// Fragment identifying the testimonial on the reviews page.
+    pub fn anchor(&self) -> String {
+        slugify(&format!("{} {}", self.author, self.title))
+    }
+}
+
+fn optional_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| {
+            NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+                .map_err(|_| serde::de::Error::custom(format!("invalid date {:?}, expected YYYY-MM-DD", value)))
+        })
+        .transpose()
+}
+
+fn serialize_date<S: Serializer>(date: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error> {
+    match date {
+        Some(date) => serializer.serialize_str(&date.format("%Y-%m-%d").to_string()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Layout of `reviews/reviews.yaml` and `reviews/approved.yaml`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct TestimonialsFile {
+    #[serde(default)]
+    pub testimonials: Vec<Testimonial>,
+}
+
+/// Reads and checks a testimonials file. A missing file has none.
+pub fn read_testimonials_file(path: &Path) -> Result<Vec<Testimonial>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let data: TestimonialsFile = serde_yaml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+    for testimonial in &data.testimonials {
+        if let Some(rating) = testimonial.rating
+            && !(1..=5).contains(&rating)
+        {
+            return Err(format!("{}: rating of {:?} must be between 1 and 5", path.display(), testimonial.author));
+        }
+    }
+    Ok(data.testimonials)
+}
+
+#[derive(Clone, Debug)]
+pub struct PageTemplate {
+    pub title: String,
+    pub content: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct CategoryData {
+    pub title: String,
+    pub subtitle: String,
+    pub images: Vec<String>,
+    pub links: HashMap<String, String>,
+    pub background: Option<String>,
+}
+
+/// Reads `reviews/reviews.yaml` followed by the visitor reviews approved
+/// into `reviews/approved.yaml`, sorted by `order`. Missing files mean no
+/// testimonials; an invalid one is an error.
+pub fn read_testimonials(content_dir: &Path) -> Result<Vec<Testimonial>, String> {
+    let reviews_dir = content_dir.join("reviews");
+    let mut testimonials = read_testimonials_file(&reviews_dir.join("reviews.yaml"))?;
+    testimonials.extend(read_testimonials_file(&reviews_dir.join(APPROVED_REVIEWS_NAME))?);
+    testimonials.sort_by_key(|testimonial| testimonial.order.unwrap_or(i32::MAX));
+    Ok(testimonials)
+}
+
+/// Groups testimonials by category, in order of first appearance.
+/// Uncategorized ones come last, under `None`.
+pub fn group_testimonials(testimonials: &[Testimonial]) -> Vec<(Option<&str>, Vec<&Testimonial>)> {
+    let mut groups: Vec<(Option<&str>, Vec<&Testimonial>)> = Vec::new();
+    for testimonial in testimonials {
+        let category = testimonial.category.as_deref();
+        match groups.iter_mut().find(|(name, _)| *name == category) {
+            Some((_, members)) => members.push(testimonial),
+            None => groups.push((category, vec![testimonial])),
+        }
+    }
+    groups.sort_by_key(|(name, _)| name.is_none());
+    groups
+}
+
+/// Average star rating and number of rated testimonials, if any are rated.
+pub fn aggregate_rating(testimonials: &[Testimonial]) -> Option<(f64, usize)> {
+    let ratings: Vec<u8> = testimonials.iter().filter_map(|testimonial| testimonial.rating).collect();
+    if ratings.is_empty() {
+        return None;
+    }
+    let total: u32 = ratings.iter().map(|&rating| u32::from(rating)).sum();
+    Some((f64::from(total) / ratings.len() as f64, ratings.len()))
+}
+
+pub fn discover_templates(content_dir: &Path) -> Result<HashMap<String, PageTemplate>, Box<dyn std::error::Error>> {
+    let mut templates = HashMap::new();
+
+    for (route, segments, title) in PAGES {
+        let path = segments.iter().fold(content_dir.to_path_buf(), |path, segment| path.join(segment));
+        if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            templates.insert(route.to_string(), PageTemplate {
+                title: title.to_string(),
+                content,
+            });
+        }
+    }
+
+    Ok(templates)
+}
+
+fn is_web_image(path: &Path) -> bool {
+    matches!(path.extension().and_then(|e| e.to_str()), Some("png") | Some("jpg") | Some("jpeg"))
+}
+
+/// When the file at `path` was last modified, if that can be read.
+pub fn modified_time(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok().map(DateTime::from)
+}
+
+/// Lists the images in `images_dir` as sorted URLs under `url_prefix`.
+pub fn list_images(images_dir: &Path, url_prefix: &str) -> Vec<String> {
+    let mut images = Vec::new();
+
+    if images_dir.exists()
+        && let Ok(entries) = fs::read_dir(images_dir)
+    {
+        for entry in entries.flatten() {
+            if is_web_image(&entry.path())
+                && let Some(filename) = entry.file_name().to_str()
+            {
+                images.push(format!("{}/{}", url_prefix, url_encode(filename)));
+            }
+        }
+    }
+
+    images.sort();
+    images
+}
+
+/// Names of the images in `source_dir` that get published with the site.
+pub fn image_file_names(source_dir: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(source_dir) {
+        for entry in entries.flatten() {
+            if is_web_image(&entry.path())
+                && let Some(filename) = entry.file_name().to_str()
+            {
+                names.push(filename.to_string());
+            }
+        }
+    }
+
+    names.sort();
+    names
+}
+
+pub fn read_links_file(images_dir: &Path) -> HashMap<String, String> {
+    let mut links = HashMap::new();
+    let links_file = images_dir.join("Links.txt");
+
+    if links_file.exists()
+        && let Ok(content) = fs::read_to_string(&links_file)
+    {
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some((name, url)) = line.split_once(',') {
+                links.insert(name.trim().to_string(), url.trim().to_string());
+            }
+        }
+    }
+
+    links
+}
+
+/// Discovers the modeling categories under `modeling/`, sorted by name.
+///
+/// Image and background URLs are built under `url_base`, e.g.
+/// `/templates/modeling` for the server or `.` for the static site.
+pub fn discover_modeling_categories(content_dir: &Path, url_base: &str) -> Vec<(String, CategoryData)> {
+    let mut categories = Vec::new();
+    let modeling_dir = content_dir.join("modeling");
+
+    if !modeling_dir.exists() {
+        return categories;
+    }
+
+    if let Ok(entries) = fs::read_dir(&modeling_dir) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                let category_name = entry.file_name().to_str().unwrap_or("").to_string();
+
+                let images_dir = entry.path().join("images");
+                if !images_dir.exists() {
+                    continue;
+                }
+
+                let title = {
+                    let mut chars = category_name.chars();
+                    match chars.next() {
+                        None => continue,
+                        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+                    }
+                };
+
+                let subtitle_file = entry.path().join("subtitle.txt");
+                let subtitle = if subtitle_file.exists() {
+                    fs::read_to_string(&subtitle_file)
+                        .map(|s| s.trim().to_string())
+                        .unwrap_or_else(|_| format!("Professional {} photography", category_name))
+                } else {
+                    format!("Professional {} photography", category_name)
+                };
+
+                let images = list_images(&images_dir, &format!("{}/{}/images", url_base, category_name));
+                let links = read_links_file(&images_dir);
+
+                // Check for background image
+                let background_dir = entry.path().join("Background");
+                let background = if background_dir.join("bkgrnd.png").exists() {
+                    Some(format!("{}/{}/Background/bkgrnd.png", url_base, category_name))
+                } else {
+                    None
+                };
+
+                categories.push((category_name, CategoryData {
+                    title,
+                    subtitle,
+                    images,
+                    links,
+                    background,
+                }));
+            }
+        }
+    }
+
+    categories.sort_by(|a, b| a.0.cmp(&b.0));
+    categories
+}
+
+pub fn read_bts_subtitle(content_dir: &Path) -> String {
+    let subtitle_file = content_dir.join("Behind the scenes").join("subtitle.txt");
+    if subtitle_file.exists() {
+        fs::read_to_string(&subtitle_file)
+            .unwrap_or_else(|_| "Behind the scenes photography".to_string())
+            .trim()
+            .to_string()
+    } else {
+        "Behind the scenes photography".to_string()
+    }
+}
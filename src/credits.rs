@@ -0,0 +1,258 @@
+//! The acting résumé from `acting/credits.yaml`, rendered on the acting
+//! page and as a printable PDF.
+
+use printpdf::{BuiltinFont, IndirectFontRef, Line, Mm, PdfDocument, PdfLayerReference, Point};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// File name of the PDF résumé, written next to the acting page.
+pub const RESUME_PDF_NAME: &str = "resume.pdf";
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Stats {
+    pub height: Option<String>,
+    pub weight: Option<String>,
+    pub hair: Option<String>,
+    pub eyes: Option<String>,
+    pub age_range: Option<String>,
+    pub voice: Option<String>,
+}
+
+impl Stats {
+    /// Label and value of each stat that is set, in display order.
+    pub fn entries(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Height", &self.height),
+            ("Weight", &self.weight),
+            ("Hair", &self.hair),
+            ("Eyes", &self.eyes),
+            ("Age Range", &self.age_range),
+            ("Voice", &self.voice),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| value.as_deref().map(|value| (label, value)))
+        .collect()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ActingCredit {
+    pub project: String,
+    pub role: String,
+    pub director: Option<String>,
+    pub company: Option<String>,
+    pub year: Option<u32>,
+    /// Film, Television, Theatre, Commercial, ...; credits are grouped by it.
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+impl ActingCredit {
+    /// The "Director / Company" column.
+    pub fn director_company(&self) -> String {
+        match (&self.director, &self.company) {
+            (Some(director), Some(company)) => format!("Dir. {} / {}", director, company),
+            (Some(director), None) => format!("Dir. {}", director),
+            (None, Some(company)) => company.clone(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ActingResume {
+    pub name: Option<String>,
+    /// Agency or contact line printed under the name.
+    pub contact: Option<String>,
+    pub stats: Stats,
+    pub credits: Vec<ActingCredit>,
+    pub training: Vec<String>,
+    pub skills: Vec<String>,
+}
+
+impl ActingResume {
+    /// Credits grouped by type, in order of each type's first appearance,
+    /// newest first within a group.
+    pub fn credits_by_type(&self) -> Vec<(&str, Vec<&ActingCredit>)> {
+        let mut groups: Vec<(&str, Vec<&ActingCredit>)> = Vec::new();
+        for credit in &self.credits {
+            match groups.iter_mut().find(|(kind, _)| *kind == credit.kind) {
+                Some((_, credits)) => credits.push(credit),
+                None => groups.push((&credit.kind, vec![credit])),
+            }
+        }
+        for (_, credits) in &mut groups {
+            credits.sort_by_key(|credit| std::cmp::Reverse(credit.year));
+        }
+        groups
+    }
+}
+
+/// Reads `acting/credits.yaml`. A missing file means no résumé; an invalid
+/// one is an error.
+pub fn read_resume(content_dir: &Path) -> Result<Option<ActingResume>, String> {
+    let path = content_dir.join("acting").join("credits.yaml");
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    serde_yaml::from_str(&content)
+        .map(Some)
+        .map_err(|e| format!("invalid {}: {}", path.display(), e))
+}
+
+// US Letter, in millimetres
+const PAGE_WIDTH: f32 = 215.9;
+const PAGE_HEIGHT: f32 = 279.4;
+const MARGIN: f32 = 18.0;
+const PT_TO_MM: f32 = 0.3528;
+
+/// Column x positions and widths of the credits table.
+const COLUMNS: [(f32, f32); 4] = [(MARGIN, 58.0), (MARGIN + 60.0, 42.0), (MARGIN + 104.0, 58.0), (PAGE_WIDTH - MARGIN - 12.0, 12.0)];
+
+/// Wraps `text` into lines that fit `width_mm` at `size` points, assuming
+/// Helvetica's average glyph width of about half an em.
+fn wrap(text: &str, width_mm: f32, size: f32) -> Vec<String> {
+    let max_chars = ((width_mm / (size * PT_TO_MM * 0.5)) as usize).max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Writes text top-down across as many pages as needed.
+struct PdfWriter {
+    doc: printpdf::PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    y: f32,
+}
+
+impl PdfWriter {
+    fn ensure_space(&mut self, height: f32) {
+        if self.y - height < MARGIN {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Resume");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(text, size, Mm(x), Mm(self.y), font);
+    }
+
+    fn paragraph(&mut self, text: &str, size: f32, bold: bool) {
+        for line in wrap(text, PAGE_WIDTH - 2.0 * MARGIN, size) {
+            let height = size * PT_TO_MM * 1.4;
+            self.ensure_space(height);
+            self.y -= height;
+            self.text(&line, size, MARGIN, bold);
+        }
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure_space(16.0);
+        self.y -= 8.0;
+        self.paragraph(&text.to_uppercase(), 11.0, true);
+        self.y -= 1.5;
+        self.layer.add_line(Line {
+            points: vec![(Point::new(Mm(MARGIN), Mm(self.y)), false), (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(self.y)), false)],
+            is_closed: false,
+        });
+        self.y -= 1.0;
+    }
+
+    fn row(&mut self, cells: [&str; 4], size: f32, bold: bool) {
+        let wrapped: Vec<Vec<String>> = cells
+            .iter()
+            .zip(COLUMNS)
+            .map(|(cell, (_, width))| wrap(cell, width, size))
+            .collect();
+        let line_height = size * PT_TO_MM * 1.4;
+        let lines = wrapped.iter().map(Vec::len).max().unwrap_or(1).max(1);
+        self.ensure_space(line_height * lines as f32);
+
+        let top = self.y;
+        for (cell, (x, _)) in wrapped.iter().zip(COLUMNS) {
+            self.y = top;
+            for line in cell {
+                self.y -= line_height;
+                self.text(line, size, x, bold);
+            }
+        }
+        self.y = top - line_height * lines as f32 - 0.8;
+    }
+}
+
+/// Renders the résumé as a print-ready US Letter PDF.
+pub fn resume_pdf(resume: &ActingResume) -> Result<Vec<u8>, String> {
+    let name = resume.name.as_deref().unwrap_or("Acting Resume");
+    let (doc, page, layer) = PdfDocument::new(format!("{} - Resume", name), Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Resume");
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| e.to_string())?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| e.to_string())?;
+    let layer = doc.get_page(page).get_layer(layer);
+
+    let mut writer = PdfWriter {
+        doc,
+        layer,
+        regular,
+        bold,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    writer.paragraph(name, 22.0, true);
+    if let Some(contact) = &resume.contact {
+        writer.paragraph(contact, 10.0, false);
+    }
+    let stats = resume
+        .stats
+        .entries()
+        .iter()
+        .map(|(label, value)| format!("{}: {}", label, value))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    if !stats.is_empty() {
+        writer.y -= 2.0;
+        writer.paragraph(&stats, 10.0, false);
+    }
+
+    for (kind, credits) in resume.credits_by_type() {
+        writer.heading(kind);
+        writer.row(["Project", "Role", "Director / Company", "Year"], 9.0, true);
+        for credit in credits {
+            let year = credit.year.map(|year| year.to_string()).unwrap_or_default();
+            writer.row([&credit.project, &credit.role, &credit.director_company(), &year], 9.5, false);
+        }
+    }
+
+    if !resume.training.is_empty() {
+        writer.heading("Training");
+        for entry in &resume.training {
+            writer.paragraph(entry, 10.0, false);
+        }
+    }
+    if !resume.skills.is_empty() {
+        writer.heading("Special Skills");
+        writer.paragraph(&resume.skills.join(", "), 10.0, false);
+    }
+
+    writer.doc.save_to_bytes().map_err(|e| e.to_string())
+}
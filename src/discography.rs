@@ -0,0 +1,228 @@
+//! Albums, EPs and singles from `music/albums.yaml`.
+
+use serde::{Deserialize, Deserializer};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseKind {
+    #[default]
+    Album,
+    Ep,
+    Single,
+}
+
+impl ReleaseKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ReleaseKind::Album => "Album",
+            ReleaseKind::Ep => "EP",
+            ReleaseKind::Single => "Single",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Track {
+    pub title: String,
+    /// Running time as `m:ss` or `h:mm:ss`.
+    pub duration: Option<String>,
+    /// Short note shown next to the title, e.g. a featured artist.
+    pub note: Option<String>,
+    /// Where to listen to this track.
+    pub url: Option<String>,
+    pub lyrics: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Credit {
+    pub role: String,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReleaseLink {
+    pub label: String,
+    pub url: String,
+    /// `stream-btn` style to use (`spotify`, `amazon`, ...); derived from
+    /// the label when omitted.
+    pub platform: Option<String>,
+}
+
+impl ReleaseLink {
+    pub fn platform(&self) -> String {
+        self.platform.clone().unwrap_or_else(|| slugify(&self.label))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Release {
+    pub title: String,
+    /// URL segment of the detail page; derived from the title when omitted.
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default, rename = "type")]
+    pub kind: ReleaseKind,
+    /// Release date as `YYYY-MM-DD`, `YYYY-MM` or `YYYY`.
+    #[serde(default, deserialize_with = "date_or_year")]
+    pub released: Option<String>,
+    /// Cover image path relative to the content directory.
+    pub cover: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tracks: Vec<Track>,
+    #[serde(default)]
+    pub credits: Vec<Credit>,
+    #[serde(default)]
+    pub links: Vec<ReleaseLink>,
+}
+
+impl Release {
+    /// Total running time in seconds, if every track has a duration.
+    pub fn total_seconds(&self) -> Option<u32> {
+        if self.tracks.is_empty() {
+            return None;
+        }
+        self.tracks
+            .iter()
+            .map(|track| track.duration.as_deref().and_then(parse_duration))
+            .sum()
+    }
+}
+
+/// Accepts `released: 2024` as well as quoted or ISO dates.
+fn date_or_year<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        Year(u32),
+        Text(String),
+    }
+
+    Ok(Option::<Raw>::deserialize(deserializer)?.map(|raw| match raw {
+        Raw::Year(year) => year.to_string(),
+        Raw::Text(text) => text,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct AlbumsFile {
+    #[serde(default)]
+    releases: Vec<Release>,
+}
+
+/// Turns a title into a URL segment: `Life Hits 4 The Heart` becomes
+/// `life-hits-4-the-heart`.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Parses `m:ss` or `h:mm:ss` into seconds.
+pub fn parse_duration(value: &str) -> Option<u32> {
+    let parts: Vec<u32> = value.trim().split(':').map(|part| part.parse().ok()).collect::<Option<_>>()?;
+    match parts.as_slice() {
+        [minutes, seconds] if *seconds < 60 => Some(minutes * 60 + seconds),
+        [hours, minutes, seconds] if *minutes < 60 && *seconds < 60 => Some(hours * 3600 + minutes * 60 + seconds),
+        _ => None,
+    }
+}
+
+/// Formats seconds as `m:ss`, or `h:mm:ss` from an hour up.
+pub fn format_duration(total: u32) -> String {
+    let (hours, minutes, seconds) = (total / 3600, total % 3600 / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June",
+    "July", "August", "September", "October", "November", "December",
+];
+
+/// Formats a release date for display: `2024-03-01` becomes `March 1, 2024`
+/// and `2024-03` becomes `March 2024`.
+pub fn display_date(date: &str) -> String {
+    let parts: Vec<&str> = date.split('-').collect();
+    let month = |part: &str| part.parse::<usize>().ok().and_then(|m| MONTHS.get(m.wrapping_sub(1)).copied());
+    match parts.as_slice() {
+        [year, m, day] => match (month(m), day.parse::<u32>()) {
+            (Some(name), Ok(day)) => format!("{} {}, {}", name, day, year),
+            _ => date.to_string(),
+        },
+        [year, m] => match month(m) {
+            Some(name) => format!("{} {}", name, year),
+            None => date.to_string(),
+        },
+        _ => date.to_string(),
+    }
+}
+
+fn is_valid_date(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('-').collect();
+    let numeric = |part: &str, len: usize| part.len() == len && part.chars().all(|c| c.is_ascii_digit());
+    match parts.as_slice() {
+        [year] => numeric(year, 4),
+        [year, month] => numeric(year, 4) && numeric(month, 2),
+        [year, month, day] => numeric(year, 4) && numeric(month, 2) && numeric(day, 2),
+        _ => false,
+    }
+}
+
+/// Reads `music/albums.yaml`, newest release first. A missing file means no
+/// releases; an invalid one is an error.
+pub fn read_releases(content_dir: &Path) -> Result<Vec<Release>, String> {
+    let path = content_dir.join("music").join("albums.yaml");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let file: AlbumsFile = serde_yaml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+
+    let mut releases = file.releases;
+    let mut slugs = HashSet::new();
+    for release in &mut releases {
+        if release.slug.is_empty() {
+            release.slug = slugify(&release.title);
+        }
+        if release.slug.is_empty() || release.slug != slugify(&release.slug) {
+            return Err(format!("{}: invalid slug {:?} for {:?}", path.display(), release.slug, release.title));
+        }
+        if !slugs.insert(release.slug.clone()) {
+            return Err(format!("{}: duplicate release slug {:?}", path.display(), release.slug));
+        }
+        if let Some(released) = &release.released
+            && !is_valid_date(released)
+        {
+            return Err(format!("{}: invalid release date {:?} for {:?}", path.display(), released, release.title));
+        }
+        for track in &release.tracks {
+            if let Some(duration) = &track.duration
+                && parse_duration(duration).is_none()
+            {
+                return Err(format!("{}: invalid duration {:?} for {:?}", path.display(), duration, track.title));
+            }
+        }
+    }
+
+    // Undated releases sort last; ISO dates compare correctly as strings
+    releases.sort_by(|a, b| b.released.cmp(&a.released));
+    Ok(releases)
+}
@@ -0,0 +1,239 @@
+//! Shows, gigs, shoots and conventions from `events/events.yaml`, and the
+//! `events.ics` calendar feed.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde::{Deserialize, Deserializer};
+use std::fs;
+use std::path::Path;
+
+use crate::discography::slugify;
+
+/// File name of the calendar feed, served and written at the site root.
+pub const ICS_NAME: &str = "events.ics";
+
+/// A start or end time: a whole day, or a local time on a day.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EventTime {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl EventTime {
+    /// Parses `YYYY-MM-DD`, `YYYY-MM-DD HH:MM` or `YYYY-MM-DDTHH:MM`.
+    pub fn parse(value: &str) -> Option<EventTime> {
+        let value = value.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            return Some(EventTime::Date(date));
+        }
+        ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .map(EventTime::DateTime)
+    }
+
+    pub fn date(self) -> NaiveDate {
+        match self {
+            EventTime::Date(date) => date,
+            EventTime::DateTime(datetime) => datetime.date(),
+        }
+    }
+
+    /// The first moment of this time.
+    fn starts_at(self) -> NaiveDateTime {
+        match self {
+            EventTime::Date(date) => date.and_time(NaiveTime::MIN),
+            EventTime::DateTime(datetime) => datetime,
+        }
+    }
+}
+
+fn event_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<EventTime, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    EventTime::parse(&value).ok_or_else(|| serde::de::Error::custom(format!("invalid date {:?}, expected YYYY-MM-DD or YYYY-MM-DD HH:MM", value)))
+}
+
+fn optional_event_time<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<EventTime>, D::Error> {
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(deserialize_with = "event_time")] EventTime);
+
+    Ok(Option::<Wrapper>::deserialize(deserializer)?.map(|Wrapper(time)| time))
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Event {
+    pub title: String,
+    /// Local time of the venue; a date alone means an all-day event.
+    #[serde(deserialize_with = "event_time")]
+    pub start: EventTime,
+    /// Last day or end time; an all-day end date is inclusive.
+    #[serde(default, deserialize_with = "optional_event_time")]
+    pub end: Option<EventTime>,
+    pub venue: Option<String>,
+    pub address: Option<String>,
+    /// Tickets or details page.
+    pub link: Option<String>,
+    /// Show, gig, shoot, convention, ...
+    pub category: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Event {
+    /// When the event is over. Events without an end stay upcoming until the
+    /// end of their start day.
+    pub fn ends_at(&self) -> NaiveDateTime {
+        match self.end.unwrap_or(EventTime::Date(self.start.date())) {
+            EventTime::Date(date) => (date + Duration::days(1)).and_time(NaiveTime::MIN),
+            EventTime::DateTime(datetime) => datetime,
+        }
+    }
+
+    pub fn is_past(&self, now: NaiveDateTime) -> bool {
+        self.ends_at() <= now
+    }
+
+    /// Stable identifier for calendar clients.
+    pub fn uid(&self) -> String {
+        format!("{}-{}@4ambertechel", self.start.date().format("%Y%m%d"), slugify(&self.title))
+    }
+
+    /// Human readable date and time, e.g. `Sat, May 2, 2026 · 7:30 PM` or
+    /// `Fri, May 1, 2026 – Sun, May 3, 2026`.
+    pub fn display_when(&self) -> String {
+        let day = |date: NaiveDate| date.format("%a, %B %-d, %Y").to_string();
+        let time = |datetime: NaiveDateTime| datetime.format("%-I:%M %p").to_string();
+
+        let start = match self.start {
+            EventTime::Date(date) => day(date),
+            EventTime::DateTime(datetime) => format!("{} · {}", day(datetime.date()), time(datetime)),
+        };
+        match self.end {
+            None => start,
+            Some(end) if end.date() == self.start.date() => match end {
+                EventTime::DateTime(datetime) => format!("{} – {}", start, time(datetime)),
+                EventTime::Date(_) => start,
+            },
+            Some(EventTime::Date(date)) => format!("{} – {}", start, day(date)),
+            Some(EventTime::DateTime(datetime)) => format!("{} – {} · {}", start, day(datetime.date()), time(datetime)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct EventsFile {
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+/// Reads `events/events.yaml`, soonest first. A missing file means no
+/// events; an invalid one is an error.
+pub fn read_events(content_dir: &Path) -> Result<Vec<Event>, String> {
+    let path = content_dir.join("events").join("events.yaml");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let file: EventsFile = serde_yaml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+
+    let mut events = file.events;
+    for event in &events {
+        if let Some(end) = event.end
+            && end.starts_at() < event.start.starts_at()
+        {
+            return Err(format!("{}: {:?} ends before it starts", path.display(), event.title));
+        }
+    }
+
+    events.sort_by_key(|event| event.start.starts_at());
+    Ok(events)
+}
+
+/// Splits events into upcoming ones, soonest first, and past ones, most
+/// recent first.
+pub fn split_events(events: &[Event], now: NaiveDateTime) -> (Vec<&Event>, Vec<&Event>) {
+    let (mut past, upcoming): (Vec<&Event>, Vec<&Event>) = events.iter().partition(|event| event.is_past(now));
+    past.reverse();
+    (upcoming, past)
+}
+
+/// Escapes a TEXT value (RFC 5545 section 3.3.11).
+fn ics_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line to 75 octets, continuing with a leading space.
+fn ics_fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+fn ics_time(name: &str, time: EventTime) -> String {
+    match time {
+        EventTime::Date(date) => format!("{};VALUE=DATE:{}", name, date.format("%Y%m%d")),
+        EventTime::DateTime(datetime) => format!("{}:{}", name, datetime.format("%Y%m%dT%H%M%S")),
+    }
+}
+
+/// Renders all events, past and upcoming, as an iCalendar feed. Times are
+/// floating, i.e. in the venue's local time.
+pub fn generate_ics(events: &[Event], stamp: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//4AmberTechel//Events//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Amber Techel Events".to_string(),
+    ];
+
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid()));
+        lines.push(format!("DTSTAMP:{}", stamp.format("%Y%m%dT%H%M%SZ")));
+        lines.push(ics_time("DTSTART", event.start));
+        match event.end {
+            // DTEND of an all-day event is exclusive
+            Some(EventTime::Date(date)) => lines.push(ics_time("DTEND", EventTime::Date(date + Duration::days(1)))),
+            Some(end) => lines.push(ics_time("DTEND", end)),
+            None => {}
+        }
+        lines.push(format!("SUMMARY:{}", ics_escape(&event.title)));
+        let location = [&event.venue, &event.address]
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !location.is_empty() {
+            lines.push(format!("LOCATION:{}", ics_escape(&location)));
+        }
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", ics_escape(description)));
+        }
+        if let Some(category) = &event.category {
+            lines.push(format!("CATEGORIES:{}", ics_escape(category)));
+        }
+        if let Some(link) = &event.link {
+            lines.push(format!("URL:{}", link));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|line| ics_fold(line)).collect()
+}
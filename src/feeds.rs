@@ -0,0 +1,275 @@
+//! Atom and JSON Feed documents announcing new posts, photos, videos and
+//! reviews.
+
+use chrono::{DateTime, NaiveTime, Utc};
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::content;
+use crate::media::{self, MediaSource, YoutubeTarget};
+use crate::posts;
+use crate::render::{html_escape, url_encode, url_encode_path};
+use crate::seo::{PERSON_NAME, SITE_NAME};
+
+/// File name of the Atom feed, served and written at the site root.
+pub const ATOM_NAME: &str = "feed.xml";
+/// File name of the JSON Feed, served and written at the site root.
+pub const JSON_FEED_NAME: &str = "feed.json";
+
+/// Most recent entries kept in each feed.
+pub const MAX_ENTRIES: usize = 50;
+
+/// Absolute URL prefixes feed and sitemap links are built from. Pages
+/// live under `site`; images and other content files under `assets`.
+#[derive(Clone, Debug)]
+pub struct SiteUrls {
+    /// Site root without a trailing slash, e.g.
+    /// `https://gorfonous.github.io/Website-test`.
+    pub site: String,
+    /// Prefix of files from the content directory: the site root for the
+    /// static site, `{site}/templates` for the server.
+    pub assets: String,
+    /// Folder the behind-the-scenes images are published in.
+    pub bts_images: String,
+}
+
+impl SiteUrls {
+    /// URLs for the static site published at `site_url`.
+    pub fn for_static_site(site_url: &str) -> Self {
+        let site = site_url.trim_end_matches('/').to_string();
+        SiteUrls {
+            assets: site.clone(),
+            bts_images: format!("{}/behind-the-scenes/images", site),
+            site,
+        }
+    }
+
+    /// URLs for the development server reachable at `origin`.
+    pub fn for_server(origin: &str) -> Self {
+        let site = origin.trim_end_matches('/').to_string();
+        SiteUrls {
+            assets: format!("{}/templates", site),
+            bts_images: format!("{}/templates/Behind%20the%20scenes/images", site),
+            site,
+        }
+    }
+}
+
+/// One item of the feeds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedEntry {
+    /// Stable, unique identifier; an absolute URL.
+    pub id: String,
+    pub title: String,
+    /// Page the entry links to.
+    pub url: String,
+    pub updated: DateTime<Utc>,
+    /// Plain text teaser.
+    pub summary: Option<String>,
+    pub content_html: String,
+    /// Preview image URL.
+    pub image: Option<String>,
+}
+
+/// One entry per image in `images_dir`, dated by the file's modification
+/// time.
+fn image_entries(images_dir: &Path, images_url: &str, page_url: &str, title: &str) -> Vec<FeedEntry> {
+    content::image_file_names(images_dir)
+        .into_iter()
+        .filter_map(|name| {
+            let updated = content::modified_time(&images_dir.join(&name))?;
+            let image = format!("{}/{}", images_url, url_encode(&name));
+            Some(FeedEntry {
+                id: image.clone(),
+                title: format!("New photo: {}", title),
+                url: page_url.to_string(),
+                updated,
+                summary: None,
+                content_html: format!(r#"<p><img src="{}" alt="{}"></p>"#, html_escape(&image), html_escape(title)),
+                image: Some(image),
+            })
+        })
+        .collect()
+}
+
+/// One entry per YouTube video or playlist on a media page. Items without a
+/// `date` are dated by the modification time of the media list.
+fn video_entries(content_dir: &Path, folder: &str, page_url: &str, urls: &SiteUrls) -> Result<Vec<FeedEntry>, String> {
+    let folder_dir = content_dir.join(folder);
+    let list_path = media::media_yaml_path(&folder_dir).or_else(|| Some(folder_dir.join("youtubeLinks.txt")).filter(|path| path.exists()));
+    let Some(list_modified) = list_path.as_deref().and_then(content::modified_time) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(media::read_media(content_dir, folder, &format!("{}/{}", urls.assets, folder))?
+        .into_iter()
+        .flat_map(|group| group.items)
+        .filter_map(|item| {
+            let MediaSource::Youtube(target) = &item.source else {
+                return None;
+            };
+            let watch_url = item.watch_url()?;
+            let updated = item
+                .date
+                .map(|date| date.and_time(NaiveTime::MIN).and_utc())
+                .unwrap_or(list_modified);
+            let title = item.title.clone().unwrap_or_else(|| "New video".to_string());
+            let mut content_html = format!(r#"<p><a href="{}">Watch {} on YouTube</a></p>"#, html_escape(&watch_url), html_escape(&title));
+            if let Some(description) = &item.description {
+                content_html = format!("<p>{}</p>{}", html_escape(description), content_html);
+            }
+            Some(FeedEntry {
+                id: watch_url,
+                title,
+                url: page_url.to_string(),
+                updated,
+                summary: item.description.clone(),
+                content_html,
+                image: match target {
+                    YoutubeTarget::Video(id) => Some(format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", id)),
+                    YoutubeTarget::Playlist(_) => None,
+                },
+            })
+        })
+        .collect())
+}
+
+/// One entry per testimonial. Testimonials without a `date` are dated by
+/// the modification time of `reviews/reviews.yaml`.
+fn testimonial_entries(content_dir: &Path, urls: &SiteUrls) -> Result<Vec<FeedEntry>, String> {
+    let file_modified = content::modified_time(&content_dir.join("reviews").join("reviews.yaml"));
+
+    Ok(content::read_testimonials(content_dir)?
+        .into_iter()
+        .filter_map(|testimonial| {
+            let updated = testimonial.date.map(|date| date.and_time(NaiveTime::MIN).and_utc()).or(file_modified)?;
+            Some(FeedEntry {
+                id: format!("{}/reviews/#{}", urls.site, testimonial.anchor()),
+                title: format!("Review from {}", testimonial.author),
+                url: format!("{}/reviews/", urls.site),
+                updated,
+                summary: Some(testimonial.quote.clone()),
+                content_html: format!(
+                    "<blockquote><p>{}</p></blockquote><p>— {}, {}</p>",
+                    html_escape(&testimonial.quote),
+                    html_escape(&testimonial.author),
+                    html_escape(&testimonial.title)
+                ),
+                image: testimonial.photo.map(|photo| format!("{}/{}", urls.assets, url_encode_path(&photo))),
+            })
+        })
+        .collect())
+}
+
+/// Gathers the feed entries from the content directory, newest first and
+/// capped at [`MAX_ENTRIES`]: published news posts, modeling and
+/// behind-the-scenes photos, YouTube videos from the music and acting pages
+/// and testimonials.
+pub fn collect_entries(content_dir: &Path, urls: &SiteUrls) -> Result<Vec<FeedEntry>, String> {
+    let mut entries = Vec::new();
+
+    for post in posts::read_posts(content_dir, &urls.assets, false)? {
+        let url = format!("{}/news/{}/", urls.site, post.slug);
+        entries.push(FeedEntry {
+            id: url.clone(),
+            title: post.title,
+            url,
+            updated: post.date.and_time(NaiveTime::MIN).and_utc(),
+            summary: Some(post.summary).filter(|summary| !summary.is_empty()),
+            content_html: post.body_html,
+            image: post.cover.map(|cover| format!("{}/{}", urls.assets, url_encode_path(&cover))),
+        });
+    }
+
+    for (name, category) in content::discover_modeling_categories(content_dir, "") {
+        let images_dir = content_dir.join("modeling").join(&name).join("images");
+        let images_url = format!("{}/modeling/{}/images", urls.assets, url_encode(&name));
+        entries.extend(image_entries(&images_dir, &images_url, &format!("{}/modeling/", urls.site), &category.title));
+    }
+    let bts_dir = content_dir.join("Behind the scenes").join("images");
+    entries.extend(image_entries(&bts_dir, &urls.bts_images, &format!("{}/behind-the-scenes/", urls.site), "Behind the Scenes"));
+
+    for folder in ["music", "acting"] {
+        entries.extend(video_entries(content_dir, folder, &format!("{}/{}/", urls.site, folder), urls)?);
+    }
+
+    entries.extend(testimonial_entries(content_dir, urls)?);
+
+    entries.sort_by(|a, b| b.updated.cmp(&a.updated).then_with(|| a.id.cmp(&b.id)));
+    let mut seen = HashSet::new();
+    entries.retain(|entry| seen.insert(entry.id.clone()));
+    entries.truncate(MAX_ENTRIES);
+    Ok(entries)
+}
+
+/// When the feed last changed: its newest entry, or the Unix epoch when it
+/// is empty.
+fn feed_updated(entries: &[FeedEntry]) -> DateTime<Utc> {
+    entries.iter().map(|entry| entry.updated).max().unwrap_or(DateTime::UNIX_EPOCH)
+}
+
+/// Renders the entries as an Atom 1.0 document.
+pub fn atom(entries: &[FeedEntry], urls: &SiteUrls) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    xml.push_str(&format!("  <title>{}</title>\n", SITE_NAME));
+    xml.push_str(&format!("  <id>{}/</id>\n", html_escape(&urls.site)));
+    xml.push_str(&format!("  <link rel=\"alternate\" type=\"text/html\" href=\"{}/\"/>\n", html_escape(&urls.site)));
+    xml.push_str(&format!("  <link rel=\"self\" type=\"application/atom+xml\" href=\"{}/{}\"/>\n", html_escape(&urls.site), ATOM_NAME));
+    xml.push_str(&format!("  <updated>{}</updated>\n", feed_updated(entries).to_rfc3339()));
+    xml.push_str(&format!("  <author><name>{}</name></author>\n", PERSON_NAME));
+
+    for entry in entries {
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <id>{}</id>\n", html_escape(&entry.id)));
+        xml.push_str(&format!("    <title>{}</title>\n", html_escape(&entry.title)));
+        xml.push_str(&format!("    <link rel=\"alternate\" type=\"text/html\" href=\"{}\"/>\n", html_escape(&entry.url)));
+        if let Some(image) = &entry.image {
+            xml.push_str(&format!("    <link rel=\"enclosure\" href=\"{}\"/>\n", html_escape(image)));
+        }
+        xml.push_str(&format!("    <updated>{}</updated>\n", entry.updated.to_rfc3339()));
+        if let Some(summary) = &entry.summary {
+            xml.push_str(&format!("    <summary>{}</summary>\n", html_escape(summary)));
+        }
+        xml.push_str(&format!("    <content type=\"html\">{}</content>\n", html_escape(&entry.content_html)));
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// Renders the entries as a JSON Feed 1.1 document.
+pub fn json_feed(entries: &[FeedEntry], urls: &SiteUrls) -> String {
+    let items: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            let mut item = json!({
+                "id": entry.id,
+                "url": entry.url,
+                "title": entry.title,
+                "content_html": entry.content_html,
+                "date_published": entry.updated.to_rfc3339(),
+            });
+            if let Some(summary) = &entry.summary {
+                item["summary"] = json!(summary);
+            }
+            if let Some(image) = &entry.image {
+                item["image"] = json!(image);
+            }
+            item
+        })
+        .collect();
+
+    let feed = json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": SITE_NAME,
+        "home_page_url": format!("{}/", urls.site),
+        "feed_url": format!("{}/{}", urls.site, JSON_FEED_NAME),
+        "authors": [{ "name": PERSON_NAME }],
+        "items": items,
+    });
+    serde_json::to_string_pretty(&feed).expect("feed is valid JSON")
+}
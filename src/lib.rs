@@ -0,0 +1,27 @@
+//! Shared code for the `server` and `generate-static` binaries.
+
+pub mod bundles;
+pub mod cli;
+pub mod content;
+pub mod credits;
+pub mod discography;
+pub mod events;
+pub mod feeds;
+pub mod lint;
+pub mod media;
+pub mod minify;
+pub mod moderation;
+pub mod orders;
+pub mod output;
+pub mod posts;
+pub mod preview;
+pub mod render;
+pub mod scaffold;
+pub mod seo;
+pub mod server;
+pub mod settings;
+pub mod shop;
+pub mod sitemap;
+pub mod static_site;
+pub mod theme;
+pub mod thumbnails;
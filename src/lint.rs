@@ -0,0 +1,298 @@
+//! Content checks for `generate-static --check`. Every content source is
+//! parsed the way the site reads it, and problems that the build would skip
+//! over quietly are reported with their file and line.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::content::{self, APPROVED_REVIEWS_NAME};
+use crate::credits;
+use crate::discography;
+use crate::events;
+use crate::media;
+use crate::posts;
+use crate::settings;
+use crate::shop;
+use crate::theme;
+
+/// Images larger than this, in bytes, are reported as too heavy for the web.
+pub const MAX_IMAGE_SIZE: u64 = 2 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// Content that still builds but probably not as intended.
+    Warning,
+    /// Content the site cannot use.
+    Error,
+}
+
+/// One problem found in the content directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub path: PathBuf,
+    /// 1-based line in `path`, when the problem has one.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn error(path: &Path, line: Option<usize>, message: impl Into<String>) -> Self {
+        Diagnostic { severity: Severity::Error, path: path.to_path_buf(), line, message: message.into() }
+    }
+
+    pub fn warning(path: &Path, line: Option<usize>, message: impl Into<String>) -> Self {
+        Diagnostic { severity: Severity::Warning, path: path.to_path_buf(), line, message: message.into() }
+    }
+}
+
+/// Formats as `path:line: error: message`, like a compiler.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}: {}", self.path.display(), line, severity, self.message),
+            None => write!(f, "{}: {}: {}", self.path.display(), severity, self.message),
+        }
+    }
+}
+
+/// Line of the first occurrence of `needle` at or after byte `*offset` of
+/// `content`. On a match `*offset` moves past it, so repeated values are
+/// found in turn.
+pub fn find_line(content: &str, needle: &str, offset: &mut usize) -> Option<usize> {
+    let start = *offset + content.get(*offset..)?.find(needle)?;
+    *offset = start + needle.len();
+    Some(content[..start].matches('\n').count() + 1)
+}
+
+/// Line number from a YAML parser message ending in `at line N column M`.
+fn error_line(message: &str) -> Option<usize> {
+    let (_, rest) = message.rsplit_once(" at line ")?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// Turns a reader error about `path` into a diagnostic, dropping the path
+/// the reader already put in front of the message.
+fn reader_error(path: &Path, message: &str) -> Diagnostic {
+    let shown = path.display().to_string();
+    let message = message
+        .strip_prefix(&format!("invalid {}: ", shown))
+        .or_else(|| message.strip_prefix(&format!("{}: ", shown)))
+        .unwrap_or(message);
+    Diagnostic::error(path, error_line(message), message)
+}
+
+/// Reports a file that a data file refers to but that does not exist.
+fn check_referenced_file(content_dir: &Path, source: &Path, file: &str, diagnostics: &mut Vec<Diagnostic>) {
+    if content_dir.join(file).is_file() {
+        return;
+    }
+    let line = fs::read_to_string(source).ok().and_then(|content| find_line(&content, file, &mut 0));
+    diagnostics.push(Diagnostic::error(source, line, format!("{:?} does not exist in the content directory", file)));
+}
+
+fn check_data_files(content_dir: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    let settings_path = content_dir.join("site.yaml");
+    match settings::read_site_settings(content_dir) {
+        Ok(settings) => {
+            let images = settings.share_image.iter().chain(settings.pages.values().filter_map(|page| page.image.as_ref()));
+            for image in images {
+                check_referenced_file(content_dir, &settings_path, image, diagnostics);
+            }
+        },
+        Err(e) => diagnostics.push(reader_error(&settings_path, &e)),
+    }
+
+    let reviews_dir = content_dir.join("reviews");
+    for path in [reviews_dir.join("reviews.yaml"), reviews_dir.join(APPROVED_REVIEWS_NAME)] {
+        match content::read_testimonials_file(&path) {
+            Ok(testimonials) => {
+                for photo in testimonials.iter().filter_map(|testimonial| testimonial.photo.as_ref()) {
+                    check_referenced_file(content_dir, &path, photo, diagnostics);
+                }
+            },
+            Err(e) => diagnostics.push(reader_error(&path, &e)),
+        }
+    }
+
+    if let Err(e) = theme::read_theme(content_dir).and_then(|theme| theme.stylesheets()) {
+        diagnostics.push(reader_error(&content_dir.join(theme::THEME_NAME), &e));
+    }
+    if let Err(e) = events::read_events(content_dir) {
+        diagnostics.push(reader_error(&content_dir.join("events").join("events.yaml"), &e));
+    }
+    if let Err(e) = credits::read_resume(content_dir) {
+        diagnostics.push(reader_error(&content_dir.join("acting").join("credits.yaml"), &e));
+    }
+
+    let catalog_path = content_dir.join(shop::CATALOG_PATH);
+    match shop::read_catalog(content_dir) {
+        Ok(catalog) => {
+            for image in catalog.products.iter().filter_map(|product| product.image.as_ref()) {
+                check_referenced_file(content_dir, &catalog_path, image, diagnostics);
+            }
+        },
+        Err(e) => diagnostics.push(reader_error(&catalog_path, &e)),
+    }
+
+    let albums_path = content_dir.join("music").join("albums.yaml");
+    match discography::read_releases(content_dir) {
+        Ok(releases) => {
+            for cover in releases.iter().filter_map(|release| release.cover.as_ref()) {
+                check_referenced_file(content_dir, &albums_path, cover, diagnostics);
+            }
+        },
+        Err(e) => diagnostics.push(reader_error(&albums_path, &e)),
+    }
+}
+
+/// Posts are checked one by one, so that every broken post is reported.
+fn check_posts(content_dir: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    let Ok(entries) = fs::read_dir(content_dir.join("posts")) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("md"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        match posts::read_post(&path, "") {
+            Ok(post) => {
+                if let Some(cover) = &post.cover {
+                    check_referenced_file(content_dir, &path, cover, diagnostics);
+                }
+            },
+            Err(e) => diagnostics.push(reader_error(&path, &e)),
+        }
+        if let Ok(markdown) = fs::read_to_string(&path) {
+            for (index, line) in markdown.lines().enumerate() {
+                if line.contains("![](") {
+                    diagnostics.push(Diagnostic::warning(&path, Some(index + 1), "image without alt text"));
+                }
+            }
+        }
+    }
+}
+
+/// Checks the modeling categories and their `Links.txt` files.
+fn check_modeling(content_dir: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    let Ok(entries) = fs::read_dir(content_dir.join("modeling")) else {
+        return;
+    };
+    let mut category_dirs: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()).collect();
+    category_dirs.sort();
+
+    for category_dir in category_dirs {
+        let images_dir = category_dir.join("images");
+        if !images_dir.is_dir() {
+            diagnostics.push(Diagnostic::warning(&category_dir, None, "category has no images/ folder and is not shown"));
+            continue;
+        }
+        let images = content::image_file_names(&images_dir);
+        if images.is_empty() {
+            diagnostics.push(Diagnostic::warning(&images_dir, None, "category has no PNG or JPEG images"));
+        }
+
+        let links_path = images_dir.join("Links.txt");
+        let Ok(links) = fs::read_to_string(&links_path) else {
+            continue;
+        };
+        let mut keys: Vec<(String, usize)> = Vec::new();
+        for (index, line) in links.lines().enumerate() {
+            let (line, number) = (line.trim(), index + 1);
+            if line.is_empty() {
+                continue;
+            }
+            let Some((name, url)) = line.split_once(',') else {
+                diagnostics.push(Diagnostic::error(&links_path, Some(number), "expected `image name, URL`"));
+                continue;
+            };
+            let (name, url) = (name.trim(), url.trim());
+            if url.is_empty() {
+                diagnostics.push(Diagnostic::error(&links_path, Some(number), format!("no URL for {:?}", name)));
+            }
+            // The gallery looks links up by file name without the extension
+            let matches_image = images
+                .iter()
+                .any(|image| Path::new(image).file_stem().and_then(|stem| stem.to_str()) == Some(name));
+            if images.iter().any(|image| image == name) {
+                diagnostics.push(Diagnostic::warning(&links_path, Some(number), format!("{:?} should be given without its extension", name)));
+            } else if !matches_image {
+                diagnostics.push(Diagnostic::warning(&links_path, Some(number), format!("{:?} matches no image in this category", name)));
+            }
+            if let Some((_, first)) = keys.iter().find(|(key, _)| key == name) {
+                diagnostics.push(Diagnostic::warning(&links_path, Some(number), format!("{:?} is already linked on line {}", name, first)));
+            } else {
+                keys.push((name.to_string(), number));
+            }
+        }
+    }
+}
+
+/// Reports `<img>` tags without an `alt` attribute in the page templates.
+fn check_templates(content_dir: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    let templates = content::PAGES
+        .iter()
+        .map(|(_, segments, _)| segments.iter().fold(content_dir.to_path_buf(), |path, segment| path.join(segment)))
+        .chain([content_dir.join("base.html")]);
+
+    for path in templates {
+        let Ok(html) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let mut offset = 0;
+        while let Some(line) = find_line(&html, "<img", &mut offset) {
+            let tag = html[offset..].split('>').next().unwrap_or_default();
+            if !tag.contains("alt=") {
+                diagnostics.push(Diagnostic::warning(&path, Some(line), "<img> without alt text"));
+            }
+        }
+    }
+}
+
+/// Reports images too large to serve as they are, in `dir` and below.
+fn check_image_sizes(dir: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    for name in content::image_file_names(dir) {
+        let path = dir.join(name);
+        if let Ok(metadata) = fs::metadata(&path)
+            && metadata.len() > MAX_IMAGE_SIZE
+        {
+            diagnostics.push(Diagnostic::warning(
+                &path,
+                None,
+                format!("image is {:.1} MB; keep images under {} MB", metadata.len() as f64 / 1_048_576.0, MAX_IMAGE_SIZE / 1_048_576),
+            ));
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut subdirs: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()).collect();
+    subdirs.sort();
+    for subdir in subdirs {
+        check_image_sizes(&subdir, diagnostics);
+    }
+}
+
+/// Runs every check over the content directory, in a stable order.
+pub fn check_content(content_dir: &Path) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    check_data_files(content_dir, &mut diagnostics);
+    check_posts(content_dir, &mut diagnostics);
+    for folder in ["music", "acting"] {
+        diagnostics.extend(media::check_media(content_dir, folder));
+    }
+    check_modeling(content_dir, &mut diagnostics);
+    check_templates(content_dir, &mut diagnostics);
+    check_image_sizes(content_dir, &mut diagnostics);
+    diagnostics
+}
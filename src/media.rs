@@ -0,0 +1,591 @@
+//! Media lists for the music and acting pages: a structured `media.yaml`
+//! per page folder (falling back to `videos.yaml`, its name before other
+//! players were supported, and the legacy `youtubeLinks.txt`), plus audio
+//! and video files placed directly in the folder.
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lint::{self, Diagnostic};
+use crate::render::url_encode;
+
+/// Structured media list file names, in order of preference.
+pub const MEDIA_LIST_FILES: &[&str] = &["media.yaml", "videos.yaml"];
+
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "m4a", "ogg", "wav"];
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "webm"];
+
+/// What a YouTube URL points at.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum YoutubeTarget {
+    Video(String),
+    Playlist(String),
+}
+
+/// A parsed YouTube URL: the video or playlist plus an optional start time.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct YoutubeRef {
+    pub target: YoutubeTarget,
+    pub start: Option<u32>,
+}
+
+/// Where a media item is played from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MediaSource {
+    Youtube(YoutubeTarget),
+    /// A Vimeo video ID.
+    Vimeo(String),
+    /// A SoundCloud track or playlist page URL.
+    SoundCloud(String),
+    /// A Spotify item: its kind (`track`, `album`, `playlist`, ...) and ID.
+    Spotify { kind: String, id: String },
+    /// A Bandcamp `EmbeddedPlayer` URL, or a release page that can only be
+    /// linked to since its player needs the numeric album ID.
+    Bandcamp(String),
+    /// URL of an audio file from the page folder.
+    Audio(String),
+    /// URL of a video file from the page folder.
+    Video(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MediaItem {
+    pub source: MediaSource,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub start: Option<u32>,
+    pub section: Option<String>,
+    pub featured: bool,
+    /// When the item was published, for the site feeds.
+    pub date: Option<NaiveDate>,
+}
+
+impl MediaItem {
+    fn untitled(source: MediaSource, start: Option<u32>) -> Self {
+        MediaItem {
+            source,
+            title: None,
+            description: None,
+            start,
+            section: None,
+            featured: false,
+            date: None,
+        }
+    }
+
+    /// The player URL for this item, honouring its start time. `None` for
+    /// local files and Bandcamp pages, which have no iframe player.
+    pub fn embed_url(&self) -> Option<String> {
+        match &self.source {
+            MediaSource::Youtube(target) => Some(youtube_embed_url(target, self.start, "www.youtube.com", false)),
+            MediaSource::Vimeo(id) => Some(match self.start {
+                Some(start) => format!("https://player.vimeo.com/video/{}?dnt=1#t={}s", id, start),
+                None => format!("https://player.vimeo.com/video/{}?dnt=1", id),
+            }),
+            MediaSource::SoundCloud(url) => Some(format!("https://w.soundcloud.com/player/?url={}", url_encode(url))),
+            MediaSource::Spotify { kind, id } => Some(format!("https://open.spotify.com/embed/{}/{}", kind, id)),
+            MediaSource::Bandcamp(url) if url.contains("/EmbeddedPlayer/") => Some(url.clone()),
+            MediaSource::Bandcamp(_) | MediaSource::Audio(_) | MediaSource::Video(_) => None,
+        }
+    }
+
+    /// The privacy-enhanced player URL a lite YouTube embed swaps in on click.
+    pub fn nocookie_embed_url(&self) -> Option<String> {
+        match &self.source {
+            MediaSource::Youtube(target) => Some(youtube_embed_url(target, self.start, "www.youtube-nocookie.com", true)),
+            _ => None,
+        }
+    }
+
+    /// The YouTube page for this item, used as the no-JavaScript link.
+    pub fn watch_url(&self) -> Option<String> {
+        match (&self.source, self.start) {
+            (MediaSource::Youtube(YoutubeTarget::Video(id)), Some(start)) => Some(format!("https://www.youtube.com/watch?v={}&t={}s", id, start)),
+            (MediaSource::Youtube(YoutubeTarget::Video(id)), None) => Some(format!("https://www.youtube.com/watch?v={}", id)),
+            (MediaSource::Youtube(YoutubeTarget::Playlist(id)), _) => Some(format!("https://www.youtube.com/playlist?list={}", id)),
+            _ => None,
+        }
+    }
+
+    pub fn has_details(&self) -> bool {
+        self.title.is_some() || self.description.is_some() || self.featured
+    }
+}
+
+fn youtube_embed_url(target: &YoutubeTarget, start: Option<u32>, host: &str, autoplay: bool) -> String {
+    let mut params = Vec::new();
+    let path = match target {
+        YoutubeTarget::Video(id) => {
+            if let Some(start) = start {
+                params.push(format!("start={}", start));
+            }
+            id.clone()
+        }
+        YoutubeTarget::Playlist(id) => {
+            params.push(format!("list={}", id));
+            "videoseries".to_string()
+        }
+    };
+    if autoplay {
+        params.push("autoplay=1".to_string());
+    }
+
+    if params.is_empty() {
+        format!("https://{}/embed/{}", host, path)
+    } else {
+        format!("https://{}/embed/{}?{}", host, path, params.join("&"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawStart {
+    Seconds(u32),
+    Text(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct RawItem {
+    url: Option<String>,
+    /// An audio or video file in the page folder.
+    file: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    start: Option<RawStart>,
+    section: Option<String>,
+    #[serde(default)]
+    featured: bool,
+    /// Publication date as `YYYY-MM-DD`.
+    date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MediaFile {
+    #[serde(default)]
+    sections: Vec<String>,
+    /// `videos` in `videos.yaml`.
+    #[serde(default, alias = "videos")]
+    media: Vec<RawItem>,
+}
+
+/// The structured media list of a page folder, if it has one.
+pub fn media_yaml_path(folder_dir: &Path) -> Option<PathBuf> {
+    MEDIA_LIST_FILES.iter().map(|name| folder_dir.join(name)).find(|path| path.exists())
+}
+
+/// Whether `id` looks like a YouTube video ID.
+pub fn is_video_id(id: &str) -> bool {
+    id.len() == 11 && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn is_playlist_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Parses a timestamp such as `90`, `90s`, `1m30s` or `1h2m3s` into seconds.
+/// Timestamps too long to count in seconds are invalid.
+pub fn parse_timestamp(value: &str) -> Option<u32> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(seconds) = value.parse::<u32>() {
+        return Some(seconds);
+    }
+
+    let mut total = 0u32;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let amount: u32 = number.parse().ok()?;
+        number.clear();
+        let seconds = match c {
+            'h' => amount.checked_mul(3600)?,
+            'm' => amount.checked_mul(60)?,
+            's' => amount,
+            _ => return None,
+        };
+        total = total.checked_add(seconds)?;
+    }
+
+    if number.is_empty() { Some(total) } else { None }
+}
+
+/// Splits a URL into its host (without `www.`), path and query/fragment.
+fn split_url(url: &str) -> (&str, &str, &str) {
+    let url = url.trim();
+    let without_scheme = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+
+    let (host, rest) = without_scheme.split_once('/').unwrap_or((without_scheme, ""));
+    let host = host.strip_prefix("www.").unwrap_or(host);
+    match rest.split_once(['?', '#']) {
+        Some((path, query)) => (host, path, query),
+        None => (host, rest, ""),
+    }
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split(['&', '#', '?'])
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Parses the YouTube URL shapes people paste: `youtu.be/ID`, `watch?v=ID`,
+/// `/shorts/ID`, `/embed/ID`, `/live/ID`, `m.` and `music.` hosts,
+/// `youtube-nocookie.com` and `playlist?list=ID`. Timestamps (`t=`,
+/// `start=`) are kept.
+pub fn parse_youtube_url(url: &str) -> Option<YoutubeRef> {
+    let (host, path, query) = split_url(url);
+    let param = |name: &str| query_param(query, name);
+    let start = param("t").or_else(|| param("start")).and_then(parse_timestamp);
+
+    let video = |id: &str| {
+        is_video_id(id).then(|| YoutubeRef {
+            target: YoutubeTarget::Video(id.to_string()),
+            start,
+        })
+    };
+
+    match host {
+        "youtu.be" => video(path.trim_end_matches('/')),
+        "youtube.com" | "m.youtube.com" | "music.youtube.com" | "youtube-nocookie.com" => {
+            let mut segments = path.split('/').filter(|s| !s.is_empty());
+            match (segments.next(), segments.next()) {
+                (Some("watch"), None) => param("v").and_then(video),
+                (Some("playlist"), None) => param("list").filter(|id| is_playlist_id(id)).map(|id| YoutubeRef {
+                    target: YoutubeTarget::Playlist(id.to_string()),
+                    start: None,
+                }),
+                (Some("embed"), Some("videoseries")) => param("list").filter(|id| is_playlist_id(id)).map(|id| YoutubeRef {
+                    target: YoutubeTarget::Playlist(id.to_string()),
+                    start: None,
+                }),
+                (Some("shorts" | "embed" | "live" | "v"), Some(id)) => video(id),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Recognizes the provider of a media URL: YouTube (see
+/// [`parse_youtube_url`]), Vimeo, SoundCloud, Spotify and Bandcamp.
+/// Returns the source and any start time found in the URL.
+pub fn parse_media_url(url: &str) -> Option<(MediaSource, Option<u32>)> {
+    if let Some(youtube) = parse_youtube_url(url) {
+        return Some((MediaSource::Youtube(youtube.target), youtube.start));
+    }
+
+    let (host, path, query) = split_url(url);
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    let first = segments.next();
+    let second = segments.next();
+    let is_numeric = |id: &str| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit());
+
+    match host {
+        "vimeo.com" | "player.vimeo.com" => {
+            let id = match (first, second) {
+                (Some("video"), Some(id)) => id,
+                (Some(id), _) => id,
+                _ => return None,
+            };
+            let start = query_param(query, "t").and_then(parse_timestamp);
+            is_numeric(id).then(|| (MediaSource::Vimeo(id.to_string()), start))
+        }
+        "soundcloud.com" | "m.soundcloud.com" => {
+            first?;
+            let page = format!("https://soundcloud.com/{}", path.trim_end_matches('/'));
+            Some((MediaSource::SoundCloud(page), None))
+        }
+        "open.spotify.com" => {
+            // Locale-prefixed links look like /intl-de/track/ID
+            let (kind, id) = match (first, second) {
+                (Some(prefix), Some(kind)) if prefix.starts_with("intl-") => (kind, segments.next()?),
+                (Some("embed"), Some(kind)) => (kind, segments.next()?),
+                (Some(kind), Some(id)) => (kind, id),
+                _ => return None,
+            };
+            let known = matches!(kind, "track" | "album" | "playlist" | "artist" | "episode" | "show");
+            let valid_id = !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric());
+            (known && valid_id).then(|| {
+                (
+                    MediaSource::Spotify {
+                        kind: kind.to_string(),
+                        id: id.to_string(),
+                    },
+                    None,
+                )
+            })
+        }
+        _ if host == "bandcamp.com" || host.ends_with(".bandcamp.com") => {
+            first?;
+            Some((MediaSource::Bandcamp(format!("https://{}/{}", host, path.trim_end_matches('/'))), None))
+        }
+        _ => None,
+    }
+}
+
+fn media_extension(file_name: &str) -> Option<String> {
+    Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+/// Whether `file_name` is an audio or video file shown with an HTML5 player.
+pub fn is_local_media(file_name: &str) -> bool {
+    media_extension(file_name).is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.as_str()) || VIDEO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// Audio and video files placed directly in a page folder, sorted.
+pub fn local_media_files(folder_dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(folder_dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| is_local_media(name))
+        .collect();
+    files.sort();
+    files
+}
+
+fn local_source(file_name: &str, url_base: &str) -> MediaSource {
+    let url = format!("{}/{}", url_base, url_encode(file_name));
+    match media_extension(file_name) {
+        Some(ext) if VIDEO_EXTENSIONS.contains(&ext.as_str()) => MediaSource::Video(url),
+        _ => MediaSource::Audio(url),
+    }
+}
+
+/// A readable title from a file name: `demo_take-2.mp3` becomes `demo take 2`.
+fn title_from_file_name(file_name: &str) -> String {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(file_name);
+    stem.replace(['_', '-'], " ")
+}
+
+fn read_media_yaml(path: &Path, folder_dir: &Path, url_base: &str) -> Result<(Vec<String>, Vec<MediaItem>), String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let file: MediaFile = serde_yaml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+
+    let folder = folder_dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    let mut items = Vec::new();
+    for (index, raw) in file.media.into_iter().enumerate() {
+        let (source, url_start) = match (&raw.url, &raw.file) {
+            (Some(url), _) => parse_media_url(url).ok_or_else(|| format!("{}: unsupported media URL {:?}", path.display(), url))?,
+            (None, Some(file_name)) if !is_local_media(file_name) => {
+                return Err(format!("{}: {:?} is not an audio or video file", path.display(), file_name));
+            }
+            (None, Some(file_name)) if !folder_dir.join(file_name).is_file() => {
+                return Err(format!("{}: {:?} does not exist in {}/", path.display(), file_name, folder));
+            }
+            (None, Some(file_name)) => (local_source(file_name, url_base), None),
+            (None, None) => return Err(format!("{}: media item {} has neither url nor file", path.display(), index + 1)),
+        };
+        let start = match raw.start {
+            Some(RawStart::Seconds(seconds)) => Some(seconds),
+            Some(RawStart::Text(text)) => parse_timestamp(&text),
+            None => url_start,
+        };
+        items.push(MediaItem {
+            source,
+            title: raw.title,
+            description: raw.description,
+            start,
+            section: raw.section,
+            featured: raw.featured,
+            date: raw.date.and_then(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()),
+        });
+    }
+
+    Ok((file.sections, items))
+}
+
+fn read_media_links(path: &Path) -> Vec<MediaItem> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(parse_media_url)
+        .map(|(source, start)| MediaItem::untitled(source, start))
+        .collect()
+}
+
+/// A run of media items shown under one heading (`None` for unsectioned
+/// items).
+#[derive(Debug)]
+pub struct MediaGroup {
+    pub section: Option<String>,
+    pub items: Vec<MediaItem>,
+}
+
+/// Reads the media for a page folder, grouped for display. Local files are
+/// linked under `url_base` (`/templates/music` for the server, `.` for the
+/// static site).
+///
+/// Items come from `media.yaml` (or `videos.yaml`), or `youtubeLinks.txt`
+/// (one URL per line) without it. Audio and video files in the folder that
+/// the YAML does not mention are added at the end, titled from their file
+/// names. A YAML list that cannot be read or parsed, or has an item no
+/// player can show, is an error.
+///
+/// Unsectioned items come first, then sections in the order given by the
+/// file's `sections` list and finally in order of first appearance.
+/// Featured items lead their group; otherwise file order is kept.
+pub fn read_media(content_dir: &Path, folder: &str, url_base: &str) -> Result<Vec<MediaGroup>, String> {
+    let folder_dir = content_dir.join(folder);
+
+    let (section_order, mut items) = match media_yaml_path(&folder_dir) {
+        Some(yaml_path) => read_media_yaml(&yaml_path, &folder_dir, url_base)?,
+        None => (Vec::new(), read_media_links(&folder_dir.join("youtubeLinks.txt"))),
+    };
+
+    for file_name in local_media_files(&folder_dir) {
+        let source = local_source(&file_name, url_base);
+        if items.iter().any(|item| item.source == source) {
+            continue;
+        }
+        let mut item = MediaItem::untitled(source, None);
+        item.title = Some(title_from_file_name(&file_name));
+        items.push(item);
+    }
+
+    Ok(group_media(&section_order, items))
+}
+
+/// Checks the media list of a page folder for `generate-static --check`:
+/// unreadable YAML, URLs no player understands, missing files, bad start
+/// times and dates, and YouTube videos or playlists listed twice.
+pub fn check_media(content_dir: &Path, folder: &str) -> Vec<Diagnostic> {
+    let folder_dir = content_dir.join(folder);
+    let links_path = folder_dir.join("youtubeLinks.txt");
+    let mut diagnostics = Vec::new();
+    // Sources in file order, with their lines, to spot duplicates
+    let mut sources: Vec<(MediaSource, Option<usize>)> = Vec::new();
+
+    let list_path = if let Some(yaml_path) = media_yaml_path(&folder_dir) {
+        let yaml_name = yaml_path.file_name().and_then(|name| name.to_str()).unwrap_or_default().to_string();
+        let content = match fs::read_to_string(&yaml_path) {
+            Ok(content) => content,
+            Err(e) => return vec![Diagnostic::error(&yaml_path, None, format!("failed to read: {}", e))],
+        };
+        let file: MediaFile = match serde_yaml::from_str(&content) {
+            Ok(file) => file,
+            Err(e) => return vec![Diagnostic::error(&yaml_path, e.location().map(|location| location.line()), e.to_string())],
+        };
+
+        let mut offset = 0;
+        for (index, raw) in file.media.iter().enumerate() {
+            let line = raw.url.as_deref().or(raw.file.as_deref()).and_then(|value| lint::find_line(&content, value, &mut offset));
+            match (&raw.url, &raw.file) {
+                (Some(url), _) => match parse_media_url(url) {
+                    Some((source, _)) => sources.push((source, line)),
+                    None => diagnostics.push(Diagnostic::error(&yaml_path, line, format!("unsupported media URL {:?}", url))),
+                },
+                (None, Some(file_name)) if !is_local_media(file_name) => {
+                    diagnostics.push(Diagnostic::error(&yaml_path, line, format!("{:?} is not an audio or video file", file_name)));
+                }
+                (None, Some(file_name)) if !folder_dir.join(file_name).is_file() => {
+                    diagnostics.push(Diagnostic::error(&yaml_path, line, format!("{:?} does not exist in {}/", file_name, folder)));
+                }
+                (None, Some(_)) => {},
+                (None, None) => diagnostics.push(Diagnostic::error(&yaml_path, None, format!("media item {} has neither url nor file", index + 1))),
+            }
+            if let Some(RawStart::Text(text)) = &raw.start
+                && parse_timestamp(text).is_none()
+            {
+                diagnostics.push(Diagnostic::error(&yaml_path, line, format!("invalid start time {:?}", text)));
+            }
+            if let Some(date) = &raw.date
+                && NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").is_err()
+            {
+                diagnostics.push(Diagnostic::error(&yaml_path, line, format!("invalid date {:?}, expected YYYY-MM-DD", date)));
+            }
+        }
+        if yaml_name != MEDIA_LIST_FILES[0] {
+            diagnostics.push(Diagnostic::warning(&yaml_path, None, format!("rename to {}", MEDIA_LIST_FILES[0])));
+        }
+        if links_path.exists() {
+            diagnostics.push(Diagnostic::warning(&links_path, None, format!("ignored because {} exists", yaml_name)));
+        }
+        yaml_path
+    } else {
+        let Ok(content) = fs::read_to_string(&links_path) else {
+            return diagnostics;
+        };
+        for (index, line) in content.lines().enumerate() {
+            let url = line.trim();
+            if url.is_empty() {
+                continue;
+            }
+            match parse_media_url(url) {
+                Some((source, _)) => sources.push((source, Some(index + 1))),
+                None => diagnostics.push(Diagnostic::error(&links_path, Some(index + 1), format!("unsupported media URL {:?}", url))),
+            }
+        }
+        links_path
+    };
+
+    for (index, (source, line)) in sources.iter().enumerate() {
+        let MediaSource::Youtube(target) = source else {
+            continue;
+        };
+        if let Some((_, first_line)) = sources[..index].iter().find(|(other, _)| other == source) {
+            let what = match target {
+                YoutubeTarget::Video(id) => format!("YouTube video {}", id),
+                YoutubeTarget::Playlist(id) => format!("YouTube playlist {}", id),
+            };
+            let first = first_line.map(|first_line| format!(", first listed on line {}", first_line)).unwrap_or_default();
+            diagnostics.push(Diagnostic::warning(&list_path, *line, format!("duplicate {}{}", what, first)));
+        }
+    }
+
+    diagnostics
+}
+
+fn group_media(section_order: &[String], items: Vec<MediaItem>) -> Vec<MediaGroup> {
+    let mut groups: Vec<MediaGroup> = Vec::new();
+    for item in items {
+        match groups.iter_mut().find(|group| group.section == item.section) {
+            Some(group) => group.items.push(item),
+            None => groups.push(MediaGroup {
+                section: item.section.clone(),
+                items: vec![item],
+            }),
+        }
+    }
+
+    let rank = |section: &Option<String>| match section {
+        None => 0,
+        Some(name) => match section_order.iter().position(|s| s == name) {
+            Some(index) => 1 + index,
+            None => 1 + section_order.len(),
+        },
+    };
+    groups.sort_by_key(|group| rank(&group.section));
+    for group in &mut groups {
+        group.items.sort_by_key(|item| !item.featured);
+    }
+
+    groups
+}
@@ -0,0 +1,286 @@
+//! Size reduction of the static output for `generate-static build
+//! --minify`.
+//!
+//! Each pass only drops what cannot change how a page renders or behaves:
+//! comments, indentation and runs of whitespace. Strings, template literals,
+//! regular expressions and the insides of `<pre>` and `<textarea>` are
+//! copied as they are, and line breaks in scripts are kept so that
+//! automatic semicolon insertion works as before.
+
+/// Elements whose contents are not HTML.
+const RAW_TEXT: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Words after which a `/` starts a regular expression rather than a
+/// division.
+const REGEX_KEYWORDS: &[&str] = &["return", "typeof", "case", "do", "else", "in", "of", "new", "delete", "void", "throw", "yield", "await"];
+
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$' || !c.is_ascii()
+}
+
+/// Copies the quoted string starting at `chars[start]`, escapes and all,
+/// returning the index after it.
+fn copy_quoted(chars: &[char], start: usize, out: &mut String) -> usize {
+    let quote = chars[start];
+    out.push(quote);
+    let mut i = start + 1;
+    while i < chars.len() {
+        let c = chars[i];
+        out.push(c);
+        i += 1;
+        if c == '\\' && i < chars.len() {
+            out.push(chars[i]);
+            i += 1;
+        } else if c == quote {
+            break;
+        }
+    }
+    i
+}
+
+/// Index after the comment starting at `chars[start]`, and whether it
+/// spanned lines.
+fn skip_comment(chars: &[char], start: usize) -> (usize, bool) {
+    if chars[start + 1] == '/' {
+        let end = (start..chars.len()).find(|&i| chars[i] == '\n').unwrap_or(chars.len());
+        return (end, false);
+    }
+    let end = (start + 2..chars.len().saturating_sub(1)).find(|&i| chars[i] == '*' && chars[i + 1] == '/').map_or(chars.len(), |i| i + 2);
+    (end, chars[start..end].contains(&'\n'))
+}
+
+/// Removes comments and whitespace from a stylesheet. Spaces are dropped
+/// around `{`, `}`, `;`, `,` and `>`, and after `:`; a space before `:`
+/// is kept, as `a :hover` and `a:hover` select different elements.
+pub fn css(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    let mut space = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i = skip_comment(&chars, i).0;
+            space = true;
+            continue;
+        }
+        if c.is_whitespace() {
+            space = true;
+            i += 1;
+            continue;
+        }
+        if space && out.chars().next_back().is_some_and(|last| !"{};,>:".contains(last)) && !"{};,>".contains(c) {
+            out.push(' ');
+        }
+        space = false;
+        if c == '"' || c == '\'' {
+            i = copy_quoted(&chars, i, &mut out);
+            continue;
+        }
+        if c == '}' && out.ends_with(';') {
+            out.pop();
+        }
+        out.push(c);
+        i += 1;
+    }
+    out
+}
+
+/// Whether a `/` after `out` starts a regular expression.
+fn regex_allowed(out: &str) -> bool {
+    let trimmed = out.trim_end();
+    match trimmed.chars().next_back() {
+        None => true,
+        Some(last) if is_word(last) => {
+            let word: String = trimmed.chars().rev().take_while(|&c| is_word(c)).collect::<Vec<_>>().into_iter().rev().collect();
+            REGEX_KEYWORDS.contains(&word.as_str())
+        },
+        Some(last) => "(,=:[!&|?{};+-*%<>~^".contains(last),
+    }
+}
+
+/// Copies the regular expression literal starting at `chars[start]`,
+/// returning the index after its closing `/`.
+fn copy_regex(chars: &[char], start: usize, out: &mut String) -> usize {
+    out.push('/');
+    let mut i = start + 1;
+    let mut in_class = false;
+    while i < chars.len() {
+        let c = chars[i];
+        out.push(c);
+        i += 1;
+        match c {
+            '\\' if i < chars.len() => {
+                out.push(chars[i]);
+                i += 1;
+            },
+            '[' => in_class = true,
+            ']' => in_class = false,
+            '/' if !in_class => break,
+            '\n' => break,
+            _ => {},
+        }
+    }
+    i
+}
+
+/// Copies the template literal starting at `chars[start]`, with its
+/// `${}` expressions, returning the index after it.
+fn copy_template(chars: &[char], start: usize, out: &mut String) -> usize {
+    out.push('`');
+    let mut i = start + 1;
+    let mut depth = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        out.push(c);
+        i += 1;
+        match c {
+            '\\' if i < chars.len() => {
+                out.push(chars[i]);
+                i += 1;
+            },
+            '$' if chars.get(i) == Some(&'{') => {
+                out.push('{');
+                i += 1;
+                depth += 1;
+            },
+            '{' if depth > 0 => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            '`' if depth == 0 => break,
+            _ => {},
+        }
+    }
+    i
+}
+
+/// Removes comments and whitespace from a script. A run of whitespace that
+/// spans lines becomes one line break; others become a space only where
+/// the tokens on either side would otherwise run together.
+pub fn js(source: &str) -> String {
+    let chars: Vec<char> = source.chars().collect();
+    let mut out = String::with_capacity(source.len());
+    // The whitespace seen since the last token: none, a space or a line break
+    let mut gap: Option<char> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '/' && matches!(chars.get(i + 1), Some('/') | Some('*')) {
+            let (end, multiline) = skip_comment(&chars, i);
+            gap = if multiline || gap == Some('\n') { Some('\n') } else { gap.or(Some(' ')) };
+            i = end;
+            continue;
+        }
+        if c.is_whitespace() {
+            gap = if c == '\n' || gap == Some('\n') { Some('\n') } else { Some(' ') };
+            i += 1;
+            continue;
+        }
+
+        if let (Some(whitespace), Some(last)) = (gap, out.chars().next_back()) {
+            if whitespace == '\n' {
+                out.push('\n');
+            } else if (is_word(last) && is_word(c)) || (last == c && "+-".contains(c)) || (last == '/' && c == '/') {
+                out.push(' ');
+            }
+        }
+        gap = None;
+
+        i = match c {
+            '"' | '\'' => copy_quoted(&chars, i, &mut out),
+            '`' => copy_template(&chars, i, &mut out),
+            '/' if regex_allowed(&out) => copy_regex(&chars, i, &mut out),
+            _ => {
+                out.push(c);
+                i + 1
+            },
+        };
+    }
+    out
+}
+
+/// Collapses each run of whitespace in text to one line break or space.
+fn push_text(out: &mut String, text: &str) {
+    let mut gap: Option<char> = None;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            gap = if c == '\n' || gap == Some('\n') { Some('\n') } else { Some(' ') };
+            continue;
+        }
+        if let Some(whitespace) = gap.take() {
+            out.push(whitespace);
+        }
+        out.push(c);
+    }
+    if let Some(whitespace) = gap {
+        out.push(whitespace);
+    }
+}
+
+/// Index of the `>` that ends the tag starting at `html[0]`, skipping any
+/// inside quoted attribute values.
+fn tag_end(html: &str) -> usize {
+    let mut quote = None;
+    for (index, c) in html.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return index + 1,
+            _ => {},
+        }
+    }
+    html.len()
+}
+
+/// Whether a `<script>` tag holds JavaScript rather than data.
+fn is_javascript(tag: &str) -> bool {
+    let lower = tag.to_ascii_lowercase();
+    match lower.split_once("type=") {
+        Some((_, rest)) => {
+            let kind = rest.trim_start_matches(['"', '\'']).split(['"', '\'', ' ', '>']).next().unwrap_or_default();
+            matches!(kind, "text/javascript" | "module" | "application/javascript")
+        },
+        None => true,
+    }
+}
+
+/// Minifies a page: comments are dropped, runs of whitespace collapsed and
+/// inline `<style>` and `<script>` contents minified. Tags themselves and
+/// the contents of `<pre>` and `<textarea>` are left as they are.
+pub fn html(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find('<') {
+        push_text(&mut out, &rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |end| end + 3);
+            // Conditional comments are markup for old browsers
+            if rest.starts_with("<!--[if") {
+                out.push_str(&rest[..end]);
+            }
+            rest = &rest[end..];
+            continue;
+        }
+
+        let end = tag_end(rest);
+        let tag = &rest[..end];
+        out.push_str(tag);
+        rest = &rest[end..];
+
+        let name: String = tag[1..].chars().take_while(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_lowercase();
+        if !RAW_TEXT.contains(&name.as_str()) || tag.ends_with("/>") {
+            continue;
+        }
+        let close = rest.to_ascii_lowercase().find(&format!("</{}", name)).unwrap_or(rest.len());
+        let contents = &rest[..close];
+        match name.as_str() {
+            "style" => out.push_str(&css(contents)),
+            "script" if is_javascript(tag) => out.push_str(&js(contents)),
+            _ => out.push_str(contents),
+        }
+        rest = &rest[close..];
+    }
+    push_text(&mut out, rest);
+    out
+}
@@ -0,0 +1,45 @@
+use std::path::Path;
+use std::process::ExitCode;
+use website_test::content::DEFAULT_CONTENT_DIR;
+use website_test::moderation;
+
+const USAGE: &str = "usage: moderate-reviews list | approve <id> | reject <id>";
+
+fn run(args: &[String]) -> Result<(), String> {
+    let pending = Path::new(moderation::DEFAULT_PENDING_FILE);
+    match args {
+        [command] if command == "list" => {
+            let reviews = moderation::read_pending(pending)?;
+            if reviews.is_empty() {
+                println!("No reviews are waiting for moderation.");
+            }
+            for review in reviews {
+                let rating = review.rating.map(|rating| format!(" ({}/5)", rating)).unwrap_or_default();
+                println!("{}  {}, {}{}\n    {}", review.id, review.author, review.title, rating, review.quote);
+            }
+            Ok(())
+        },
+        [command, id] if command == "approve" => {
+            let testimonial = moderation::approve(pending, Path::new(DEFAULT_CONTENT_DIR), id)?;
+            println!("Approved the review from {}", testimonial.author);
+            Ok(())
+        },
+        [command, id] if command == "reject" => {
+            let review = moderation::reject(pending, id)?;
+            println!("Rejected the review from {}", review.author);
+            Ok(())
+        },
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
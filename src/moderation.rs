@@ -0,0 +1,166 @@
+//! Reviews left by visitors on `/reviews/`. They wait in a pending file,
+//! outside the published content, until they are approved into
+//! `reviews/approved.yaml` or rejected.
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::content::{self, Testimonial, TestimonialsFile, APPROVED_REVIEWS_NAME};
+use crate::discography::slugify;
+
+/// Pending reviews file used unless told otherwise, next to `messages.txt`.
+pub const DEFAULT_PENDING_FILE: &str = "pending-reviews.yaml";
+
+/// Longest name or role a visitor may enter, in characters.
+pub const MAX_NAME_LENGTH: usize = 100;
+/// Longest review a visitor may enter, in characters.
+pub const MAX_REVIEW_LENGTH: usize = 2000;
+
+/// Serializes changes to the pending and approved files within a process.
+static FILES: Mutex<()> = Mutex::new(());
+
+/// A review waiting for moderation.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct PendingReview {
+    /// Identifier used to approve or reject the review.
+    pub id: String,
+    /// When it was submitted, in RFC 3339.
+    pub submitted: String,
+    pub author: String,
+    pub title: String,
+    pub quote: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating: Option<u8>,
+}
+
+impl PendingReview {
+    /// Checks a visitor's submission, returning a message for the visitor
+    /// when it is incomplete or too long. An empty role becomes `Visitor`.
+    pub fn new(author: &str, title: &str, quote: &str, rating: Option<u8>, submitted: DateTime<Utc>) -> Result<Self, String> {
+        let (author, title, quote) = (author.trim(), title.trim(), quote.trim());
+        if author.is_empty() || quote.is_empty() {
+            return Err("Please enter your name and your review.".to_string());
+        }
+        if author.chars().count() > MAX_NAME_LENGTH || title.chars().count() > MAX_NAME_LENGTH {
+            return Err(format!("Names and roles can be at most {} characters long.", MAX_NAME_LENGTH));
+        }
+        if quote.chars().count() > MAX_REVIEW_LENGTH {
+            return Err(format!("Reviews can be at most {} characters long.", MAX_REVIEW_LENGTH));
+        }
+        if let Some(rating) = rating
+            && !(1..=5).contains(&rating)
+        {
+            return Err("Ratings go from 1 to 5 stars.".to_string());
+        }
+
+        Ok(PendingReview {
+            id: format!("{}-{}", submitted.format("%Y%m%d%H%M%S"), slugify(author)),
+            submitted: submitted.to_rfc3339_opts(SecondsFormat::Secs, true),
+            author: author.to_string(),
+            title: if title.is_empty() { "Visitor".to_string() } else { title.to_string() },
+            quote: quote.to_string(),
+            rating,
+        })
+    }
+
+    /// Day the review was submitted.
+    pub fn date(&self) -> Option<NaiveDate> {
+        DateTime::parse_from_rfc3339(&self.submitted).ok().map(|submitted| submitted.date_naive())
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct PendingFile {
+    #[serde(default)]
+    reviews: Vec<PendingReview>,
+}
+
+/// Writes `content` next to `path` first, so readers never see half a file.
+pub(crate) fn write_atomically(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| format!("failed to create {}: {}", parent.display(), e))?;
+    }
+    let staging = path.with_extension("yaml.tmp");
+    fs::write(&staging, content).map_err(|e| format!("failed to write {}: {}", staging.display(), e))?;
+    fs::rename(&staging, path).map_err(|e| format!("failed to replace {}: {}", path.display(), e))
+}
+
+/// Reads the pending reviews, oldest first. A missing file means none.
+pub fn read_pending(path: &Path) -> Result<Vec<PendingReview>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let file: PendingFile = serde_yaml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+    Ok(file.reviews)
+}
+
+fn write_pending(path: &Path, reviews: Vec<PendingReview>) -> Result<(), String> {
+    let yaml = serde_yaml::to_string(&PendingFile { reviews }).map_err(|e| format!("failed to serialize pending reviews: {}", e))?;
+    write_atomically(path, &yaml)
+}
+
+/// Adds a review to the pending file, giving it an unused id.
+pub fn submit(path: &Path, mut review: PendingReview) -> Result<PendingReview, String> {
+    let _guard = FILES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut reviews = read_pending(path)?;
+    let base = review.id.clone();
+    let mut n = 2;
+    while reviews.iter().any(|pending| pending.id == review.id) {
+        review.id = format!("{}-{}", base, n);
+        n += 1;
+    }
+    reviews.push(review.clone());
+    write_pending(path, reviews)?;
+    Ok(review)
+}
+
+/// Removes the review with `id` from the pending file.
+fn take_pending(path: &Path, id: &str) -> Result<PendingReview, String> {
+    let mut reviews = read_pending(path)?;
+    let index = reviews
+        .iter()
+        .position(|review| review.id == id)
+        .ok_or_else(|| format!("no pending review {:?}", id))?;
+    let review = reviews.remove(index);
+    write_pending(path, reviews)?;
+    Ok(review)
+}
+
+/// Publishes a pending review by appending it to `reviews/approved.yaml`
+/// in the content directory, where both binaries pick it up.
+pub fn approve(pending_path: &Path, content_dir: &Path, id: &str) -> Result<Testimonial, String> {
+    let _guard = FILES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let approved_path = content_dir.join("reviews").join(APPROVED_REVIEWS_NAME);
+    // Check the approved file before touching the pending one
+    let mut testimonials = content::read_testimonials_file(&approved_path)?;
+
+    let review = take_pending(pending_path, id)?;
+    let testimonial = Testimonial {
+        date: review.date(),
+        quote: review.quote,
+        author: review.author,
+        title: review.title,
+        rating: review.rating,
+        source: None,
+        photo: None,
+        category: None,
+        featured: false,
+        order: None,
+    };
+    testimonials.push(testimonial.clone());
+
+    let yaml = serde_yaml::to_string(&TestimonialsFile { testimonials }).map_err(|e| format!("failed to serialize testimonials: {}", e))?;
+    write_atomically(&approved_path, &format!("# Visitor reviews approved from the moderation queue.\n{}", yaml))?;
+    Ok(testimonial)
+}
+
+/// Drops a pending review.
+pub fn reject(pending_path: &Path, id: &str) -> Result<PendingReview, String> {
+    let _guard = FILES.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    take_pending(pending_path, id)
+}
@@ -0,0 +1,238 @@
+//! Orders placed from the shop's carts. They are kept in an orders file
+//! outside the published content, as they hold customers' addresses, and
+//! paid for through a [`PaymentProvider`].
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::moderation::write_atomically;
+use crate::shop::{self, Cart, Catalog, ItemKey, Price};
+
+/// Orders file used unless told otherwise, next to `messages.txt`.
+pub const DEFAULT_ORDERS_FILE: &str = "orders.yaml";
+
+/// Longest name or email a customer may enter, in characters.
+pub const MAX_NAME_LENGTH: usize = 100;
+/// Longest shipping address a customer may enter, in characters.
+pub const MAX_ADDRESS_LENGTH: usize = 500;
+
+/// One line of an order, with its name and price when it was placed.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OrderItem {
+    pub product: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub variant: String,
+    pub name: String,
+    pub quantity: u32,
+    /// Price of one unit, in cents.
+    pub price: u64,
+}
+
+/// A paid order.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Order {
+    pub id: String,
+    /// When it was placed, in RFC 3339.
+    pub placed: String,
+    pub name: String,
+    pub email: String,
+    pub address: String,
+    pub items: Vec<OrderItem>,
+    /// What was charged, in cents.
+    pub total: u64,
+    pub currency: String,
+    /// Provider that took the payment, and its reference for it.
+    pub provider: String,
+    pub payment: String,
+}
+
+impl Order {
+    /// Checks a customer's details and the cart against the catalog and
+    /// what is left in stock, returning a message for the customer when
+    /// the order cannot be placed. The order is not paid for yet.
+    pub fn new(catalog: &Catalog, cart: &Cart, name: &str, email: &str, address: &str, sold: &HashMap<ItemKey, u32>, placed: DateTime<Utc>) -> Result<Self, String> {
+        let (name, email, address) = (name.trim(), email.trim(), address.trim());
+        if name.is_empty() || address.is_empty() || !email.contains('@') {
+            return Err("Please enter your name, email address and shipping address.".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LENGTH || email.chars().count() > MAX_NAME_LENGTH {
+            return Err(format!("Names and email addresses can be at most {} characters long.", MAX_NAME_LENGTH));
+        }
+        if address.chars().count() > MAX_ADDRESS_LENGTH {
+            return Err(format!("Addresses can be at most {} characters long.", MAX_ADDRESS_LENGTH));
+        }
+
+        let mut items = Vec::new();
+        for line in &cart.lines {
+            shop::check_quantity(catalog, &line.key, line.quantity, sold)?;
+            let item = catalog.item(&line.key).ok_or_else(|| "That item is no longer for sale.".to_string())?;
+            items.push(OrderItem {
+                product: line.key.product.clone(),
+                variant: line.key.variant.clone(),
+                name: item.name(),
+                quantity: line.quantity,
+                price: item.price().0,
+            });
+        }
+        if items.is_empty() {
+            return Err("Your cart is empty.".to_string());
+        }
+
+        Ok(Order {
+            id: placed.format("%Y%m%d-%H%M%S").to_string(),
+            placed: placed.to_rfc3339_opts(SecondsFormat::Secs, true),
+            name: name.to_string(),
+            email: email.to_string(),
+            address: address.to_string(),
+            total: items.iter().map(|item| item.price * u64::from(item.quantity)).sum(),
+            items,
+            currency: catalog.currency.clone(),
+            provider: String::new(),
+            payment: String::new(),
+        })
+    }
+
+    pub fn total(&self) -> Price {
+        Price(self.total)
+    }
+}
+
+/// Takes payment for orders.
+pub trait PaymentProvider: Send + Sync {
+    /// Name recorded with each order.
+    fn name(&self) -> &str;
+
+    /// Charges the customer the order's total, returning the provider's
+    /// reference for the payment, or why it was declined.
+    fn charge(&self, order: &Order) -> Result<String, String>;
+}
+
+/// Provider for local testing: accepts every payment without charging
+/// anyone, unless told to decline them all.
+#[derive(Clone, Debug, Default)]
+pub struct FakePayments {
+    pub decline: bool,
+}
+
+impl PaymentProvider for FakePayments {
+    fn name(&self) -> &str {
+        "fake"
+    }
+
+    fn charge(&self, order: &Order) -> Result<String, String> {
+        if self.decline {
+            return Err("the test provider declines every payment".to_string());
+        }
+        Ok(format!("fake-{}", order.id))
+    }
+}
+
+/// Why an order was not placed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckoutError {
+    /// Something the customer can fix, or a declined payment; the message
+    /// is for them.
+    Rejected(String),
+    /// The orders file could not be read or written.
+    Failed(String),
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct OrdersFile {
+    #[serde(default)]
+    orders: Vec<Order>,
+}
+
+/// Reads the orders, oldest first. A missing file means none.
+pub fn read_orders(path: &Path) -> Result<Vec<Order>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let file: OrdersFile = serde_yaml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+    Ok(file.orders)
+}
+
+/// Units of each item the orders took.
+pub fn sold(orders: &[Order]) -> HashMap<ItemKey, u32> {
+    let mut sold = HashMap::new();
+    for item in orders.iter().flat_map(|order| &order.items) {
+        let key = ItemKey { product: item.product.clone(), variant: item.variant.clone() };
+        *sold.entry(key).or_default() += item.quantity;
+    }
+    sold
+}
+
+/// The orders file and the orders being paid for that go into it. Their
+/// units count as sold until the payment is declined, so that two checkouts
+/// cannot both take the last unit, but the lock is not held while the
+/// payment provider is called. The lock also serializes changes to the file.
+#[derive(Debug)]
+pub struct Orders {
+    path: PathBuf,
+    paying: Mutex<Vec<Order>>,
+}
+
+impl Orders {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Orders { path: path.into(), paying: Mutex::new(Vec::new()) }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Order>> {
+        self.paying.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Orders in the file and those being paid for.
+    fn read_with_paying(&self, paying: &[Order]) -> Result<Vec<Order>, String> {
+        let mut orders = read_orders(&self.path)?;
+        orders.extend(paying.iter().cloned());
+        Ok(orders)
+    }
+
+    /// Units of each item sold so far, or being paid for, for showing what
+    /// is left.
+    pub fn read_sold(&self) -> Result<HashMap<ItemKey, u32>, String> {
+        let paying = self.lock();
+        self.read_with_paying(&paying).map(|orders| sold(&orders))
+    }
+
+    /// Places an order for the cart: checks it against the stock left and
+    /// sets those units aside, takes payment and records the order, giving
+    /// it an unused id.
+    pub fn place(&self, catalog: &Catalog, cart: &Cart, customer: (&str, &str, &str), payments: &dyn PaymentProvider, placed: DateTime<Utc>) -> Result<Order, CheckoutError> {
+        let mut order = {
+            let mut paying = self.lock();
+            let orders = self.read_with_paying(&paying).map_err(CheckoutError::Failed)?;
+            let (name, email, address) = customer;
+            let mut order = Order::new(catalog, cart, name, email, address, &sold(&orders), placed).map_err(CheckoutError::Rejected)?;
+            let base = order.id.clone();
+            let mut n = 2;
+            while orders.iter().any(|placed| placed.id == order.id) {
+                order.id = format!("{}-{}", base, n);
+                n += 1;
+            }
+            paying.push(order.clone());
+            order
+        };
+
+        let payment = payments.charge(&order);
+        let mut paying = self.lock();
+        paying.retain(|other| other.id != order.id);
+        order.payment = payment.map_err(|reason| CheckoutError::Rejected(format!("Your payment was declined: {}.", reason)))?;
+        order.provider = payments.name().to_string();
+
+        // The payment has gone through, so a failure from here on has to be looked into
+        let not_recorded = |e: String| CheckoutError::Failed(format!("order {} was paid ({}) but not recorded: {}", order.id, order.payment, e));
+        let mut orders = read_orders(&self.path).map_err(not_recorded)?;
+        orders.push(order.clone());
+        let yaml = serde_yaml::to_string(&OrdersFile { orders }).map_err(|e| not_recorded(format!("failed to serialize orders: {}", e)))?;
+        write_atomically(&self.path, &yaml).map_err(not_recorded)?;
+        Ok(order)
+    }
+}
@@ -0,0 +1,395 @@
+//! The static output directory, built incrementally and swapped in whole.
+//!
+//! A build writes into a staging directory next to the output directory
+//! and only replaces the output when it finished without errors, so a
+//! failed or interrupted build never leaves a half-written site behind. A
+//! manifest in the output directory records a hash of every file the last
+//! build produced: files that come out the same are hard-linked from the
+//! previous output instead of written again, and copies of unchanged
+//! sources are skipped without reading them. A build that changes nothing
+//! leaves the output directory alone.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::UNIX_EPOCH;
+
+/// File name of the manifest, kept at the top of the output directory.
+pub const MANIFEST_NAME: &str = ".build-manifest.json";
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+struct FileRecord {
+    /// FNV-1a hash of the contents, in hex.
+    hash: String,
+    /// Size and modification time, in nanoseconds since the epoch, of the
+    /// source a copied file came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<(u64, u64)>,
+    /// Hash of the input a file from [`Output::write_from`] was made from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    input: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct Manifest {
+    files: BTreeMap<String, FileRecord>,
+}
+
+/// How much a build prints.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Errors only.
+    Quiet,
+    /// Each page generated and a summary.
+    #[default]
+    Normal,
+    /// Also every file written or copied.
+    Verbose,
+}
+
+/// What a build did to the output directory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OutputStats {
+    /// Generated files whose contents changed.
+    pub written: usize,
+    /// Copied files whose source changed.
+    pub copied: usize,
+    /// Files already up to date.
+    pub unchanged: usize,
+    /// Files of the previous output that this build no longer produced.
+    pub removed: usize,
+}
+
+/// 64-bit FNV-1a, stable across Rust versions unlike `DefaultHasher`.
+pub fn hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3));
+    format!("{:016x}", hash)
+}
+
+fn source_stamp(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    Some((metadata.len(), modified.as_nanos() as u64))
+}
+
+/// A hidden directory next to `dir`, e.g. `.docs-staging` for `docs`.
+fn sibling(dir: &Path, suffix: &str) -> PathBuf {
+    let name = dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_else(|| "output".to_string());
+    dir.with_file_name(format!(".{}-{}", name, suffix))
+}
+
+fn create_parent(path: &Path) -> Result<(), String> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent).map_err(|e| format!("failed to create {}: {}", parent.display(), e)),
+        None => Ok(()),
+    }
+}
+
+/// The output of one build. Generated files are written right away; copies
+/// are queued and done in parallel by [`Output::finish`]. Paths are given
+/// inside [`Output::dir`], the final output directory, and land in staging.
+pub struct Output {
+    dir: PathBuf,
+    staging: PathBuf,
+    previous: BTreeMap<String, FileRecord>,
+    files: Mutex<BTreeMap<String, FileRecord>>,
+    copies: Mutex<BTreeMap<String, PathBuf>>,
+    stats: Mutex<OutputStats>,
+    errors: Mutex<Vec<String>>,
+    verbosity: Verbosity,
+}
+
+impl Output {
+    /// Starts a build of `dir` in a fresh staging directory. A missing or
+    /// unreadable manifest just means every file is written again.
+    pub fn open(dir: &Path, verbosity: Verbosity) -> Result<Self, String> {
+        let staging = sibling(dir, "staging");
+        restore_previous(dir)?;
+        // Left over from a build that was interrupted
+        if staging.exists() {
+            fs::remove_dir_all(&staging).map_err(|e| format!("failed to remove {}: {}", staging.display(), e))?;
+        }
+        fs::create_dir_all(&staging).map_err(|e| format!("failed to create {}: {}", staging.display(), e))?;
+
+        let previous = fs::read_to_string(dir.join(MANIFEST_NAME))
+            .ok()
+            .and_then(|json| serde_json::from_str::<Manifest>(&json).ok())
+            .unwrap_or_default()
+            .files;
+        Ok(Output {
+            dir: dir.to_path_buf(),
+            staging,
+            previous,
+            files: Mutex::new(BTreeMap::new()),
+            copies: Mutex::new(BTreeMap::new()),
+            stats: Mutex::new(OutputStats::default()),
+            errors: Mutex::new(Vec::new()),
+            verbosity,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Prints a progress message unless the build is quiet.
+    pub fn note(&self, message: impl fmt::Display) {
+        if self.verbosity >= Verbosity::Normal {
+            println!("{}", message);
+        }
+    }
+
+    /// Records a problem that makes the build unpublishable.
+    pub fn fail(&self, message: impl Into<String>) {
+        self.errors.lock().unwrap().push(message.into());
+    }
+
+    /// Manifest key of `path`, which is inside the output directory.
+    fn key(&self, path: &Path) -> String {
+        path.strip_prefix(&self.dir).unwrap_or(path).to_string_lossy().replace('\\', "/")
+    }
+
+    fn record(&self, key: String, record: FileRecord, changed: bool, copied: bool) {
+        if changed && self.verbosity == Verbosity::Verbose {
+            println!("  {} {}", if copied { "copied" } else { "wrote" }, key);
+        }
+        self.files.lock().unwrap().insert(key, record);
+        let mut stats = self.stats.lock().unwrap();
+        match (changed, copied) {
+            (false, _) => stats.unchanged += 1,
+            (true, false) => stats.written += 1,
+            (true, true) => stats.copied += 1,
+        }
+    }
+
+    /// Whether the previous output still has `key`, with a manifest record
+    /// that `current` accepts.
+    fn is_current(&self, key: &str, current: impl Fn(&FileRecord) -> bool) -> bool {
+        self.previous.get(key).is_some_and(current) && self.dir.join(key).is_file()
+    }
+
+    /// Carries `key` over from the previous output, linking rather than
+    /// copying where the file system allows.
+    fn reuse(&self, key: &str) -> Result<(), String> {
+        let (from, to) = (self.dir.join(key), self.staging.join(key));
+        create_parent(&to)?;
+        fs::hard_link(&from, &to)
+            .or_else(|_| fs::copy(&from, &to).map(|_| ()))
+            .map_err(|e| format!("failed to copy {}: {}", from.display(), e))
+    }
+
+    fn stage(&self, key: &str, contents: &[u8]) -> Result<(), String> {
+        let path = self.staging.join(key);
+        create_parent(&path)?;
+        fs::write(&path, contents).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+    }
+
+    fn try_write(&self, key: String, contents: &[u8], input: Option<String>) -> Result<(), String> {
+        let record = FileRecord { hash: hash(contents), source: None, input };
+        let unchanged = self.is_current(&key, |previous| previous.hash == record.hash);
+        if unchanged {
+            self.reuse(&key)?;
+        } else {
+            self.stage(&key, contents)?;
+        }
+        self.record(key, record, !unchanged, false);
+        Ok(())
+    }
+
+    /// Writes a generated file at `path`. Failures are recorded with
+    /// [`Output::fail`].
+    pub fn write(&self, path: &Path, contents: impl AsRef<[u8]>) {
+        if let Err(e) = self.try_write(self.key(path), contents.as_ref(), None) {
+            self.fail(e);
+        }
+    }
+
+    /// Writes the file `render` makes from `input`, unless the last build
+    /// made it from the same input. For files such as PDFs that come out
+    /// different on every render. Returns whether the file is there;
+    /// failures are recorded with [`Output::fail`].
+    pub fn write_from(&self, path: &Path, input: &[u8], render: impl FnOnce() -> Result<Vec<u8>, String>) -> bool {
+        let key = self.key(path);
+        let input = hash(input);
+        let result = if self.is_current(&key, |previous| previous.input.as_ref() == Some(&input)) {
+            self.reuse(&key).map(|()| self.record(key.clone(), self.previous[&key].clone(), false, false))
+        } else {
+            render().and_then(|contents| self.try_write(key.clone(), &contents, Some(input)))
+        };
+        match result {
+            Ok(()) => true,
+            Err(e) => {
+                self.fail(format!("Failed to generate {}: {}", key, e));
+                false
+            }
+        }
+    }
+
+    /// Queues a copy of `source` to `dest`. A file already written or queued
+    /// for `dest` wins.
+    pub fn copy(&self, source: &Path, dest: &Path) {
+        let key = self.key(dest);
+        if self.files.lock().unwrap().contains_key(&key) {
+            return;
+        }
+        self.copies.lock().unwrap().entry(key).or_insert_with(|| source.to_path_buf());
+    }
+
+    /// Copies one queued file unless its source is unchanged since the last
+    /// build, judged by size and modification time and then by hash.
+    fn copy_file(&self, key: String, source: &Path) -> Result<(), String> {
+        let metadata = fs::metadata(source).map_err(|e| format!("failed to read {}: {}", source.display(), e))?;
+        let stamp = source_stamp(&metadata);
+        let same_size = fs::metadata(self.dir.join(&key)).is_ok_and(|dest| dest.len() == metadata.len());
+
+        if stamp.is_some() && same_size && self.is_current(&key, |previous| previous.source == stamp) {
+            self.reuse(&key)?;
+            self.record(key.clone(), self.previous[&key].clone(), false, true);
+            return Ok(());
+        }
+
+        let bytes = fs::read(source).map_err(|e| format!("failed to read {}: {}", source.display(), e))?;
+        let record = FileRecord { hash: hash(&bytes), source: stamp, input: None };
+        let unchanged = same_size && self.is_current(&key, |previous| previous.hash == record.hash);
+        if unchanged {
+            self.reuse(&key)?;
+        } else {
+            self.stage(&key, &bytes)?;
+        }
+        self.record(key, record, !unchanged, true);
+        Ok(())
+    }
+
+    /// Does the queued copies on all cores. Without errors so far, saves
+    /// the manifest and swaps the staging directory in for the output,
+    /// unless the output is already exactly what this build produced;
+    /// otherwise discards it, leaving the output as it was, and returns
+    /// every error.
+    pub fn finish(self) -> Result<OutputStats, Vec<String>> {
+        // Files generated after a copy of theirs was queued win too
+        let copies: Vec<(String, PathBuf)> = {
+            let written = self.files.lock().unwrap();
+            std::mem::take(&mut *self.copies.lock().unwrap())
+                .into_iter()
+                .filter(|(key, _)| !written.contains_key(key))
+                .collect()
+        };
+        let workers = thread::available_parallelism().map_or(1, |n| n.get()).min(copies.len().max(1));
+        let next = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| {
+                    while let Some((key, source)) = copies.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        if let Err(e) = self.copy_file(key.clone(), source) {
+                            self.fail(format!("Failed to copy {}: {}", key, e));
+                        }
+                    }
+                });
+            }
+        });
+
+        let errors = self.errors.into_inner().unwrap();
+        let result = if errors.is_empty() {
+            let files = self.files.into_inner().unwrap();
+            let mut stats = self.stats.into_inner().unwrap();
+            stats.removed = count_stale(&self.dir, &self.dir, &files);
+            if stats.removed == 0 && files == self.previous {
+                // Nothing changed: leave the output, manifest included, untouched
+                let _ = fs::remove_dir_all(&self.staging);
+                return Ok(stats);
+            }
+            save_manifest(&self.staging, files)
+                .and_then(|()| swap(&self.staging, &self.dir))
+                .map(|()| stats)
+                .map_err(|e| vec![e])
+        } else {
+            Err(errors)
+        };
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&self.staging);
+        }
+        result
+    }
+}
+
+fn save_manifest(staging: &Path, files: BTreeMap<String, FileRecord>) -> Result<(), String> {
+    let manifest = serde_json::to_string_pretty(&Manifest { files }).map_err(|e| format!("failed to serialize the build manifest: {}", e))?;
+    let path = staging.join(MANIFEST_NAME);
+    fs::write(&path, manifest).map_err(|e| format!("failed to write {}: {}", path.display(), e))
+}
+
+/// Counts the files under `dir` that are not in `files`.
+fn count_stale(root: &Path, dir: &Path, files: &BTreeMap<String, FileRecord>) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .map(|path| {
+            if path.is_dir() {
+                return count_stale(root, &path, files);
+            }
+            let key = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            usize::from(key != MANIFEST_NAME && !files.contains_key(&key))
+        })
+        .sum()
+}
+
+/// Moves the old output back into place when a swap was interrupted between
+/// its two renames, leaving only `.docs-previous`.
+fn restore_previous(dir: &Path) -> Result<(), String> {
+    let previous = sibling(dir, "previous");
+    if dir.exists() || !previous.exists() {
+        return Ok(());
+    }
+    fs::rename(&previous, dir).map_err(|e| format!("failed to restore {} from {}: {}", dir.display(), previous.display(), e))
+}
+
+/// Puts `staging` in place of `dir`. This is two renames, not an atomic
+/// exchange: the old output is moved aside first, and moved back if the
+/// staging directory cannot take its place. A crash in between leaves the
+/// old output in `.docs-previous`, which the next build restores before
+/// anything else.
+fn swap(staging: &Path, dir: &Path) -> Result<(), String> {
+    let previous = sibling(dir, "previous");
+    if previous.exists() {
+        fs::remove_dir_all(&previous).map_err(|e| format!("failed to remove {}: {}", previous.display(), e))?;
+    }
+    let had_output = dir.exists();
+    if had_output {
+        fs::rename(dir, &previous).map_err(|e| format!("failed to move {} aside: {}", dir.display(), e))?;
+    }
+    if let Err(e) = fs::rename(staging, dir) {
+        let mut message = format!("failed to move {} into place: {}", staging.display(), e);
+        if had_output && let Err(e) = fs::rename(&previous, dir) {
+            message = format!("{}; the previous output is left in {}: {}", message, previous.display(), e);
+        }
+        return Err(message);
+    }
+    if had_output {
+        let _ = fs::remove_dir_all(&previous);
+    }
+    Ok(())
+}
+
+/// Removes `dir` and what an interrupted build may have left next to it,
+/// returning the directories that were there. Refuses, removing nothing,
+/// when `dir` has no build manifest, as it then is not a build's output.
+pub fn clean(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    if dir.exists() && !dir.join(MANIFEST_NAME).is_file() {
+        return Err(format!("refusing to remove {}: it has no {}, so it is not build output", dir.display(), MANIFEST_NAME));
+    }
+    let mut removed = Vec::new();
+    for path in [dir.to_path_buf(), sibling(dir, "staging"), sibling(dir, "previous")] {
+        if path.exists() {
+            fs::remove_dir_all(&path).map_err(|e| format!("failed to remove {}: {}", path.display(), e))?;
+            removed.push(path);
+        }
+    }
+    Ok(removed)
+}
@@ -0,0 +1,197 @@
+//! News posts: Markdown files with YAML front matter in `posts/`.
+
+use chrono::NaiveDate;
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, TagEnd};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::discography::slugify;
+
+/// Posts on each `/news/` list page.
+pub const POSTS_PER_PAGE: usize = 10;
+
+/// Slugs that would collide with the `/news/page/` and `/news/tags/` routes.
+pub const RESERVED_SLUGS: &[&str] = &["page", "tags"];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FrontMatter {
+    title: String,
+    /// Publication date as `YYYY-MM-DD`.
+    date: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Cover image path relative to the content directory.
+    cover: Option<String>,
+    /// Drafts are only shown by the development server.
+    #[serde(default)]
+    draft: bool,
+    /// Teaser for list pages; the first paragraph is used when omitted.
+    summary: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Post {
+    /// URL segment, from the file name.
+    pub slug: String,
+    pub title: String,
+    pub date: NaiveDate,
+    pub tags: Vec<String>,
+    pub cover: Option<String>,
+    pub draft: bool,
+    pub summary: String,
+    /// The rendered Markdown body.
+    pub body_html: String,
+}
+
+impl Post {
+    pub fn display_date(&self) -> String {
+        self.date.format("%B %-d, %Y").to_string()
+    }
+
+    pub fn has_tag(&self, tag_slug: &str) -> bool {
+        self.tags.iter().any(|tag| slugify(tag) == tag_slug)
+    }
+}
+
+/// Splits `---` delimited YAML front matter from the Markdown body.
+fn split_front_matter(source: &str) -> Option<(&str, &str)> {
+    let mut lines = source.split_inclusive('\n');
+    let opening = lines.next()?;
+    if opening.trim_end() != "---" {
+        return None;
+    }
+
+    let mut offset = opening.len();
+    for line in lines {
+        if line.trim_end() == "---" {
+            return Some((&source[opening.len()..offset], &source[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Points a relative URL at the `posts/` folder under `asset_base`.
+fn rebase<'a>(url: CowStr<'a>, asset_base: &str) -> CowStr<'a> {
+    if url.is_empty() || url.starts_with('/') || url.starts_with('#') || url.contains(':') {
+        url
+    } else {
+        format!("{}/posts/{}", asset_base, url).into()
+    }
+}
+
+/// Renders Markdown to HTML. Relative image and link URLs point at files in
+/// the `posts/` folder, served under `asset_base`. Returns the HTML and the
+/// text of the first paragraph.
+pub fn render_markdown(markdown: &str, asset_base: &str) -> (String, String) {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_FOOTNOTES | Options::ENABLE_SMART_PUNCTUATION;
+    let mut first_paragraph = String::new();
+    let mut paragraphs_seen = 0;
+    let mut in_paragraph = false;
+    let events = Parser::new_ext(markdown, options).map(|event| {
+        match &event {
+            Event::Start(Tag::Paragraph) => {
+                in_paragraph = true;
+                paragraphs_seen += 1;
+            },
+            Event::End(TagEnd::Paragraph) => in_paragraph = false,
+            Event::Text(text) | Event::Code(text) if in_paragraph && paragraphs_seen == 1 => first_paragraph.push_str(text),
+            Event::SoftBreak if in_paragraph && paragraphs_seen == 1 => first_paragraph.push(' '),
+            _ => {},
+        }
+        match event {
+            Event::Start(Tag::Image { link_type, dest_url, title, id }) => Event::Start(Tag::Image { link_type, dest_url: rebase(dest_url, asset_base), title, id }),
+            Event::Start(Tag::Link { link_type, dest_url, title, id }) => Event::Start(Tag::Link { link_type, dest_url: rebase(dest_url, asset_base), title, id }),
+            event => event,
+        }
+    });
+
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, events);
+    (html, first_paragraph)
+}
+
+/// Reads one post file.
+pub fn read_post(path: &Path, asset_base: &str) -> Result<Post, String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    let (front_matter, body) = split_front_matter(&source).ok_or_else(|| format!("{}: missing --- front matter", path.display()))?;
+    let meta: FrontMatter = serde_yaml::from_str(front_matter).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+    let date = NaiveDate::parse_from_str(&meta.date, "%Y-%m-%d")
+        .map_err(|_| format!("{}: invalid date {:?}, expected YYYY-MM-DD", path.display(), meta.date))?;
+
+    let slug = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default().to_string();
+    if slug.is_empty() || slug != slugify(&slug) || RESERVED_SLUGS.contains(&slug.as_str()) {
+        return Err(format!("{}: file name must be a lowercase slug like my-first-post.md", path.display()));
+    }
+
+    let (body_html, first_paragraph) = render_markdown(body, asset_base);
+    Ok(Post {
+        slug,
+        title: meta.title,
+        date,
+        tags: meta.tags,
+        cover: meta.cover,
+        draft: meta.draft,
+        summary: meta.summary.unwrap_or(first_paragraph),
+        body_html,
+    })
+}
+
+/// Reads `posts/*.md`, newest first. Drafts are left out unless
+/// `include_drafts` is set. A missing folder means no posts; an invalid post
+/// is an error.
+pub fn read_posts(content_dir: &Path, asset_base: &str, include_drafts: bool) -> Result<Vec<Post>, String> {
+    let posts_dir = content_dir.join("posts");
+    if !posts_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&posts_dir).map_err(|e| format!("failed to read {}: {}", posts_dir.display(), e))?;
+    let mut paths: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("md"))
+        .collect();
+    paths.sort();
+
+    let mut posts = Vec::new();
+    for path in paths {
+        let post = read_post(&path, asset_base)?;
+        if include_drafts || !post.draft {
+            posts.push(post);
+        }
+    }
+
+    posts.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.slug.cmp(&b.slug)));
+    Ok(posts)
+}
+
+/// Distinct tags in order of first use, with their URL slugs.
+pub fn tags(posts: &[Post]) -> Vec<(String, String)> {
+    let mut seen = HashSet::new();
+    let mut tags = Vec::new();
+    for tag in posts.iter().flat_map(|post| &post.tags) {
+        let slug = slugify(tag);
+        if seen.insert(slug.clone()) {
+            tags.push((tag.clone(), slug));
+        }
+    }
+    tags
+}
+
+/// Number of list pages needed for `count` posts; always at least one.
+pub fn page_count(count: usize) -> usize {
+    count.div_ceil(POSTS_PER_PAGE).max(1)
+}
+
+/// The posts on 1-based `page`, or `None` past the last page.
+pub fn page(posts: &[Post], page: usize) -> Option<&[Post]> {
+    if page == 0 || page > page_count(posts.len()) {
+        return None;
+    }
+    let start = (page - 1) * POSTS_PER_PAGE;
+    Some(&posts[start..posts.len().min(start + POSTS_PER_PAGE)])
+}
@@ -0,0 +1,88 @@
+//! Preview of the static output as GitHub Pages serves it, for
+//! `generate-static serve`.
+//!
+//! Files are only served under the base path. A URL ending in `/` gets the
+//! `index.html` of that folder; any other URL must name a file exactly, as
+//! the generated links do. Nothing is redirected: a folder without its
+//! trailing slash, like every other missing path, gets the site's
+//! `404.html` with status 404, so a link that only works through the
+//! development server's routing shows up before it is deployed.
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tower_http::services::ServeFile;
+
+struct Preview {
+    out_dir: PathBuf,
+    base_path: String,
+}
+
+/// Decodes `%XX` escapes; `None` for malformed escapes or invalid UTF-8.
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// The file in `out_dir` that GitHub Pages would answer `path` with, if
+/// any. `base_path` has no trailing slash and is empty at the root.
+pub fn resolve(out_dir: &Path, base_path: &str, path: &str) -> Option<PathBuf> {
+    let rest = path.strip_prefix(base_path).filter(|rest| rest.starts_with('/'))?;
+    let rest = percent_decode(rest)?;
+
+    let mut file = out_dir.to_path_buf();
+    for segment in rest.split('/').filter(|segment| !segment.is_empty()) {
+        if segment == "." || segment == ".." || segment.contains('\\') {
+            return None;
+        }
+        file.push(segment);
+    }
+    if rest.ends_with('/') {
+        file.push("index.html");
+    }
+    file.is_file().then_some(file)
+}
+
+async fn serve_path(State(preview): State<Arc<Preview>>, request: Request) -> Response {
+    let Some(file) = resolve(&preview.out_dir, &preview.base_path, request.uri().path()) else {
+        return not_found(&preview.out_dir).await;
+    };
+    match ServeFile::new(file).try_call(request).await {
+        Ok(response) => response.map(Body::new),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// The site's `404.html`, always in full: conditional and range headers
+/// are meant for the file that was asked for, not for this page.
+async fn not_found(out_dir: &Path) -> Response {
+    match tokio::fs::read(out_dir.join("404.html")).await {
+        Ok(page) => (StatusCode::NOT_FOUND, [(header::CONTENT_TYPE, "text/html")], page).into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, "404 Not Found").into_response(),
+    }
+}
+
+/// Serves `out_dir` under `base_path`, e.g. `/Website-test`.
+pub fn router(out_dir: &Path, base_path: &str) -> Router {
+    let preview = Preview {
+        out_dir: out_dir.to_path_buf(),
+        base_path: base_path.trim_end_matches('/').to_string(),
+    };
+    Router::new().fallback(serve_path).with_state(Arc::new(preview))
+}
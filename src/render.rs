@@ -0,0 +1,1293 @@
+//! HTML and JSON fragments shared by the server and the static generator.
+
+use std::collections::HashMap;
+
+use crate::content::{self, CategoryData, Testimonial};
+use crate::credits::ActingResume;
+use crate::discography::{self, Release};
+use crate::events::Event;
+use crate::media::{MediaGroup, MediaItem, MediaSource, YoutubeTarget};
+use crate::moderation::{self, PendingReview};
+use crate::orders;
+use crate::posts::Post;
+use crate::seo::PageMeta;
+use crate::shop::{self, Cart, Catalog, ItemKey, Price, Product};
+
+/// Layout every page is rendered into.
+pub const BASE_TEMPLATE: &str = include_str!("../templates/base.html");
+
+/// Styles of the layout, bundled ahead of the content's `styles.css`.
+pub const BASE_STYLES: &str = include_str!("../templates/base.css");
+
+pub fn url_encode(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| match c {
+            ' ' => "%20".to_string(),
+            '+' => "%2B".to_string(),
+            '#' => "%23".to_string(),
+            '&' => "%26".to_string(),
+            '=' => "%3D".to_string(),
+            '?' => "%3F".to_string(),
+            c if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '~' => c.to_string(),
+            _ => format!("%{:02X}", c as u8),
+        })
+        .collect()
+}
+
+/// Percent-encodes each segment of a relative path, keeping the slashes.
+pub fn url_encode_path(path: &str) -> String {
+    path.split('/').map(url_encode).collect::<Vec<_>>().join("/")
+}
+
+pub fn html_escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// What the cart indicator in the layout shows.
+#[derive(Clone, Copy, Debug)]
+pub enum CartIndicator<'a> {
+    /// The number of items in the visitor's cart, read from `count_url`,
+    /// linking to the cart.
+    Live { cart_url: &'a str, count_url: &'a str },
+    /// A link to a cart elsewhere, whose count this site cannot read.
+    Link(&'a str),
+    /// No cart.
+    Hidden,
+}
+
+/// Keeps the cart count up to date, also on pages restored from the
+/// back-forward cache after something was added.
+const CART_COUNT_SCRIPT: &str = r#"<script>
+        (function () {
+            var count = document.querySelector('.cart-count');
+            function refresh() {
+                fetch(count.dataset.countUrl, { credentials: 'same-origin' })
+                    .then(function (response) { return response.json(); })
+                    .then(function (cart) { count.textContent = cart.count; })
+                    .catch(function () {});
+            }
+            refresh();
+            window.addEventListener('pageshow', function (event) {
+                if (event.persisted) refresh();
+            });
+        })();
+    </script>"#;
+
+pub fn generate_cart_indicator_html(indicator: CartIndicator) -> String {
+    match indicator {
+        CartIndicator::Live { cart_url, count_url } => format!(
+            r#"<a href="{}" class="cart-indicator">CART (<span class="cart-count" data-count-url="{}">0</span>)</a>
+    {}"#,
+            cart_url, count_url, CART_COUNT_SCRIPT
+        ),
+        CartIndicator::Link(cart_url) => format!(r#"<a href="{}" class="cart-indicator">CART</a>"#, html_escape(cart_url)),
+        CartIndicator::Hidden => String::new(),
+    }
+}
+
+/// Renders a page into the layout, with the server's own cart.
+pub fn generate_page(title: &str, content: &str, meta: &PageMeta) -> String {
+    generate_page_with_cart(title, content, meta, CartIndicator::Live { cart_url: "/cart/", count_url: "/cart/count" })
+}
+
+pub fn generate_page_with_cart(title: &str, content: &str, meta: &PageMeta, cart: CartIndicator) -> String {
+    BASE_TEMPLATE
+        .replace("{{HEAD}}", &meta.head_html(title))
+        .replace("{{TITLE}}", &html_escape(title))
+        .replace("{{CART}}", &generate_cart_indicator_html(cart))
+        .replace("{{CONTENT}}", content)
+}
+
+/// Filled and empty stars for a rating out of five.
+fn rating_html(rating: Option<u8>) -> String {
+    rating
+        .map(|rating| {
+            let stars = usize::from(rating.min(5));
+            format!(
+                r#"<div class="testimonial-rating" aria-label="{} out of 5 stars">{}{}</div>"#,
+                rating,
+                "★".repeat(stars),
+                "☆".repeat(5 - stars)
+            )
+        })
+        .unwrap_or_default()
+}
+
+fn testimonial_photo_html(testimonial: &Testimonial, asset_base: &str) -> String {
+    testimonial
+        .photo
+        .as_deref()
+        .map(|photo| {
+            format!(
+                r#"<img src="{}/{}" alt="{}" class="testimonial-photo" loading="lazy">"#,
+                asset_base,
+                html_escape(&url_encode_path(photo)),
+                html_escape(&testimonial.author)
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// `html` followed by a line break and `indent`, or nothing when empty.
+fn with_line_break(html: String, indent: &str) -> String {
+    if html.is_empty() { html } else { format!("{}\n{}", html, indent) }
+}
+
+fn testimonial_card(testimonial: &Testimonial, asset_base: &str) -> String {
+    let mut meta = Vec::new();
+    if let Some(date) = testimonial.date {
+        meta.push(format!(r#"<time datetime="{}">{}</time>"#, date.format("%Y-%m-%d"), date.format("%B %-d, %Y")));
+    }
+    if let Some(source) = &testimonial.source {
+        meta.push(format!(r#"<a href="{}" target="_blank" rel="noopener">Original review</a>"#, html_escape(source)));
+    }
+    let meta_html = if meta.is_empty() {
+        String::new()
+    } else {
+        format!("\n                    <span class=\"testimonial-meta\">{}</span>", meta.join(" · "))
+    };
+    format!(
+        r#"<div class="testimonial-card" id="{}">
+                {}<div class="testimonial-text">
+                    <p>{}</p>
+                </div>
+                <div class="testimonial-author">
+                    {}<span class="author-name">{}</span>
+                    <span class="author-title">{}</span>{}
+                </div>
+            </div>"#,
+        testimonial.anchor(),
+        with_line_break(rating_html(testimonial.rating), "                "),
+        html_escape(&testimonial.quote),
+        with_line_break(testimonial_photo_html(testimonial, asset_base), "                    "),
+        html_escape(&testimonial.author),
+        html_escape(&testimonial.title),
+        meta_html
+    )
+}
+
+/// Shows or hides testimonial groups from the category buttons. The buttons
+/// stay hidden without JavaScript, when every group is shown.
+const TESTIMONIALS_FILTER_SCRIPT: &str = r#"<script>
+        (function () {
+            const filter = document.querySelector('.testimonials-filter');
+            filter.hidden = false;
+            filter.addEventListener('click', function (event) {
+                const button = event.target.closest('button');
+                if (!button) return;
+                const category = button.dataset.category;
+                filter.querySelectorAll('button').forEach(function (other) {
+                    other.classList.toggle('active', other === button);
+                });
+                document.querySelectorAll('.testimonials-group').forEach(function (group) {
+                    group.hidden = category !== '' && group.dataset.category !== category;
+                });
+            });
+        })();
+    </script>"#;
+
+/// Renders the testimonials grid. Once any testimonial has a category, they
+/// are grouped under a heading per category, with buttons to filter by one.
+pub fn generate_testimonials_html(testimonials: &[Testimonial], asset_base: &str) -> String {
+    let cards = |members: &[&Testimonial]| {
+        members
+            .iter()
+            .map(|testimonial| testimonial_card(testimonial, asset_base))
+            .collect::<Vec<_>>()
+            .join("\n            ")
+    };
+
+    let groups = content::group_testimonials(testimonials);
+    if groups.iter().all(|(category, _)| category.is_none()) {
+        let all: Vec<&Testimonial> = testimonials.iter().collect();
+        return format!("<div class=\"testimonials-grid\">\n            {}\n        </div>", cards(&all));
+    }
+
+    let named = |category: Option<&str>| category.unwrap_or("Other").to_string();
+    let mut html = String::new();
+    if groups.len() > 1 {
+        let buttons: Vec<String> = groups
+            .iter()
+            .map(|(category, _)| {
+                let name = named(*category);
+                format!(
+                    r#"<button type="button" data-category="{}">{}</button>"#,
+                    discography::slugify(&name),
+                    html_escape(&name)
+                )
+            })
+            .collect();
+        html.push_str(&format!(
+            "<div class=\"testimonials-filter\" hidden>\n            <button type=\"button\" data-category=\"\" class=\"active\">All</button>\n            {}\n        </div>\n        ",
+            buttons.join("\n            ")
+        ));
+    }
+    let sections: Vec<String> = groups
+        .iter()
+        .map(|(category, members)| {
+            let name = named(*category);
+            format!(
+                "<section class=\"testimonials-group\" data-category=\"{}\">\n            <h3 class=\"testimonials-group-heading\">{}</h3>\n            <div class=\"testimonials-grid\">\n            {}\n            </div>\n        </section>",
+                discography::slugify(&name),
+                html_escape(&name),
+                cards(members)
+            )
+        })
+        .collect();
+    html.push_str(&sections.join("\n        "));
+    if groups.len() > 1 {
+        html.push_str("\n        ");
+        html.push_str(TESTIMONIALS_FILTER_SCRIPT);
+    }
+    html
+}
+
+/// Renders the "Leave a review" form posting to `action`. The `website`
+/// field is a honeypot hidden from people.
+pub fn generate_review_form_html(action: &str) -> String {
+    format!(
+        r#"<form class="review-form" method="post" action="{}">
+            <h2 class="testimonials-heading">Leave a Review</h2>
+            <div class="form-group">
+                <label for="review-author">Name</label>
+                <input type="text" id="review-author" name="author" required maxlength="{}" placeholder="Your name">
+            </div>
+            <div class="form-group">
+                <label for="review-title">Role <span class="optional">(optional)</span></label>
+                <input type="text" id="review-title" name="title" maxlength="{}" placeholder="e.g. Photographer, Director or Fan">
+            </div>
+            <div class="form-group">
+                <label for="review-rating">Rating <span class="optional">(optional)</span></label>
+                <select id="review-rating" name="rating">
+                    <option value="">No rating</option>
+                    <option value="5">★★★★★</option>
+                    <option value="4">★★★★☆</option>
+                    <option value="3">★★★☆☆</option>
+                    <option value="2">★★☆☆☆</option>
+                    <option value="1">★☆☆☆☆</option>
+                </select>
+            </div>
+            <div class="form-group">
+                <label for="review-quote">Review</label>
+                <textarea id="review-quote" name="quote" rows="5" required maxlength="{}" placeholder="Share your experience..."></textarea>
+            </div>
+            <div class="form-trap" aria-hidden="true">
+                <label for="review-website">Leave this empty</label>
+                <input type="text" id="review-website" name="website" tabindex="-1" autocomplete="off">
+            </div>
+            <button type="submit" class="submit-btn">Submit Review</button>
+            <p class="review-form-note">Reviews appear here once they have been approved.</p>
+        </form>"#,
+        action,
+        moderation::MAX_NAME_LENGTH,
+        moderation::MAX_NAME_LENGTH,
+        moderation::MAX_REVIEW_LENGTH
+    )
+}
+
+/// Renders the moderation queue with approve and reject buttons posting
+/// to `{admin_base}/{id}/approve` and `/reject`.
+pub fn generate_moderation_html(pending: &[PendingReview], admin_base: &str) -> String {
+    let mut html = String::from(
+        r#"<section class="reviews-section-page">
+    <div class="reviews-header">
+        <h1 class="reviews-title">Review Moderation</h1>"#,
+    );
+    html.push_str(&format!(
+        "\n        <p class=\"reviews-tagline\">{} pending</p>\n    </div>\n    <div class=\"reviews-content\">",
+        pending.len()
+    ));
+    if pending.is_empty() {
+        html.push_str("\n        <p class=\"events-empty\">No reviews are waiting for moderation.</p>");
+    }
+    for review in pending {
+        html.push_str(&format!(
+            r#"
+        <div class="testimonial-card moderation-card">
+            {}<div class="testimonial-text">
+                <p>{}</p>
+            </div>
+            <div class="testimonial-author">
+                <span class="author-name">{}</span>
+                <span class="author-title">{}</span>
+                <span class="testimonial-meta">Submitted {}</span>
+            </div>
+            <div class="moderation-actions">
+                <form method="post" action="{base}/{id}/approve"><button type="submit" class="moderation-approve">Approve</button></form>
+                <form method="post" action="{base}/{id}/reject"><button type="submit" class="moderation-reject">Reject</button></form>
+            </div>
+        </div>"#,
+            with_line_break(rating_html(review.rating), "            "),
+            html_escape(&review.quote),
+            html_escape(&review.author),
+            html_escape(&review.title),
+            html_escape(&review.submitted),
+            base = admin_base,
+            id = url_encode(&review.id),
+        ));
+    }
+    html.push_str("\n    </div>\n</section>");
+    html
+}
+
+/// What the shop page offers next to each product.
+#[derive(Clone, Copy, Debug)]
+pub enum ShopActions<'a> {
+    /// Add to cart forms posting to this URL.
+    Cart(&'a str),
+    /// Links to the product on the shop at this URL, which takes orders.
+    Link(&'a str),
+    /// Products are shown, but cannot be ordered.
+    Disabled,
+}
+
+/// Price of a product, or the lowest of its variants' prices.
+fn product_price_html(product: &Product, currency: &str) -> String {
+    let prices: Vec<Price> = product.items().iter().map(|item| item.price()).collect();
+    let lowest = prices.iter().min().copied().unwrap_or_default();
+    if prices.iter().any(|price| *price != lowest) {
+        format!("From {}", lowest.display(currency))
+    } else {
+        lowest.display(currency)
+    }
+}
+
+fn product_actions_html(product: &Product, currency: &str, sold: &HashMap<ItemKey, u32>, actions: ShopActions) -> String {
+    let add_url = match actions {
+        ShopActions::Cart(add_url) => add_url,
+        ShopActions::Link(shop_url) => return format!(r##"<a href="{}#{}" class="product-add">Buy in the Shop</a>"##, html_escape(shop_url), html_escape(&product.slug)),
+        ShopActions::Disabled => return r#"<button type="button" class="product-add" disabled>Not Available Online</button>"#.to_string(),
+    };
+    let items = product.items();
+    if items.iter().all(|item| item.available(sold) == Some(0)) {
+        return r#"<button type="button" class="product-add" disabled>Sold Out</button>"#.to_string();
+    }
+
+    let variants = if product.variants.is_empty() {
+        String::new()
+    } else {
+        let options = items
+            .iter()
+            .filter_map(|item| item.variant.map(|variant| (item, variant)))
+            .map(|(item, variant)| {
+                let sold_out = item.available(sold) == Some(0);
+                format!(
+                    r#"<option value="{}"{}>{} - {}{}</option>"#,
+                    html_escape(&variant.slug),
+                    if sold_out { " disabled" } else { "" },
+                    html_escape(&variant.name),
+                    item.price().display(currency),
+                    if sold_out { " (sold out)" } else { "" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n                        ");
+        format!(
+            r#"
+                    <select name="variant" aria-label="Option">
+                        {}
+                    </select>"#,
+            options
+        )
+    };
+    format!(
+        r#"<form class="product-form" method="post" action="{}">
+                    <input type="hidden" name="product" value="{}">{}
+                    <input type="number" name="quantity" value="1" min="1" max="{}" aria-label="Quantity">
+                    <button type="submit" class="product-add">Add to Cart</button>
+                </form>"#,
+        add_url,
+        html_escape(&product.slug),
+        variants,
+        shop::MAX_QUANTITY
+    )
+}
+
+/// Renders the shop's products. `sold` is taken off their stock; images
+/// are loaded from under `asset_base`.
+pub fn generate_shop_html(catalog: &Catalog, sold: &HashMap<ItemKey, u32>, actions: ShopActions, asset_base: &str) -> String {
+    if catalog.products.is_empty() {
+        return r#"<p class="events-empty">Nothing is for sale yet.</p>"#.to_string();
+    }
+    let cards = catalog
+        .products
+        .iter()
+        .map(|product| {
+            let image = product
+                .image
+                .as_deref()
+                .map(|image| {
+                    format!(
+                        r#"<img src="{}/{}" alt="{}" class="product-image" loading="lazy">
+            "#,
+                        asset_base,
+                        html_escape(&url_encode_path(image)),
+                        html_escape(&product.name)
+                    )
+                })
+                .unwrap_or_default();
+            let description = product
+                .description
+                .as_deref()
+                .map(|description| format!("\n                <p class=\"product-description\">{}</p>", html_escape(description)))
+                .unwrap_or_default();
+            format!(
+                r#"<article class="product-card" id="{}">
+            {}<div class="product-info">
+                <span class="product-kind">{}</span>
+                <h2 class="product-name">{}</h2>{}
+                <p class="product-price">{}</p>
+                {}
+            </div>
+        </article>"#,
+                html_escape(&product.slug),
+                image,
+                product.kind.label(),
+                html_escape(&product.name),
+                description,
+                product_price_html(product, &catalog.currency),
+                product_actions_html(product, &catalog.currency, sold, actions)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n        ");
+
+    format!(r#"<div class="shop-grid">
+        {}
+    </div>"#, cards)
+}
+
+/// Renders the cart page: its lines, each with a quantity form posting to
+/// `{cart_base}/update`, and the checkout form posting to
+/// `{cart_base}/checkout`.
+pub fn generate_cart_html(cart: &Cart, catalog: &Catalog, cart_base: &str, shop_url: &str) -> String {
+    let mut html = String::from(
+        r#"<section class="shop-section-page">
+    <div class="shop-header">
+        <h1 class="shop-title">Your Cart</h1>
+    </div>
+    <div class="shop-content">"#,
+    );
+    let items = cart.items(catalog);
+    if items.is_empty() {
+        html.push_str(&format!(
+            "\n        <p class=\"events-empty\">Your cart is empty.</p>\n        <a href=\"{}\" class=\"cart-continue\">Continue shopping</a>\n    </div>\n</section>",
+            shop_url
+        ));
+        return html;
+    }
+
+    html.push_str(
+        r#"
+        <table class="cart-table">
+            <thead>
+                <tr><th>Item</th><th>Price</th><th>Quantity</th><th>Subtotal</th></tr>
+            </thead>
+            <tbody>"#,
+    );
+    for (item, quantity) in &items {
+        let key = item.key();
+        html.push_str(&format!(
+            r#"
+                <tr>
+                    <td>{}</td>
+                    <td>{}</td>
+                    <td>
+                        <form class="cart-quantity" method="post" action="{}/update">
+                            <input type="hidden" name="product" value="{}">
+                            <input type="hidden" name="variant" value="{}">
+                            <input type="number" name="quantity" value="{}" min="0" max="{}" aria-label="Quantity">
+                            <button type="submit">Update</button>
+                        </form>
+                    </td>
+                    <td>{}</td>
+                </tr>"#,
+            html_escape(&item.name()),
+            item.price().display(&catalog.currency),
+            cart_base,
+            html_escape(&key.product),
+            html_escape(&key.variant),
+            quantity,
+            shop::MAX_QUANTITY,
+            Price(item.price().0 * u64::from(*quantity)).display(&catalog.currency)
+        ));
+    }
+    html.push_str(&format!(
+        r#"
+            </tbody>
+            <tfoot>
+                <tr><th colspan="3">Total</th><td>{}</td></tr>
+            </tfoot>
+        </table>
+        <a href="{}" class="cart-continue">Continue shopping</a>
+        <form class="review-form checkout-form" method="post" action="{}/checkout">
+            <h2 class="testimonials-heading">Checkout</h2>
+            <div class="form-group">
+                <label for="checkout-name">Name</label>
+                <input type="text" id="checkout-name" name="name" required maxlength="{}" autocomplete="name">
+            </div>
+            <div class="form-group">
+                <label for="checkout-email">Email</label>
+                <input type="email" id="checkout-email" name="email" required maxlength="{}" autocomplete="email">
+            </div>
+            <div class="form-group">
+                <label for="checkout-address">Shipping address</label>
+                <textarea id="checkout-address" name="address" rows="4" required maxlength="{}" autocomplete="street-address"></textarea>
+            </div>
+            <div class="form-trap" aria-hidden="true">
+                <label for="checkout-website">Leave this empty</label>
+                <input type="text" id="checkout-website" name="website" tabindex="-1" autocomplete="off">
+            </div>
+            <button type="submit" class="submit-btn">Place Order</button>
+        </form>
+    </div>
+</section>"#,
+        cart.total(catalog).display(&catalog.currency),
+        shop_url,
+        cart_base,
+        orders::MAX_NAME_LENGTH,
+        orders::MAX_NAME_LENGTH,
+        orders::MAX_ADDRESS_LENGTH
+    ));
+    html
+}
+
+/// Scrolls the featured testimonials one slide at a time.
+const CAROUSEL_SCRIPT: &str = r#"<script>
+        document.querySelectorAll('.featured-testimonials').forEach(function (carousel) {
+            const track = carousel.querySelector('.featured-testimonials-track');
+            carousel.querySelectorAll('[data-scroll]').forEach(function (button) {
+                button.hidden = false;
+                button.addEventListener('click', function () {
+                    track.scrollBy({ left: Number(button.dataset.scroll) * track.clientWidth, behavior: 'smooth' });
+                });
+            });
+        });
+    </script>"#;
+
+/// Renders the home page carousel of featured testimonials, or nothing
+/// when none is featured.
+pub fn generate_featured_testimonials_html(testimonials: &[Testimonial], reviews_url: &str, asset_base: &str) -> String {
+    let slides: Vec<String> = testimonials
+        .iter()
+        .filter(|testimonial| testimonial.featured)
+        .map(|testimonial| {
+            format!(
+                r#"<figure class="featured-testimonial">
+                {}{}<blockquote><p>{}</p></blockquote>
+                <figcaption><span class="author-name">{}</span> <span class="author-title">{}</span></figcaption>
+            </figure>"#,
+                with_line_break(testimonial_photo_html(testimonial, asset_base), "                "),
+                with_line_break(rating_html(testimonial.rating), "                "),
+                html_escape(&testimonial.quote),
+                html_escape(&testimonial.author),
+                html_escape(&testimonial.title)
+            )
+        })
+        .collect();
+    if slides.is_empty() {
+        return String::new();
+    }
+
+    let controls = if slides.len() > 1 {
+        r#"
+        <button type="button" class="carousel-button carousel-prev" data-scroll="-1" aria-label="Previous review" hidden>‹</button>
+        <button type="button" class="carousel-button carousel-next" data-scroll="1" aria-label="Next review" hidden>›</button>"#
+    } else {
+        ""
+    };
+    let script = if slides.len() > 1 { format!("\n    {}", CAROUSEL_SCRIPT) } else { String::new() };
+    format!(
+        r#"<section class="featured-testimonials" aria-label="Featured reviews">
+        <h2 class="featured-testimonials-heading">What People Say</h2>
+        <div class="featured-testimonials-track">
+            {}
+        </div>{}
+        <a href="{}" class="featured-testimonials-more">Read all reviews →</a>
+    </section>{}"#,
+        slides.join("\n            "),
+        controls,
+        reviews_url,
+        script
+    )
+}
+
+/// How `generate_media_embeds` renders YouTube videos.
+#[derive(Clone, Copy)]
+pub enum EmbedStyle<'a> {
+    /// A full YouTube player iframe per video.
+    Iframe,
+    /// A thumbnail facade; the function maps a video to its thumbnail URL.
+    Lite(&'a dyn Fn(&YoutubeTarget) -> String),
+}
+
+/// Swaps a lite embed for a `youtube-nocookie.com` player when clicked.
+const LITE_EMBED_SCRIPT: &str = r#"<script>
+                document.addEventListener('click', function (event) {
+                    const facade = event.target.closest('.youtube-lite');
+                    if (!facade) return;
+                    event.preventDefault();
+                    const iframe = document.createElement('iframe');
+                    iframe.src = facade.dataset.embed;
+                    iframe.title = facade.getAttribute('aria-label');
+                    iframe.allow = 'autoplay; encrypted-media; picture-in-picture';
+                    iframe.allowFullscreen = true;
+                    iframe.setAttribute('frameborder', '0');
+                    const wrapper = document.createElement('div');
+                    wrapper.className = 'youtube-video-wrapper';
+                    wrapper.appendChild(iframe);
+                    facade.replaceWith(wrapper);
+                });
+            </script>"#;
+
+fn generate_player(item: &MediaItem, style: EmbedStyle) -> String {
+    let title_attr = item
+        .title
+        .as_deref()
+        .map(|title| format!(r#" title="{}""#, html_escape(title)))
+        .unwrap_or_default();
+    let iframe = |class: &str, height: Option<u32>, allow: &str| {
+        let height_attr = height.map(|height| format!(r#" height="{}""#, height)).unwrap_or_default();
+        format!(
+            r#"<div class="{}">
+                    <iframe src="{}"{}{} allow="{}" loading="lazy" frameborder="0" allowfullscreen></iframe>
+                </div>"#,
+            class,
+            html_escape(&item.embed_url().unwrap_or_default()),
+            title_attr,
+            height_attr,
+            allow
+        )
+    };
+    let start_fragment = item.start.map(|start| format!("#t={}", start)).unwrap_or_default();
+
+    match (&item.source, style) {
+        (MediaSource::Youtube(_), EmbedStyle::Iframe) => format!(
+            r#"<div class="youtube-video-wrapper">
+                    <iframe src="{}"{} frameborder="0" allowfullscreen></iframe>
+                </div>"#,
+            html_escape(&item.embed_url().unwrap_or_default()),
+            title_attr
+        ),
+        (MediaSource::Youtube(target), EmbedStyle::Lite(thumbnail_url)) => format!(
+            r#"<a class="youtube-video-wrapper youtube-lite" href="{}" data-embed="{}" aria-label="{}">
+                    <img src="{}" alt="" loading="lazy">
+                    <span class="youtube-lite-play"></span>
+                </a>"#,
+            html_escape(&item.watch_url().unwrap_or_default()),
+            html_escape(&item.nocookie_embed_url().unwrap_or_default()),
+            html_escape(&format!("Play {}", item.title.as_deref().unwrap_or("video"))),
+            html_escape(&thumbnail_url(target))
+        ),
+        (MediaSource::Vimeo(_), _) => iframe("youtube-video-wrapper", None, "fullscreen; picture-in-picture"),
+        (MediaSource::SoundCloud(_), _) => iframe("media-audio-embed", Some(166), "autoplay"),
+        (MediaSource::Spotify { kind, .. }, _) => {
+            let height = if matches!(kind.as_str(), "track" | "episode") { 152 } else { 352 };
+            iframe("media-audio-embed", Some(height), "autoplay; clipboard-write; encrypted-media; fullscreen; picture-in-picture")
+        },
+        (MediaSource::Bandcamp(url), _) => match item.embed_url() {
+            Some(_) => iframe("media-audio-embed", Some(120), "autoplay"),
+            None => format!(
+                r#"<a class="media-link" href="{}" target="_blank" rel="noopener">Listen on Bandcamp</a>"#,
+                html_escape(url)
+            ),
+        },
+        (MediaSource::Audio(url), _) => format!(
+            r#"<audio class="media-audio" src="{}{}" controls preload="none"></audio>"#,
+            html_escape(url),
+            start_fragment
+        ),
+        (MediaSource::Video(url), _) => format!(
+            r#"<video class="media-video" src="{}{}" controls preload="metadata"></video>"#,
+            html_escape(url),
+            start_fragment
+        ),
+    }
+}
+
+fn generate_media_embed(item: &MediaItem, style: EmbedStyle) -> String {
+    let player = generate_player(item, style);
+    if !item.has_details() {
+        return player;
+    }
+
+    let mut caption = String::new();
+    if let Some(title) = &item.title {
+        caption.push_str(&format!(r#"<h4 class="media-title">{}</h4>"#, html_escape(title)));
+    }
+    if let Some(description) = &item.description {
+        caption.push_str(&format!(r#"<p class="media-description">{}</p>"#, html_escape(description)));
+    }
+
+    format!(
+        r#"<figure class="media-card{}">
+                {}
+                <figcaption>{}</figcaption>
+            </figure>"#,
+        if item.featured { " featured" } else { "" },
+        player,
+        caption
+    )
+}
+
+/// Renders media groups for the `.youtube-videos-grid` on the music and
+/// acting pages. Unsectioned items sit directly in the grid; each section
+/// gets a heading and a nested grid spanning the full width.
+pub fn generate_media_embeds(groups: &[MediaGroup], style: EmbedStyle) -> String {
+    let html = groups
+        .iter()
+        .map(|group| {
+            let embeds = group
+                .items
+                .iter()
+                .map(|item| generate_media_embed(item, style))
+                .collect::<Vec<_>>()
+                .join("\n");
+            match &group.section {
+                Some(section) => format!(
+                    r#"<section class="media-group">
+                <h3 class="media-group-title">{}</h3>
+                <div class="youtube-videos-grid">
+                {}
+                </div>
+            </section>"#,
+                    html_escape(section),
+                    embeds
+                ),
+                None => embeds,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let has_youtube = groups
+        .iter()
+        .flat_map(|group| &group.items)
+        .any(|item| matches!(item.source, MediaSource::Youtube(_)));
+    match style {
+        EmbedStyle::Lite(_) if has_youtube => format!("{}\n{}", html, LITE_EMBED_SCRIPT),
+        _ => html,
+    }
+}
+
+fn release_cover_html(release: &Release, asset_base: &str, class: &str) -> String {
+    match &release.cover {
+        Some(cover) => format!(
+            r#"<img src="{}/{}" alt="{} Cover" class="{}" loading="lazy">"#,
+            asset_base,
+            html_escape(&url_encode_path(cover)),
+            html_escape(&release.title),
+            class
+        ),
+        None => format!(r#"<div class="{} release-cover-placeholder">🎵</div>"#, class),
+    }
+}
+
+/// One line summary of a release: kind, date, track count and runtime.
+fn release_meta(release: &Release) -> String {
+    let mut parts = vec![release.kind.label().to_string()];
+    if let Some(released) = &release.released {
+        parts.push(discography::display_date(released));
+    }
+    if !release.tracks.is_empty() {
+        let count = release.tracks.len();
+        parts.push(format!("{} track{}", count, if count == 1 { "" } else { "s" }));
+    }
+    if let Some(total) = release.total_seconds() {
+        parts.push(discography::format_duration(total));
+    }
+    parts.join(" · ")
+}
+
+/// Renders the discography grid on the music page. Each card links to
+/// `{page_base}/{slug}/`; covers are loaded from under `asset_base`.
+pub fn generate_discography_html(releases: &[Release], page_base: &str, asset_base: &str) -> String {
+    let cards = releases
+        .iter()
+        .map(|release| {
+            format!(
+                r#"<a href="{}/{}/" class="release-card">
+                    {}
+                    <span class="release-card-title">{}</span>
+                    <span class="release-card-meta">{}</span>
+                </a>"#,
+                page_base,
+                release.slug,
+                release_cover_html(release, asset_base, "release-card-cover"),
+                html_escape(&release.title),
+                html_escape(&release_meta(release))
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n                ");
+
+    format!(r#"<div class="discography-grid">
+                {}
+            </div>"#, cards)
+}
+
+/// Renders the detail page content for one release. `music_url` is the
+/// music page the back link points to.
+pub fn generate_release_html(release: &Release, music_url: &str, asset_base: &str) -> String {
+    let links = release
+        .links
+        .iter()
+        .map(|link| {
+            format!(
+                r#"<a href="{}" target="_blank" class="stream-btn {}">{}</a>"#,
+                html_escape(&link.url),
+                html_escape(&link.platform()),
+                html_escape(&link.label)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n                        ");
+
+    let tracks = release
+        .tracks
+        .iter()
+        .enumerate()
+        .map(|(index, track)| {
+            let (open, close) = match &track.url {
+                Some(url) => (format!(r#"<a href="{}" target="_blank" class="track-item">"#, html_escape(url)), "</a>"),
+                None => (r#"<div class="track-item">"#.to_string(), "</div>"),
+            };
+            let note = track
+                .note
+                .as_deref()
+                .map(|note| format!(r#"<span class="track-genre">{}</span>"#, html_escape(note)))
+                .unwrap_or_default();
+            let duration = track
+                .duration
+                .as_deref()
+                .map(|duration| format!(r#"<span class="track-duration">{}</span>"#, html_escape(duration)))
+                .unwrap_or_default();
+            format!(
+                r#"{}
+                    <span class="track-number">{:02}</span>
+                    <span class="track-info">
+                        <span class="track-name">{}</span>
+                        {}
+                    </span>
+                    {}
+                {}"#,
+                open,
+                index + 1,
+                html_escape(&track.title),
+                note,
+                duration,
+                close
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n                ");
+
+    let lyrics = release
+        .tracks
+        .iter()
+        .filter_map(|track| {
+            let lyrics = track.lyrics.as_deref()?;
+            Some(format!(
+                r#"<details class="track-lyrics">
+                    <summary>{}</summary>
+                    <p>{}</p>
+                </details>"#,
+                html_escape(&track.title),
+                html_escape(lyrics.trim()).replace('\n', "<br>\n")
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let credits = release
+        .credits
+        .iter()
+        .map(|credit| format!("<dt>{}</dt><dd>{}</dd>", html_escape(&credit.role), html_escape(&credit.name)))
+        .collect::<Vec<_>>();
+
+    let mut html = format!(
+        r#"<section class="music-section-page release-page">
+    <div class="music-content">
+        <a href="{}" class="release-back-link">← Back to Music</a>
+        <div class="album-feature">
+            <div class="album-feature-content">
+                <div class="album-feature-image">
+                    {}
+                </div>
+                <div class="album-feature-info">
+                    <span class="new-release-badge">{}</span>
+                    <h1 class="release-title">{}</h1>
+                    <p class="release-meta">{}</p>"#,
+        music_url,
+        release_cover_html(release, asset_base, "featured-album-cover"),
+        release.kind.label(),
+        html_escape(&release.title),
+        html_escape(&release_meta(release))
+    );
+    if let Some(description) = &release.description {
+        html.push_str(&format!("\n                    <p class=\"album-description\">{}</p>", html_escape(description)));
+    }
+    if !links.is_empty() {
+        html.push_str(&format!("\n                    <div class=\"streaming-links\">\n                        {}\n                    </div>", links));
+    }
+    html.push_str("\n                </div>\n            </div>\n        </div>");
+
+    if !tracks.is_empty() {
+        html.push_str(&format!(
+            "\n\n        <div class=\"track-listing\">\n            <h2>Track Listing</h2>\n            <div class=\"tracks-grid\">\n                {}\n            </div>\n        </div>",
+            tracks
+        ));
+    }
+    if !lyrics.is_empty() {
+        html.push_str(&format!(
+            "\n\n        <div class=\"release-lyrics\">\n            <h2>Lyrics</h2>\n            {}\n        </div>",
+            lyrics.join("\n            ")
+        ));
+    }
+    if !credits.is_empty() {
+        html.push_str(&format!(
+            "\n\n        <div class=\"release-credits\">\n            <h2>Credits</h2>\n            <dl>{}</dl>\n        </div>",
+            credits.join("")
+        ));
+    }
+    html.push_str("\n    </div>\n</section>");
+    html
+}
+
+/// Renders the résumé section on the acting page, with a download link when
+/// a PDF is available at `pdf_url`.
+pub fn generate_resume_html(resume: &ActingResume, pdf_url: Option<&str>) -> String {
+    let mut html = String::from("<section class=\"resume-section\">\n    <div class=\"resume-header\">\n        <h2>Résumé</h2>");
+    if let Some(url) = pdf_url {
+        html.push_str(&format!(
+            "\n        <a href=\"{}\" class=\"resume-download\" download>Download PDF</a>",
+            html_escape(url)
+        ));
+    }
+    html.push_str("\n    </div>");
+
+    let stats = resume
+        .stats
+        .entries()
+        .iter()
+        .map(|(label, value)| format!("<div><dt>{}</dt><dd>{}</dd></div>", label, html_escape(value)))
+        .collect::<Vec<_>>();
+    if !stats.is_empty() {
+        html.push_str(&format!("\n    <dl class=\"resume-stats\">{}</dl>", stats.join("")));
+    }
+
+    for (kind, credits) in resume.credits_by_type() {
+        let rows = credits
+            .iter()
+            .map(|credit| {
+                format!(
+                    "<tr><td class=\"credit-project\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    html_escape(&credit.project),
+                    html_escape(&credit.role),
+                    html_escape(&credit.director_company()),
+                    credit.year.map(|year| year.to_string()).unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n            ");
+        html.push_str(&format!(
+            r#"
+    <h3 class="resume-type">{}</h3>
+    <table class="credits-table">
+        <thead><tr><th>Project</th><th>Role</th><th>Director / Company</th><th>Year</th></tr></thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>"#,
+            html_escape(kind),
+            rows
+        ));
+    }
+
+    if !resume.training.is_empty() {
+        let items = resume.training.iter().map(|entry| format!("<li>{}</li>", html_escape(entry))).collect::<Vec<_>>();
+        html.push_str(&format!("\n    <h3 class=\"resume-type\">Training</h3>\n    <ul class=\"resume-training\">{}</ul>", items.join("")));
+    }
+    if !resume.skills.is_empty() {
+        let items = resume.skills.iter().map(|skill| format!("<li>{}</li>", html_escape(skill))).collect::<Vec<_>>();
+        html.push_str(&format!("\n    <h3 class=\"resume-type\">Special Skills</h3>\n    <ul class=\"resume-skills\">{}</ul>", items.join("")));
+    }
+    html.push_str("\n</section>");
+    html
+}
+
+fn generate_event_card(event: &Event, past: bool) -> String {
+    let date = event.start.date();
+    let mut details = String::new();
+    if let Some(category) = &event.category {
+        details.push_str(&format!("\n        <span class=\"event-category\">{}</span>", html_escape(category)));
+    }
+    details.push_str(&format!(
+        "\n        <h3 class=\"event-title\">{}</h3>\n        <p class=\"event-when\">{}</p>",
+        html_escape(&event.title),
+        html_escape(&event.display_when())
+    ));
+    let location = [&event.venue, &event.address]
+        .into_iter()
+        .flatten()
+        .map(|part| html_escape(part))
+        .collect::<Vec<_>>();
+    if !location.is_empty() {
+        details.push_str(&format!("\n        <p class=\"event-venue\">{}</p>", location.join("<br>")));
+    }
+    if let Some(description) = &event.description {
+        details.push_str(&format!("\n        <p class=\"event-description\">{}</p>", html_escape(description)));
+    }
+    if let Some(link) = &event.link
+        && !past
+    {
+        details.push_str(&format!(
+            "\n        <a href=\"{}\" target=\"_blank\" class=\"event-link\">Details &amp; Tickets</a>",
+            html_escape(link)
+        ));
+    }
+
+    format!(
+        r#"<article class="event-card{}">
+    <div class="event-date"><span class="event-month">{}</span><span class="event-day">{}</span></div>
+    <div class="event-details">{}
+    </div>
+</article>"#,
+        if past { " past" } else { "" },
+        date.format("%b"),
+        date.format("%-d"),
+        details
+    )
+}
+
+/// Renders the events page: upcoming events, then an archive of past ones.
+pub fn generate_events_html(upcoming: &[&Event], past: &[&Event], ics_url: &str) -> String {
+    let mut html = format!(
+        r#"<div class="events-actions">
+    <a href="{}" class="events-subscribe">📅 Subscribe to the calendar</a>
+</div>
+<h2 class="events-heading">Upcoming</h2>"#,
+        ics_url
+    );
+    if upcoming.is_empty() {
+        html.push_str("\n<p class=\"events-empty\">No upcoming events right now. Check back soon!</p>");
+    } else {
+        let cards = upcoming.iter().map(|event| generate_event_card(event, false)).collect::<Vec<_>>();
+        html.push_str(&format!("\n<div class=\"events-list\">\n{}\n</div>", cards.join("\n")));
+    }
+    if !past.is_empty() {
+        let cards = past.iter().map(|event| generate_event_card(event, true)).collect::<Vec<_>>();
+        html.push_str(&format!(
+            "\n<details class=\"events-archive\">\n<summary>Past Events ({})</summary>\n<div class=\"events-list\">\n{}\n</div>\n</details>",
+            past.len(),
+            cards.join("\n")
+        ));
+    }
+    html
+}
+
+/// Renders the home page "next up" widget, or nothing without an upcoming
+/// event.
+pub fn generate_next_event_html(next: Option<&Event>, events_url: &str) -> String {
+    let Some(event) = next else {
+        return String::new();
+    };
+    let venue = event
+        .venue
+        .as_deref()
+        .map(|venue| format!(" · {}", html_escape(venue)))
+        .unwrap_or_default();
+    format!(
+        r#"<a href="{}" class="next-event">
+        <span class="next-event-label">Next Up</span>
+        <span class="next-event-title">{}</span>
+        <span class="next-event-when">{}{}</span>
+    </a>"#,
+        events_url,
+        html_escape(&event.title),
+        html_escape(&event.display_when()),
+        venue
+    )
+}
+
+fn post_cover_html(post: &Post, asset_base: &str, class: &str) -> String {
+    post.cover
+        .as_deref()
+        .map(|cover| {
+            format!(
+                r#"<img src="{}/{}" alt="" class="{}" loading="lazy">"#,
+                asset_base,
+                html_escape(&url_encode_path(cover)),
+                class
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// Date, draft badge and tag links of a post.
+fn post_meta_html(post: &Post, news_base: &str) -> String {
+    let mut meta = format!(
+        r#"<time datetime="{}">{}</time>"#,
+        post.date.format("%Y-%m-%d"),
+        post.display_date()
+    );
+    if post.draft {
+        meta.push_str(r#" <span class="post-draft-badge">Draft</span>"#);
+    }
+    for tag in &post.tags {
+        meta.push_str(&format!(
+            r#" <a href="{}/tags/{}/" class="post-tag">#{}</a>"#,
+            news_base,
+            discography::slugify(tag),
+            html_escape(tag)
+        ));
+    }
+    meta
+}
+
+/// Renders a `/news/` list page. `page` and `page_count` drive the
+/// pagination links; page 1 lives at `{news_base}/`, later pages at
+/// `{news_base}/page/{n}/`.
+pub fn generate_news_html(posts: &[Post], heading: Option<&str>, page: usize, page_count: usize, news_base: &str, asset_base: &str) -> String {
+    let mut html = String::new();
+    if let Some(heading) = heading {
+        html.push_str(&format!(
+            "<div class=\"news-list-header\">\n    <h2>{}</h2>\n    <a href=\"{}/\" class=\"news-all-link\">All news</a>\n</div>\n",
+            html_escape(heading),
+            news_base
+        ));
+    }
+
+    if posts.is_empty() {
+        html.push_str(r#"<p class="news-empty">No news yet. Check back soon!</p>"#);
+        return html;
+    }
+
+    let cards = posts
+        .iter()
+        .map(|post| {
+            format!(
+                r#"<article class="post-card">
+    {}
+    <div class="post-card-body">
+        <h2 class="post-card-title"><a href="{}/{}/">{}</a></h2>
+        <p class="post-meta">{}</p>
+        <p class="post-summary">{}</p>
+        <a href="{}/{}/" class="post-read-more">Read more →</a>
+    </div>
+</article>"#,
+                post_cover_html(post, asset_base, "post-card-cover"),
+                news_base,
+                post.slug,
+                html_escape(&post.title),
+                post_meta_html(post, news_base),
+                html_escape(&post.summary),
+                news_base,
+                post.slug
+            )
+        })
+        .collect::<Vec<_>>();
+    html.push_str(&format!("<div class=\"news-list\">\n{}\n</div>", cards.join("\n")));
+
+    if page_count > 1 {
+        let page_url = |page: usize| {
+            if page == 1 {
+                format!("{}/", news_base)
+            } else {
+                format!("{}/page/{}/", news_base, page)
+            }
+        };
+        let newer = if page > 1 {
+            format!(r#"<a href="{}" class="pagination-newer" rel="prev">← Newer</a>"#, page_url(page - 1))
+        } else {
+            String::new()
+        };
+        let older = if page < page_count {
+            format!(r#"<a href="{}" class="pagination-older" rel="next">Older →</a>"#, page_url(page + 1))
+        } else {
+            String::new()
+        };
+        html.push_str(&format!(
+            "\n<nav class=\"pagination\">{}<span class=\"pagination-status\">Page {} of {}</span>{}</nav>",
+            newer, page, page_count, older
+        ));
+    }
+    html
+}
+
+/// Renders a single news post.
+pub fn generate_post_html(post: &Post, news_base: &str, asset_base: &str) -> String {
+    format!(
+        r#"<section class="news-section-page">
+    <article class="post">
+        <a href="{}/" class="post-back-link">← All news</a>
+        <h1 class="post-title">{}</h1>
+        <p class="post-meta">{}</p>
+        {}
+        <div class="post-body">
+{}
+        </div>
+    </article>
+</section>"#,
+        news_base,
+        html_escape(&post.title),
+        post_meta_html(post, news_base),
+        post_cover_html(post, asset_base, "post-cover"),
+        post.body_html.trim_end()
+    )
+}
+
+/// Renders a JSON array of image URLs.
+pub fn generate_images_json(images: &[String]) -> String {
+    let images_json: Vec<String> = images.iter().map(|img| format!("\"{}\"", img)).collect();
+    format!("[{}]", images_json.join(", "))
+}
+
+pub fn generate_categories_json(categories: &[(String, CategoryData)]) -> String {
+    let mut json_parts = Vec::new();
+
+    for (key, data) in categories {
+        let escaped_subtitle = data.subtitle
+            .replace("\\", "\\\\")
+            .replace("\"", "\\\"")
+            .replace("\n", " ")
+            .replace("\r", "");
+
+        let links_json: Vec<String> = data.links.iter()
+            .map(|(k, v)| format!("\"{}\": \"{}\"", k, v.replace("\"", "\\\"")))
+            .collect();
+
+        let background_json = match &data.background {
+            Some(bg) => format!(", \"background\": \"{}\"", bg),
+            None => String::new(),
+        };
+
+        json_parts.push(format!(
+            "\"{}\": {{\"title\": \"{}\", \"subtitle\": \"{}\", \"images\": {}, \"links\": {{{}}}{}}}",
+            key,
+            data.title,
+            escaped_subtitle,
+            generate_images_json(&data.images),
+            links_json.join(", "),
+            background_json
+        ));
+    }
+
+    format!("{{{}}}", json_parts.join(", "))
+}
+
+/// Fills in the modeling page template. The category `initial` is shown
+/// first; without it the first category is.
+pub fn generate_modeling_html(template: &str, categories: &[(String, CategoryData)], initial: Option<&str>) -> String {
+    let initial_json = initial
+        .map(|key| serde_json::to_string(key).expect("category name is valid JSON"))
+        .unwrap_or_else(|| "null".to_string());
+    template
+        .replace("{{CATEGORIES_JSON}}", &generate_categories_json(categories))
+        .replace("{{INITIAL_CATEGORY}}", &initial_json)
+}
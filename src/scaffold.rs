@@ -0,0 +1,55 @@
+//! New content for `generate-static new`: news posts and modeling
+//! categories, laid out the way the site reads them.
+
+use chrono::NaiveDate;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::discography::slugify;
+use crate::posts::RESERVED_SLUGS;
+
+/// Writes `posts/{slug}.md` for a draft post titled `title`, returning its
+/// path. An existing post is never overwritten.
+pub fn new_post(content_dir: &Path, title: &str, date: NaiveDate) -> Result<PathBuf, String> {
+    let slug = slugify(title);
+    if slug.is_empty() || RESERVED_SLUGS.contains(&slug.as_str()) {
+        return Err(format!("{:?} does not make a usable post slug", title));
+    }
+
+    let posts_dir = content_dir.join("posts");
+    let path = posts_dir.join(format!("{}.md", slug));
+    if path.exists() {
+        return Err(format!("{} already exists", path.display()));
+    }
+
+    // Quoted by the YAML writer when the title needs it
+    let title_yaml = serde_yaml::to_string(title).map_err(|e| format!("invalid title {:?}: {}", title, e))?;
+    let post = format!(
+        "---\ntitle: {}\ndate: {}\ntags: []\ndraft: true\n---\nWrite the post here. Remove `draft: true` above to publish it.\n",
+        title_yaml.trim_end(),
+        date.format("%Y-%m-%d")
+    );
+    fs::create_dir_all(&posts_dir).map_err(|e| format!("failed to create {}: {}", posts_dir.display(), e))?;
+    fs::write(&path, post).map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// Creates `modeling/{name}/images/` with an empty `Links.txt`, returning
+/// the images folder.
+pub fn new_category(content_dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("{:?} is not a usable category folder name", name));
+    }
+
+    let category_dir = content_dir.join("modeling").join(name);
+    if category_dir.exists() {
+        return Err(format!("{} already exists", category_dir.display()));
+    }
+
+    let images_dir = category_dir.join("images");
+    fs::create_dir_all(&images_dir).map_err(|e| format!("failed to create {}: {}", images_dir.display(), e))?;
+    let links_path = images_dir.join("Links.txt");
+    fs::write(&links_path, "").map_err(|e| format!("failed to write {}: {}", links_path.display(), e))?;
+    Ok(images_dir)
+}
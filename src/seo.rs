@@ -0,0 +1,362 @@
+//! Search and social metadata for the page `<head>`: descriptions, Open
+//! Graph and Twitter cards, canonical URLs and JSON-LD structured data.
+
+use serde_json::{json, Value};
+
+use crate::content::{self, CategoryData, Testimonial};
+use crate::discography::{self, slugify, Release};
+use crate::media::{MediaGroup, MediaSource, YoutubeTarget};
+use crate::posts::Post;
+use crate::render::{html_escape, url_encode, url_encode_path};
+use crate::settings::SiteSettings;
+
+pub const SITE_NAME: &str = "4AmberTechel";
+/// The person the site is about, as named in feeds and structured data.
+pub const PERSON_NAME: &str = "Amber Techel";
+
+/// Metadata of one page. URLs are absolute.
+#[derive(Clone, Debug, Default)]
+pub struct PageMeta {
+    pub description: Option<String>,
+    /// Share image for link previews.
+    pub image: Option<String>,
+    pub canonical: Option<String>,
+    /// Open Graph type; `website` when unset.
+    pub og_type: Option<&'static str>,
+    /// Twitter account the site belongs to, e.g. `@AmberTechel`.
+    pub twitter_site: Option<String>,
+    /// Structured data objects, one `<script>` each.
+    pub json_ld: Vec<Value>,
+    /// Asks search engines not to index the page, e.g. error pages.
+    pub noindex: bool,
+    /// Route of the page, e.g. `/bio/`, which picks its theme override.
+    pub route: Option<String>,
+}
+
+impl PageMeta {
+    /// Metadata for pages that should stay out of search results.
+    pub fn noindex() -> Self {
+        PageMeta { noindex: true, ..PageMeta::default() }
+    }
+
+    /// The tags that go after `<title>`.
+    pub fn head_html(&self, title: &str) -> String {
+        let mut tags = Vec::new();
+        let meta_name = |name: &str, content: &str| format!(r#"<meta name="{}" content="{}">"#, name, html_escape(content));
+        let meta_property = |property: &str, content: &str| format!(r#"<meta property="{}" content="{}">"#, property, html_escape(content));
+
+        if self.noindex {
+            tags.push(meta_name("robots", "noindex"));
+        }
+        if let Some(description) = &self.description {
+            tags.push(meta_name("description", description));
+        }
+        if let Some(canonical) = &self.canonical {
+            tags.push(format!(r#"<link rel="canonical" href="{}">"#, html_escape(canonical)));
+        }
+
+        tags.push(meta_property("og:type", self.og_type.unwrap_or("website")));
+        tags.push(meta_property("og:site_name", SITE_NAME));
+        tags.push(meta_property("og:title", title));
+        if let Some(description) = &self.description {
+            tags.push(meta_property("og:description", description));
+        }
+        if let Some(canonical) = &self.canonical {
+            tags.push(meta_property("og:url", canonical));
+        }
+        if let Some(image) = &self.image {
+            tags.push(meta_property("og:image", image));
+        }
+
+        tags.push(meta_name("twitter:card", if self.image.is_some() { "summary_large_image" } else { "summary" }));
+        if let Some(site) = &self.twitter_site {
+            tags.push(meta_name("twitter:site", site));
+        }
+        tags.push(meta_name("twitter:title", title));
+        if let Some(description) = &self.description {
+            tags.push(meta_name("twitter:description", description));
+        }
+        if let Some(image) = &self.image {
+            tags.push(meta_name("twitter:image", image));
+        }
+
+        for object in &self.json_ld {
+            // `</` would end the script element early
+            let json = serde_json::to_string(object).expect("JSON-LD is valid JSON").replace("</", r"<\/");
+            tags.push(format!(r#"<script type="application/ld+json">{}</script>"#, json));
+        }
+
+        tags.join("\n    ")
+    }
+}
+
+/// Builds page metadata from the site settings. Canonical and share image
+/// URLs point at the published static site, so they are only set when
+/// `site_url` is configured.
+pub struct Seo<'a> {
+    settings: &'a SiteSettings,
+    site: Option<String>,
+}
+
+impl<'a> Seo<'a> {
+    pub fn new(settings: &'a SiteSettings) -> Self {
+        Seo {
+            settings,
+            site: settings.site_url.as_deref().map(|url| url.trim_end_matches('/').to_string()),
+        }
+    }
+
+    /// Absolute URL of a page path such as `/bio/`.
+    pub fn page_url(&self, path: &str) -> Option<String> {
+        self.site.as_ref().map(|site| format!("{}{}", site, path))
+    }
+
+    /// Absolute URL of a file given relative to the content directory.
+    pub fn asset_url(&self, path: &str) -> Option<String> {
+        self.site.as_ref().map(|site| format!("{}/{}", site, url_encode_path(path)))
+    }
+
+    fn person(&self) -> Value {
+        let mut person = json!({ "@type": "Person", "name": PERSON_NAME });
+        if let Some(url) = self.page_url("/bio/") {
+            person["url"] = json!(url);
+        }
+        person
+    }
+
+    /// Metadata for the page at `route`: the description and share image
+    /// from its `pages` entry in `site.yaml`, falling back to the site-wide
+    /// ones.
+    pub fn page(&self, route: &str) -> PageMeta {
+        let page = self.settings.pages.get(route);
+        let description = page
+            .and_then(|page| page.description.clone())
+            .or_else(|| self.settings.description.clone());
+        let image = page
+            .and_then(|page| page.image.as_deref())
+            .or(self.settings.share_image.as_deref())
+            .and_then(|image| self.asset_url(image));
+        PageMeta {
+            description,
+            image,
+            canonical: self.page_url(route),
+            og_type: None,
+            twitter_site: self.settings.twitter.clone(),
+            json_ld: Vec::new(),
+            noindex: false,
+            route: Some(route.to_string()),
+        }
+    }
+
+    /// Uses `image` as the share image unless `site.yaml` picks one for
+    /// the page.
+    fn page_with_image(&self, route: &str, image: Option<String>) -> PageMeta {
+        let mut meta = self.page(route);
+        if self.settings.pages.get(route).and_then(|page| page.image.as_ref()).is_none()
+            && let Some(image) = image
+        {
+            meta.image = Some(image);
+        }
+        meta
+    }
+
+    pub fn home(&self) -> PageMeta {
+        let mut meta = self.page("/");
+        let mut website = json!({ "@context": "https://schema.org", "@type": "WebSite", "name": SITE_NAME });
+        if let Some(url) = self.page_url("/") {
+            website["url"] = json!(url);
+        }
+        meta.json_ld.push(website);
+        meta
+    }
+
+    /// The bio page, described as a `Person`.
+    pub fn bio(&self) -> PageMeta {
+        let mut meta = self.page("/bio/");
+        meta.og_type = Some("profile");
+        let mut person = self.person();
+        person["@context"] = json!("https://schema.org");
+        if let Some(description) = &meta.description {
+            person["description"] = json!(description);
+        }
+        if let Some(image) = &meta.image {
+            person["image"] = json!(image);
+        }
+        if !self.settings.same_as.is_empty() {
+            person["sameAs"] = json!(self.settings.same_as);
+        }
+        meta.json_ld.push(person);
+        meta
+    }
+
+    /// A media page, with a `VideoObject` per YouTube video.
+    pub fn media(&self, route: &str, groups: &[MediaGroup]) -> PageMeta {
+        let mut meta = self.page(route);
+        for item in groups.iter().flat_map(|group| &group.items) {
+            let MediaSource::Youtube(YoutubeTarget::Video(id)) = &item.source else {
+                continue;
+            };
+            let mut video = json!({
+                "@context": "https://schema.org",
+                "@type": "VideoObject",
+                "name": item.title.clone().unwrap_or_else(|| format!("{} video", PERSON_NAME)),
+                "thumbnailUrl": format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", id),
+                "embedUrl": item.embed_url(),
+                "url": item.watch_url(),
+            });
+            if let Some(description) = &item.description {
+                video["description"] = json!(description);
+            }
+            if let Some(date) = item.date {
+                video["uploadDate"] = json!(date.format("%Y-%m-%d").to_string());
+            }
+            meta.json_ld.push(video);
+        }
+        meta
+    }
+
+    /// The music page: its videos plus a `MusicAlbum` per release.
+    pub fn music(&self, groups: &[MediaGroup], releases: &[Release]) -> PageMeta {
+        let mut meta = self.media("/music/", groups);
+        meta.json_ld.extend(releases.iter().map(|release| self.music_album(release)));
+        meta
+    }
+
+    fn music_album(&self, release: &Release) -> Value {
+        let release_type = match release.kind {
+            discography::ReleaseKind::Album => "AlbumRelease",
+            discography::ReleaseKind::Ep => "EPRelease",
+            discography::ReleaseKind::Single => "SingleRelease",
+        };
+        let mut album = json!({
+            "@context": "https://schema.org",
+            "@type": "MusicAlbum",
+            "name": release.title,
+            "albumReleaseType": format!("https://schema.org/{}", release_type),
+            "byArtist": self.person(),
+            "numTracks": release.tracks.len(),
+        });
+        if let Some(url) = self.page_url(&format!("/music/releases/{}/", release.slug)) {
+            album["url"] = json!(url);
+        }
+        if let Some(image) = release.cover.as_deref().and_then(|cover| self.asset_url(cover)) {
+            album["image"] = json!(image);
+        }
+        if let Some(released) = &release.released {
+            album["datePublished"] = json!(released);
+        }
+        if let Some(description) = &release.description {
+            album["description"] = json!(description);
+        }
+        if !release.tracks.is_empty() {
+            let tracks: Vec<Value> = release
+                .tracks
+                .iter()
+                .map(|track| {
+                    let mut recording = json!({ "@type": "MusicRecording", "name": track.title });
+                    if let Some(seconds) = track.duration.as_deref().and_then(discography::parse_duration) {
+                        recording["duration"] = json!(format!("PT{}M{}S", seconds / 60, seconds % 60));
+                    }
+                    recording
+                })
+                .collect();
+            album["track"] = json!(tracks);
+        }
+        album
+    }
+
+    /// A release detail page, shared with its cover.
+    pub fn release(&self, release: &Release) -> PageMeta {
+        let route = format!("/music/releases/{}/", release.slug);
+        let cover = release.cover.as_deref().and_then(|cover| self.asset_url(cover));
+        let mut meta = self.page_with_image(&route, cover);
+        meta.og_type = Some("music.album");
+        if let Some(description) = &release.description {
+            meta.description = Some(description.clone());
+        }
+        meta.json_ld.push(self.music_album(release));
+        meta
+    }
+
+    /// Share image of a modeling category: its background, or else its
+    /// first image.
+    fn category_cover(&self, name: &str, category: &CategoryData) -> Option<String> {
+        if category.background.is_some() {
+            self.asset_url(&format!("modeling/{}/Background/bkgrnd.png", name))
+        } else {
+            // Image URLs end in the already encoded file name
+            let file = category.images.first()?.rsplit('/').next()?;
+            self.page_url(&format!("/modeling/{}/images/{}", url_encode(name), file))
+        }
+    }
+
+    /// The modeling page, shared with the first category's cover.
+    pub fn modeling(&self, categories: &[(String, CategoryData)]) -> PageMeta {
+        let cover = categories.first().and_then(|(name, category)| self.category_cover(name, category));
+        self.page_with_image("/modeling/", cover)
+    }
+
+    /// A modeling category page, shared with the category's cover and
+    /// subtitle.
+    pub fn modeling_category(&self, name: &str, category: &CategoryData) -> PageMeta {
+        let route = format!("/modeling/{}/", slugify(name));
+        let mut meta = self.page_with_image(&route, self.category_cover(name, category));
+        if self.settings.pages.get(&route).and_then(|page| page.description.as_ref()).is_none() {
+            meta.description = Some(category.subtitle.clone());
+        }
+        meta
+    }
+
+    /// The reviews page, with a `Review` of the person per testimonial and
+    /// the person's aggregate rating.
+    pub fn reviews(&self, testimonials: &[Testimonial]) -> PageMeta {
+        let mut meta = self.page("/reviews/");
+        if let Some((average, count)) = content::aggregate_rating(testimonials) {
+            let mut person = self.person();
+            person["@context"] = json!("https://schema.org");
+            person["aggregateRating"] = json!({
+                "@type": "AggregateRating",
+                "ratingValue": (average * 10.0).round() / 10.0,
+                "bestRating": 5,
+                "worstRating": 1,
+                "ratingCount": count,
+            });
+            meta.json_ld.push(person);
+        }
+        for testimonial in testimonials {
+            let mut review = json!({
+                "@context": "https://schema.org",
+                "@type": "Review",
+                "reviewBody": testimonial.quote,
+                "author": { "@type": "Person", "name": testimonial.author, "jobTitle": testimonial.title },
+                "itemReviewed": self.person(),
+            });
+            if let Some(rating) = testimonial.rating {
+                review["reviewRating"] = json!({ "@type": "Rating", "ratingValue": rating, "bestRating": 5, "worstRating": 1 });
+            }
+            if let Some(date) = testimonial.date {
+                review["datePublished"] = json!(date.format("%Y-%m-%d").to_string());
+            }
+            if let Some(source) = &testimonial.source {
+                review["url"] = json!(source);
+            }
+            if let Some(photo) = testimonial.photo.as_deref().and_then(|photo| self.asset_url(photo)) {
+                review["author"]["image"] = json!(photo);
+            }
+            meta.json_ld.push(review);
+        }
+        meta
+    }
+
+    /// A news post, shared as an article with its cover and summary.
+    pub fn post(&self, post: &Post) -> PageMeta {
+        let route = format!("/news/{}/", post.slug);
+        let cover = post.cover.as_deref().and_then(|cover| self.asset_url(cover));
+        let mut meta = self.page_with_image(&route, cover);
+        meta.og_type = Some("article");
+        if !post.summary.is_empty() {
+            meta.description = Some(post.summary.clone());
+        }
+        meta
+    }
+}
@@ -0,0 +1,270 @@
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{Html, IntoResponse, Redirect, Response},
+    Form, Json,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use super::{forms, AppError, AppState};
+use crate::orders::CheckoutError;
+use crate::render::{generate_cart_html, generate_page, generate_shop_html, ShopActions};
+use crate::seo::{PageMeta, Seo};
+use crate::shop::{self, Cart, Catalog, ItemKey};
+
+/// Cookie holding the visitor's cart session.
+pub const CART_COOKIE: &str = "cart";
+
+/// How long a cart is kept after it was last changed.
+pub const CART_LIFETIME: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Most carts kept at once; starting another drops the one changed longest
+/// ago.
+pub const MAX_CARTS: usize = 10_000;
+
+/// Visitors' carts by session, kept in memory. Sessions are only ever
+/// started by [`Carts::create`], so a cookie the server did not hand out
+/// cannot add carts.
+#[derive(Default)]
+pub struct Carts {
+    carts: Mutex<HashMap<String, (Cart, Instant)>>,
+}
+
+impl Carts {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, (Cart, Instant)>> {
+        self.carts.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The session's cart; empty when there is none.
+    pub fn get(&self, session: &str) -> Cart {
+        self.lock()
+            .get(session)
+            .filter(|(_, changed)| changed.elapsed() < CART_LIFETIME)
+            .map(|(cart, _)| cart.clone())
+            .unwrap_or_default()
+    }
+
+    /// Keeps `cart` under a new session id, which it returns, dropping carts
+    /// that have expired and, past [`MAX_CARTS`], the oldest.
+    pub fn create(&self, cart: Cart) -> String {
+        let mut carts = self.lock();
+        carts.retain(|_, (_, changed)| changed.elapsed() < CART_LIFETIME);
+        while carts.len() >= MAX_CARTS {
+            let Some(oldest) = carts.iter().min_by_key(|(_, (_, changed))| *changed).map(|(id, _)| id.clone()) else {
+                break;
+            };
+            carts.remove(&oldest);
+        }
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        carts.insert(id.clone(), (cart, Instant::now()));
+        id
+    }
+
+    /// Changes the session's cart; `None`, changing nothing, when the
+    /// session has no cart or it has expired.
+    pub fn update<R>(&self, session: &str, change: impl FnOnce(&mut Cart) -> R) -> Option<R> {
+        let mut carts = self.lock();
+        let (cart, changed) = carts.get_mut(session).filter(|(_, changed)| changed.elapsed() < CART_LIFETIME)?;
+        *changed = Instant::now();
+        Some(change(cart))
+    }
+
+    pub fn remove(&self, session: &str) {
+        self.lock().remove(session);
+    }
+
+    /// Number of carts kept, expired ones included until the next is created.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The cart session the request's cookie names, if any.
+fn session(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == CART_COOKIE)
+        .map(|(_, id)| id.to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// The cookie that hands a new cart session to the visitor.
+fn session_cookie(id: &str) -> HeaderValue {
+    let cookie = format!("{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Lax", CART_COOKIE, id, CART_LIFETIME.as_secs());
+    HeaderValue::from_str(&cookie).expect("cart cookie is a valid header")
+}
+
+fn read_catalog(state: &AppState) -> Result<Catalog, AppError> {
+    shop::read_catalog(&state.config.content_dir).map_err(AppError::Internal)
+}
+
+fn read_sold(state: &AppState) -> Result<HashMap<ItemKey, u32>, AppError> {
+    state.orders.read_sold().map_err(AppError::Internal)
+}
+
+#[derive(Deserialize)]
+pub struct CartForm {
+    product: String,
+    /// Empty for a product without variants.
+    #[serde(default)]
+    variant: String,
+    #[serde(default)]
+    quantity: String,
+}
+
+impl CartForm {
+    fn key(&self) -> ItemKey {
+        ItemKey { product: self.product.clone(), variant: self.variant.clone() }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CheckoutForm {
+    name: String,
+    email: String,
+    address: String,
+    /// Honeypot, see [`forms::is_spam`].
+    #[serde(default)]
+    website: String,
+}
+
+fn cart_notice(message: &str) -> Response {
+    let notice = forms::notice(false, "Your Cart Could Not Be Changed", message, "/cart/", "Back to your cart");
+    (StatusCode::BAD_REQUEST, notice).into_response()
+}
+
+// Shop page handler
+pub async fn shop_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/shop/", "shop")?;
+    let catalog = read_catalog(&state)?;
+    let products_html = generate_shop_html(&catalog, &read_sold(&state)?, ShopActions::Cart("/cart/add"), "/templates");
+    let content = template.content.replace("{{PRODUCTS}}", &products_html);
+    let settings = super::read_settings(&state.config)?;
+    Ok(Html(generate_page(&template.title, &content, &Seo::new(&settings).page("/shop/"))))
+}
+
+// Cart page handler
+pub async fn cart_page_handler(State(state): State<AppState>, headers: HeaderMap) -> Result<Html<String>, AppError> {
+    state.templates.get().ok_or(AppError::Unavailable)?;
+    let catalog = read_catalog(&state)?;
+    let cart = session(&headers).map(|id| state.carts.get(&id)).unwrap_or_default();
+    Ok(Html(generate_page("Cart", &generate_cart_html(&cart, &catalog, "/cart", "/shop/"), &PageMeta::noindex())))
+}
+
+// Live cart count for the cart indicator
+pub async fn cart_count_handler(State(state): State<AppState>, headers: HeaderMap) -> Json<serde_json::Value> {
+    let count = session(&headers).map(|id| state.carts.get(&id).count()).unwrap_or_default();
+    Json(serde_json::json!({ "count": count }))
+}
+
+// Add to cart handler: adds to what the cart already holds
+pub async fn add_to_cart_handler(State(state): State<AppState>, headers: HeaderMap, Form(form): Form<CartForm>) -> Result<Response, AppError> {
+    let catalog = read_catalog(&state)?;
+    let sold = read_sold(&state)?;
+    let Ok(quantity) = (if form.quantity.trim().is_empty() { Ok(1) } else { form.quantity.trim().parse::<u32>() }) else {
+        return Ok(cart_notice("Please enter how many you would like."));
+    };
+
+    let key = form.key();
+    let add = |cart: &mut Cart| {
+        let total = cart.quantity(&key).saturating_add(quantity);
+        shop::check_quantity(&catalog, &key, total, &sold).map(|()| cart.set(key.clone(), total))
+    };
+    let added = match session(&headers).and_then(|id| state.carts.update(&id, add)) {
+        Some(added) => added.map(|()| None),
+        // No session yet, or one this server did not start or has dropped
+        None => {
+            let mut cart = Cart::default();
+            add(&mut cart).map(|()| Some(session_cookie(&state.carts.create(cart))))
+        },
+    };
+    let cookie = match added {
+        Ok(cookie) => cookie,
+        Err(message) => return Ok(cart_notice(&message)),
+    };
+
+    let mut response = Redirect::to("/cart/").into_response();
+    if let Some(cookie) = cookie {
+        response.headers_mut().insert(header::SET_COOKIE, cookie);
+    }
+    Ok(response)
+}
+
+// Cart update handler: sets a line's quantity, removing it at zero
+pub async fn update_cart_handler(State(state): State<AppState>, headers: HeaderMap, Form(form): Form<CartForm>) -> Result<Response, AppError> {
+    let Some(id) = session(&headers) else {
+        return Ok(Redirect::to("/cart/").into_response());
+    };
+    let Ok(quantity) = form.quantity.trim().parse::<u32>() else {
+        return Ok(cart_notice("Please enter how many you would like."));
+    };
+    if quantity > 0 {
+        let catalog = read_catalog(&state)?;
+        if let Err(message) = shop::check_quantity(&catalog, &form.key(), quantity, &read_sold(&state)?) {
+            return Ok(cart_notice(&message));
+        }
+    }
+    // A session without a cart is left without one
+    state.carts.update(&id, |cart| cart.set(form.key(), quantity));
+    Ok(Redirect::to("/cart/").into_response())
+}
+
+// Checkout handler: takes payment and records the order. Every attempt
+// counts against the form rate limit, as each one may reach the provider
+pub async fn checkout_handler(
+    State(state): State<AppState>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Form(form): Form<CheckoutForm>,
+) -> Result<Response, AppError> {
+    let client = super::client_key(connect_info);
+    super::check_rate_limit(&state, &client)?;
+    if forms::is_spam(&form.website) {
+        state.metrics.record_spam(false);
+        tracing::warn!(client = %client, "checkout honeypot filled in, order dropped");
+        return Ok(forms::notice(true, "Thank You for Your Order!", "Your order has been placed.", "/shop/", "Back to the shop").into_response());
+    }
+    let id = session(&headers).unwrap_or_default();
+    let cart = state.carts.get(&id);
+    let catalog = read_catalog(&state)?;
+
+    let task_state = state.clone();
+    let placed = tokio::task::spawn_blocking(move || {
+        let customer = (form.name.as_str(), form.email.as_str(), form.address.as_str());
+        task_state.orders.place(&catalog, &cart, customer, task_state.payments.as_ref(), chrono::Utc::now())
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("checkout task failed: {}", e)))?;
+
+    let order = match placed {
+        Ok(order) => order,
+        Err(CheckoutError::Rejected(message)) => {
+            let notice = forms::notice(false, "Your Order Could Not Be Placed", &message, "/cart/", "Back to your cart");
+            return Ok((StatusCode::BAD_REQUEST, notice).into_response());
+        },
+        Err(CheckoutError::Failed(e)) => return Err(AppError::Internal(e)),
+    };
+    state.carts.remove(&id);
+    state.metrics.record_order();
+    tracing::info!(id = %order.id, total = order.total, provider = %order.provider, "new order placed");
+
+    let message = format!(
+        "Your order number is {} and {} has been charged. I'll be in touch at {} about delivery.",
+        order.id,
+        order.total().display(&order.currency),
+        order.email
+    );
+    Ok(forms::notice(true, "Thank You for Your Order!", &message, "/shop/", "Back to the shop").into_response())
+}
@@ -0,0 +1,59 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Number of contact messages that may wait for the writer before
+/// submissions start waiting for room in the queue.
+const QUEUE_CAPACITY: usize = 256;
+
+/// Handle for queueing contact messages to be appended to the messages file.
+///
+/// Messages are written by a single background task, so the file is never
+/// appended to concurrently. Once every handle has been dropped the task
+/// drains what is left in the queue and exits.
+#[derive(Clone)]
+pub struct ContactQueue {
+    sender: mpsc::Sender<String>,
+}
+
+impl ContactQueue {
+    /// Spawns the writer task for `path` and returns the queue handle along
+    /// with the task, which completes once all pending messages are written.
+    pub fn spawn(path: impl Into<PathBuf>) -> (Self, JoinHandle<()>) {
+        let path = path.into();
+        let (sender, mut receiver) = mpsc::channel::<String>(QUEUE_CAPACITY);
+
+        let task = tokio::spawn(async move {
+            let mut written = 0usize;
+            while let Some(entry) = receiver.recv().await {
+                let path = path.clone();
+                let result = tokio::task::spawn_blocking(move || append_entry(&path, &entry)).await;
+                match result {
+                    Ok(Ok(())) => written += 1,
+                    Ok(Err(e)) => tracing::error!(error = %e, "error writing to messages file"),
+                    Err(e) => tracing::error!(error = %e, "contact writer task failed"),
+                }
+            }
+            tracing::info!(written, "contact message writer stopped");
+        });
+
+        (ContactQueue { sender }, task)
+    }
+
+    /// Queues a formatted message entry. Fails only when the writer has
+    /// already shut down.
+    pub async fn push(&self, entry: String) -> Result<(), String> {
+        self.sender
+            .send(entry)
+            .await
+            .map_err(|_| "contact message writer is not running".to_string())
+    }
+}
+
+fn append_entry(path: &Path, entry: &str) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(entry.as_bytes())?;
+    file.flush()
+}
@@ -0,0 +1,174 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode, Uri},
+    response::{Html, IntoResponse, Redirect, Response},
+};
+
+use crate::render::{generate_page, html_escape};
+use crate::seo::PageMeta;
+
+use super::{AppState, ServerConfig};
+use crate::{content, discography, posts};
+
+/// Routes served by a page handler. Requests for these paths without the
+/// trailing slash are redirected by the fallback handler.
+pub const PAGE_ROUTES: &[&str] = &[
+    "/bio/",
+    "/acting/",
+    "/music/",
+    "/modeling/",
+    "/reviews/",
+    "/events/",
+    "/news/",
+    "/behind-the-scenes/",
+    "/contact/",
+    "/shop/",
+    "/cart/",
+    "/admin/reviews/",
+];
+
+
+/// Error type shared by all handlers, rendered as a branded error page.
+#[derive(Debug)]
+pub enum AppError {
+    NotFound(String),
+    Internal(String),
+    Unavailable,
+    /// Too many form submissions; retry after this many seconds.
+    RateLimited(u64),
+    /// Admin pages without the right credentials.
+    Unauthorized,
+}
+
+impl AppError {
+    pub fn missing_template(page: &str) -> Self {
+        AppError::NotFound(format!("The {} page template was not found.", page))
+    }
+}
+
+pub fn generate_error_page(status: StatusCode, message: &str) -> String {
+    let template = if status == StatusCode::NOT_FOUND {
+        include_str!("../../templates/404.html")
+    } else {
+        include_str!("../../templates/500.html")
+    };
+    let title = if status == StatusCode::NOT_FOUND {
+        "404 - Page Not Found"
+    } else {
+        "500 - Server Error"
+    };
+    let content = template.replace("{{MESSAGE}}", &html_escape(message));
+    generate_page(title, &content, &PageMeta::noindex())
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        match self {
+            AppError::Unavailable => {
+                return (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    [(header::RETRY_AFTER, "5")],
+                    "The site is starting up, please try again shortly.",
+                )
+                    .into_response();
+            },
+            AppError::RateLimited(retry_after) => {
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(header::RETRY_AFTER, retry_after.to_string())],
+                    "Too many submissions, please try again later.",
+                )
+                    .into_response();
+            },
+            AppError::Unauthorized => {
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    [(header::WWW_AUTHENTICATE, r#"Basic realm="Moderation", charset="UTF-8""#)],
+                    "Please sign in to moderate reviews.",
+                )
+                    .into_response();
+            },
+            _ => {},
+        }
+
+        let (status, message) = match self {
+            AppError::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            AppError::Internal(message) => {
+                tracing::error!(error = %message, "internal server error");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "An unexpected error occurred. Please try again later.".to_string(),
+                )
+            }
+            AppError::Unavailable | AppError::RateLimited(_) | AppError::Unauthorized => unreachable!("handled above"),
+        };
+
+        (status, Html(generate_error_page(status, &message))).into_response()
+    }
+}
+
+/// Turns a panic inside a handler into the branded 500 page.
+pub fn panic_response(panic: Box<dyn std::any::Any + Send + 'static>) -> Response {
+    let detail = panic
+        .downcast_ref::<String>()
+        .map(String::as_str)
+        .or_else(|| panic.downcast_ref::<&str>().copied())
+        .unwrap_or("unknown panic");
+    AppError::Internal(format!("handler panicked: {}", detail)).into_response()
+}
+
+/// Whether `route` is a parameterized page that exists: a modeling
+/// category, a release, a later news page, a tag or a post.
+fn is_parameterized_page(config: &ServerConfig, route: &str) -> bool {
+    let Some(rest) = route.strip_suffix('/') else {
+        return false;
+    };
+    let segment = |prefix: &str| rest.strip_prefix(prefix).filter(|segment| !segment.is_empty() && !segment.contains('/'));
+
+    if let Some(slug) = segment("/modeling/") {
+        return content::discover_modeling_categories(&config.content_dir, "")
+            .iter()
+            .any(|(name, _)| discography::slugify(name) == slug);
+    }
+    if let Some(slug) = segment("/music/releases/") {
+        return discography::read_releases(&config.content_dir).is_ok_and(|releases| releases.iter().any(|release| release.slug == slug));
+    }
+    let Ok(posts) = super::read_posts(config) else {
+        return false;
+    };
+    if let Some(page) = segment("/news/page/") {
+        return page.parse::<usize>().is_ok_and(|page| page >= 1 && page <= posts::page_count(posts.len()));
+    }
+    if let Some(tag) = segment("/news/tags/") {
+        return posts.iter().any(|post| post.has_tag(tag));
+    }
+    segment("/news/").is_some_and(|slug| slug != "page" && slug != "tags" && posts.iter().any(|post| post.slug == slug))
+}
+
+// Fallback handler: redirects known pages missing their trailing slash and
+// renders the 404 page for everything else
+pub async fn fallback_handler(State(state): State<AppState>, uri: Uri) -> Result<Redirect, AppError> {
+    let path = uri.path().to_string();
+    let with_slash = format!("{}/", path.trim_end_matches('/'));
+
+    let is_page = !path.ends_with('/')
+        && (PAGE_ROUTES.contains(&with_slash.as_str()) || {
+            // Parameterized pages exist when their content does
+            let config = state.config.clone();
+            let route = with_slash.clone();
+            tokio::task::spawn_blocking(move || is_parameterized_page(&config, &route))
+                .await
+                .map_err(|e| AppError::Internal(format!("route task failed: {}", e)))?
+        });
+    if is_page {
+        let target = match uri.query() {
+            Some(query) => format!("{}?{}", with_slash, query),
+            None => with_slash,
+        };
+        return Ok(Redirect::permanent(&target));
+    }
+
+    Err(AppError::NotFound(
+        "The page you're looking for doesn't exist or has moved.".to_string(),
+    ))
+}
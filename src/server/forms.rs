@@ -0,0 +1,79 @@
+use axum::response::Html;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::render::html_escape;
+
+/// Submissions accepted from one client within [`SUBMISSION_WINDOW`],
+/// across the contact and review forms.
+pub const SUBMISSION_LIMIT: usize = 5;
+pub const SUBMISSION_WINDOW: Duration = Duration::from_secs(10 * 60);
+
+/// Counts form submissions per client over a sliding window.
+pub struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    submissions: Mutex<HashMap<String, Vec<Instant>>>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        RateLimiter::new(SUBMISSION_LIMIT, SUBMISSION_WINDOW)
+    }
+}
+
+impl RateLimiter {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        RateLimiter { limit, window, submissions: Mutex::new(HashMap::new()) }
+    }
+
+    /// Records a submission from `client`, or returns false when the client
+    /// has used up its submissions for now.
+    pub fn allow(&self, client: &str) -> bool {
+        let now = Instant::now();
+        let mut submissions = self.submissions.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Forget clients whose submissions have all expired
+        submissions.retain(|_, times| {
+            times.retain(|time| now.duration_since(*time) < self.window);
+            !times.is_empty()
+        });
+
+        let times = submissions.entry(client.to_string()).or_default();
+        if times.len() >= self.limit {
+            return false;
+        }
+        times.push(now);
+        true
+    }
+
+    /// Seconds until the window is over, for `Retry-After`.
+    pub fn window_secs(&self) -> u64 {
+        self.window.as_secs()
+    }
+}
+
+/// Whether the hidden `website` field was filled in. People never see it;
+/// bots that fill in every field do.
+pub fn is_spam(honeypot: &str) -> bool {
+    !honeypot.trim().is_empty()
+}
+
+/// Result banner shown after a form submission, with a link back.
+pub fn notice(success: bool, heading: &str, message: &str, back_url: &str, back_label: &str) -> Html<String> {
+    let background = if success { "#4CAF50, #45a049" } else { "#ff6b6b, #ee5a52" };
+    Html(format!(
+        r#"
+        <div style="text-align: center; padding: 50px; background: linear-gradient(45deg, {}); color: white; border-radius: 15px; margin: 20px;">
+            <h1>{}</h1>
+            <p>{}</p>
+            <a href="{}" style="color: white; text-decoration: underline;">{}</a>
+        </div>
+        "#,
+        background,
+        html_escape(heading),
+        html_escape(message),
+        back_url,
+        back_label
+    ))
+}
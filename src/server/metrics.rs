@@ -0,0 +1,200 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use super::AppState;
+
+/// Upper bounds (in seconds) of the request latency histogram buckets.
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Default)]
+struct RouteLatency {
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// In-process counters exposed in Prometheus text format on `/metrics`.
+#[derive(Default)]
+pub struct Metrics {
+    requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    latencies: Mutex<BTreeMap<String, RouteLatency>>,
+    contact_submissions: AtomicU64,
+    review_submissions: AtomicU64,
+    orders_placed: AtomicU64,
+    honeypot_rejections: AtomicU64,
+    rate_limit_rejections: AtomicU64,
+    image_cache_hits: AtomicU64,
+    image_cache_misses: AtomicU64,
+}
+
+impl Metrics {
+    pub fn record_request(&self, route: &str, method: &str, status: u16, seconds: f64) {
+        if let Ok(mut requests) = self.requests.lock() {
+            *requests
+                .entry((route.to_string(), method.to_string(), status))
+                .or_insert(0) += 1;
+        }
+
+        if let Ok(mut latencies) = self.latencies.lock() {
+            let latency = latencies.entry(route.to_string()).or_insert_with(|| RouteLatency {
+                buckets: vec![0; LATENCY_BUCKETS.len()],
+                ..Default::default()
+            });
+            for (bucket, bound) in latency.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+                if seconds <= *bound {
+                    *bucket += 1;
+                }
+            }
+            latency.sum += seconds;
+            latency.count += 1;
+        }
+    }
+
+    pub fn record_contact_submission(&self) {
+        self.contact_submissions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_review_submission(&self) {
+        self.review_submissions.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_order(&self) {
+        self.orders_placed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a form submission turned away as spam, by the honeypot or the
+    /// rate limit.
+    pub fn record_spam(&self, rate_limited: bool) {
+        let counter = if rate_limited { &self.rate_limit_rejections } else { &self.honeypot_rejections };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_image_cache(&self, hit: bool) {
+        let counter = if hit { &self.image_cache_hits } else { &self.image_cache_misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP http_requests_total Total HTTP requests by route, method and status.\n");
+        out.push_str("# TYPE http_requests_total counter\n");
+        if let Ok(requests) = self.requests.lock() {
+            for ((route, method, status), count) in requests.iter() {
+                let _ = writeln!(
+                    out,
+                    "http_requests_total{{route=\"{}\",method=\"{}\",status=\"{}\"}} {}",
+                    escape_label(route), method, status, count
+                );
+            }
+        }
+
+        out.push_str("# HELP http_request_duration_seconds Request latency by route.\n");
+        out.push_str("# TYPE http_request_duration_seconds histogram\n");
+        if let Ok(latencies) = self.latencies.lock() {
+            for (route, latency) in latencies.iter() {
+                let route = escape_label(route);
+                for (bound, count) in LATENCY_BUCKETS.iter().zip(&latency.buckets) {
+                    let _ = writeln!(
+                        out,
+                        "http_request_duration_seconds_bucket{{route=\"{}\",le=\"{}\"}} {}",
+                        route, bound, count
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "http_request_duration_seconds_bucket{{route=\"{}\",le=\"+Inf\"}} {}",
+                    route, latency.count
+                );
+                let _ = writeln!(out, "http_request_duration_seconds_sum{{route=\"{}\"}} {}", route, latency.sum);
+                let _ = writeln!(out, "http_request_duration_seconds_count{{route=\"{}\"}} {}", route, latency.count);
+            }
+        }
+
+        out.push_str("# HELP contact_submissions_total Contact form messages saved.\n");
+        out.push_str("# TYPE contact_submissions_total counter\n");
+        let _ = writeln!(out, "contact_submissions_total {}", self.contact_submissions.load(Ordering::Relaxed));
+
+        out.push_str("# HELP review_submissions_total Visitor reviews queued for moderation.\n");
+        out.push_str("# TYPE review_submissions_total counter\n");
+        let _ = writeln!(out, "review_submissions_total {}", self.review_submissions.load(Ordering::Relaxed));
+
+        out.push_str("# HELP orders_placed_total Shop orders paid for and recorded.\n");
+        out.push_str("# TYPE orders_placed_total counter\n");
+        let _ = writeln!(out, "orders_placed_total {}", self.orders_placed.load(Ordering::Relaxed));
+
+        out.push_str("# HELP form_spam_rejections_total Form submissions turned away, by reason.\n");
+        out.push_str("# TYPE form_spam_rejections_total counter\n");
+        let _ = writeln!(out, "form_spam_rejections_total{{reason=\"honeypot\"}} {}", self.honeypot_rejections.load(Ordering::Relaxed));
+        let _ = writeln!(out, "form_spam_rejections_total{{reason=\"rate_limit\"}} {}", self.rate_limit_rejections.load(Ordering::Relaxed));
+
+        out.push_str("# HELP image_cache_hits_total Image requests answered from the client cache (304).\n");
+        out.push_str("# TYPE image_cache_hits_total counter\n");
+        let _ = writeln!(out, "image_cache_hits_total {}", self.image_cache_hits.load(Ordering::Relaxed));
+
+        out.push_str("# HELP image_cache_misses_total Image requests answered with the full file.\n");
+        out.push_str("# TYPE image_cache_misses_total counter\n");
+        let _ = writeln!(out, "image_cache_misses_total {}", self.image_cache_misses.load(Ordering::Relaxed));
+
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Label for requests that did not match a route, such as nested file services.
+fn route_label(path: &str) -> &'static str {
+    if path.starts_with("/templates/") {
+        "/templates/*"
+    } else if path.starts_with("/docs/") {
+        "/docs/*"
+    } else {
+        "unmatched"
+    }
+}
+
+fn is_image_path(path: &str) -> bool {
+    let lower = path.to_ascii_lowercase();
+    [".png", ".jpg", ".jpeg", ".webp", ".gif"].iter().any(|ext| lower.ends_with(ext))
+}
+
+/// Middleware recording request counts, latencies and image cache results.
+pub async fn track_metrics(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let method = request.method().to_string();
+    let image_request = is_image_path(request.uri().path());
+    let route = match request.extensions().get::<MatchedPath>() {
+        Some(path) => path.as_str().to_string(),
+        None => route_label(request.uri().path()).to_string(),
+    };
+
+    let response = next.run(request).await;
+    let status = response.status();
+
+    state.metrics.record_request(&route, &method, status.as_u16(), start.elapsed().as_secs_f64());
+    if image_request && (status == StatusCode::NOT_MODIFIED || status.is_success()) {
+        state.metrics.record_image_cache(status == StatusCode::NOT_MODIFIED);
+    }
+
+    response
+}
+
+// Metrics endpoint handler
+pub async fn metrics_handler(State(state): State<AppState>) -> ([(axum::http::HeaderName, &'static str); 1], String) {
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.metrics.render(),
+    )
+}
@@ -0,0 +1,741 @@
+//! The development server: page handlers, routing and shared state.
+
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::get,
+    Form, Router,
+};
+use base64::Engine;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use subtle::ConstantTimeEq;
+use tower::ServiceBuilder;
+use tower_http::catch_panic::CatchPanicLayer;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::services::ServeDir;
+use tower_http::set_header::SetResponseHeaderLayer;
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
+use tracing::Level;
+
+use crate::content::{self, PageTemplate};
+use crate::credits;
+use crate::discography;
+use crate::events;
+use crate::feeds;
+use crate::render::{
+    generate_discography_html, generate_events_html, generate_featured_testimonials_html, generate_images_json, generate_media_embeds, generate_modeling_html,
+    generate_moderation_html, generate_news_html, generate_next_event_html, generate_page, generate_post_html, generate_release_html, generate_resume_html, generate_review_form_html, generate_testimonials_html, EmbedStyle,
+};
+use crate::settings::{self, SiteSettings, VideoEmbedMode};
+use crate::thumbnails::{self, ThumbnailCache};
+use crate::media::{self, MediaGroup, YoutubeTarget};
+use crate::moderation::{self, PendingReview};
+use crate::orders::{self, FakePayments, Orders, PaymentProvider};
+use crate::posts::{self, Post};
+use crate::seo::{PageMeta, Seo};
+use crate::sitemap;
+
+mod cart;
+mod contact;
+mod error;
+mod forms;
+mod metrics;
+mod styles;
+
+pub use cart::{Carts, MAX_CARTS};
+pub use contact::ContactQueue;
+pub use error::AppError;
+pub use forms::RateLimiter;
+pub use metrics::Metrics;
+pub use styles::Stylesheets;
+
+/// Filesystem locations the server reads from and writes to.
+#[derive(Clone, Debug)]
+pub struct ServerConfig {
+    /// Content directory with page templates, images and data files.
+    pub content_dir: PathBuf,
+    /// Generated static site, served under `/docs`.
+    pub docs_dir: PathBuf,
+    /// File contact form messages are appended to.
+    pub messages_file: PathBuf,
+    /// File visitor reviews wait in until they are moderated.
+    pub pending_reviews_file: PathBuf,
+    /// File orders placed from the shop are recorded in.
+    pub orders_file: PathBuf,
+    /// Password for the moderation pages under `/admin/`; they are off
+    /// without one.
+    pub admin_password: Option<String>,
+    /// Thumbnail cache for lite video embeds, served under `/thumbnails`.
+    pub thumbnails: ThumbnailCache,
+    /// Whether draft news posts are listed and served.
+    pub drafts: bool,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            content_dir: PathBuf::from(content::DEFAULT_CONTENT_DIR),
+            docs_dir: PathBuf::from("docs"),
+            messages_file: PathBuf::from("messages.txt"),
+            pending_reviews_file: PathBuf::from(moderation::DEFAULT_PENDING_FILE),
+            orders_file: PathBuf::from(orders::DEFAULT_ORDERS_FILE),
+            admin_password: None,
+            thumbnails: ThumbnailCache::default(),
+            drafts: true,
+        }
+    }
+}
+
+/// Shared state handed to every handler.
+#[derive(Clone)]
+pub struct AppState {
+    pub config: Arc<ServerConfig>,
+    /// Page templates, set once the site model has finished loading.
+    pub templates: Arc<OnceLock<HashMap<String, PageTemplate>>>,
+    pub metrics: Arc<Metrics>,
+    pub contact: ContactQueue,
+    /// Shared by the contact and review forms and checkout.
+    pub form_limiter: Arc<RateLimiter>,
+    /// CSS bundles of the pages served so far.
+    pub styles: Arc<Stylesheets>,
+    /// Visitors' shopping carts.
+    pub carts: Arc<Carts>,
+    /// The orders file and the orders being paid for.
+    pub orders: Arc<Orders>,
+    /// Takes payment at checkout.
+    pub payments: Arc<dyn PaymentProvider>,
+}
+
+impl AppState {
+    /// Creates state whose site model has not been loaded yet, taking
+    /// payments through [`FakePayments`].
+    pub fn new(config: ServerConfig, contact: ContactQueue) -> Self {
+        AppState {
+            orders: Arc::new(Orders::new(&config.orders_file)),
+            config: Arc::new(config),
+            templates: Arc::new(OnceLock::new()),
+            metrics: Arc::new(Metrics::default()),
+            contact,
+            form_limiter: Arc::new(RateLimiter::default()),
+            styles: Arc::new(Stylesheets::default()),
+            carts: Arc::new(Carts::default()),
+            payments: Arc::new(FakePayments::default()),
+        }
+    }
+
+    /// Takes payment through `payments` instead of the fake provider.
+    pub fn with_payments(mut self, payments: impl PaymentProvider + 'static) -> Self {
+        self.payments = Arc::new(payments);
+        self
+    }
+
+    /// Looks up the template for `route`, failing while the site is still
+    /// loading or when the page has no template.
+    fn template(&self, route: &str, page: &str) -> Result<&PageTemplate, AppError> {
+        let templates = self.templates.get().ok_or(AppError::Unavailable)?;
+        templates.get(route).ok_or_else(|| AppError::missing_template(page))
+    }
+}
+
+#[derive(Deserialize)]
+struct ContactForm {
+    name: String,
+    email: Option<String>,
+    subject: String,
+    message: String,
+    /// Honeypot, see [`forms::is_spam`].
+    #[serde(default)]
+    website: String,
+}
+
+#[derive(Deserialize)]
+struct ReviewForm {
+    author: String,
+    #[serde(default)]
+    title: String,
+    quote: String,
+    /// Stars, or empty for no rating.
+    #[serde(default)]
+    rating: String,
+    /// Honeypot, see [`forms::is_spam`].
+    #[serde(default)]
+    website: String,
+}
+
+/// Loads templates and modeling categories, logging what was discovered.
+pub fn load_site_model(config: &ServerConfig) -> Result<HashMap<String, PageTemplate>, String> {
+    let templates = content::discover_templates(&config.content_dir)
+        .map_err(|e| format!("error discovering templates: {}", e))?;
+
+    let mut sorted_paths: Vec<_> = templates.keys().collect();
+    sorted_paths.sort();
+    for path in sorted_paths {
+        tracing::info!(path = %path, title = %templates[path].title, "discovered template");
+    }
+
+    // Discover modeling categories
+    let categories = content::discover_modeling_categories(&config.content_dir, "/templates/modeling");
+    for (name, data) in &categories {
+        tracing::info!(category = %name, images = data.images.len(), "discovered modeling category");
+    }
+
+    Ok(templates)
+}
+
+/// Reads `site.yaml`, which embed styles and page metadata depend on.
+fn read_settings(config: &ServerConfig) -> Result<SiteSettings, AppError> {
+    settings::read_site_settings(&config.content_dir).map_err(AppError::Internal)
+}
+
+/// Reads a page folder's media list.
+fn read_media(config: &ServerConfig, folder: &str) -> Result<Vec<MediaGroup>, AppError> {
+    media::read_media(&config.content_dir, folder, &format!("/templates/{}", folder)).map_err(AppError::Internal)
+}
+
+/// Renders a media list, with YouTube videos in the site's embed mode.
+fn render_media(settings: &SiteSettings, groups: &[MediaGroup]) -> String {
+    let thumbnail_url = |target: &YoutubeTarget| match target {
+        YoutubeTarget::Video(id) => format!("/thumbnails/{}.jpg", id),
+        YoutubeTarget::Playlist(_) => format!("/thumbnails/{}", thumbnails::PLACEHOLDER_NAME),
+    };
+    let style = match settings.video_embeds {
+        VideoEmbedMode::Iframe => EmbedStyle::Iframe,
+        VideoEmbedMode::Lite => EmbedStyle::Lite(&thumbnail_url),
+    };
+    generate_media_embeds(groups, style)
+}
+
+// Home page handler
+async fn home_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/", "home")?;
+    let events = events::read_events(&state.config.content_dir).map_err(AppError::Internal)?;
+    let (upcoming, _) = events::split_events(&events, chrono::Local::now().naive_local());
+    let testimonials = content::read_testimonials(&state.config.content_dir).map_err(AppError::Internal)?;
+    let content = template.content
+        .replace("{{NEXT_EVENT}}", &generate_next_event_html(upcoming.first().copied(), "/events/"))
+        .replace("{{FEATURED_TESTIMONIALS}}", &generate_featured_testimonials_html(&testimonials, "/reviews/", "/templates"));
+    let settings = read_settings(&state.config)?;
+    Ok(Html(generate_page(&template.title, &content, &Seo::new(&settings).home())))
+}
+
+// Contact page handler
+async fn contact_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/contact/", "contact")?;
+    let settings = read_settings(&state.config)?;
+    Ok(Html(generate_page(&template.title, &template.content, &Seo::new(&settings).page("/contact/"))))
+}
+
+// Bio page handler
+async fn bio_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/bio/", "bio")?;
+    let settings = read_settings(&state.config)?;
+    Ok(Html(generate_page(&template.title, &template.content, &Seo::new(&settings).bio())))
+}
+
+// Music page handler
+async fn music_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/music/", "music")?;
+    let settings = read_settings(&state.config)?;
+    let groups = read_media(&state.config, "music")?;
+    let releases = discography::read_releases(&state.config.content_dir).map_err(AppError::Internal)?;
+    let content = template.content
+        .replace("{{YOUTUBE_EMBEDS}}", &render_media(&settings, &groups))
+        .replace("{{DISCOGRAPHY}}", &generate_discography_html(&releases, "/music/releases", "/templates"));
+    Ok(Html(generate_page(&template.title, &content, &Seo::new(&settings).music(&groups, &releases))))
+}
+
+// Release detail page handler
+async fn release_page_handler(
+    State(state): State<AppState>,
+    axum::extract::Path(slug): axum::extract::Path<String>,
+) -> Result<Html<String>, AppError> {
+    state.templates.get().ok_or(AppError::Unavailable)?;
+    let releases = discography::read_releases(&state.config.content_dir).map_err(AppError::Internal)?;
+    let release = releases
+        .iter()
+        .find(|release| release.slug == slug)
+        .ok_or_else(|| AppError::NotFound("That release doesn't exist.".to_string()))?;
+    let settings = read_settings(&state.config)?;
+    Ok(Html(generate_page(&release.title, &generate_release_html(release, "/music/", "/templates"), &Seo::new(&settings).release(release))))
+}
+
+// Acting page handler
+async fn acting_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/acting/", "acting")?;
+    let settings = read_settings(&state.config)?;
+    let groups = read_media(&state.config, "acting")?;
+    let resume_html = credits::read_resume(&state.config.content_dir)
+        .map_err(AppError::Internal)?
+        .map(|resume| generate_resume_html(&resume, Some("/acting/resume.pdf")))
+        .unwrap_or_default();
+    let content = template.content
+        .replace("{{ACTING_YOUTUBE_EMBEDS}}", &render_media(&settings, &groups))
+        .replace("{{ACTING_RESUME}}", &resume_html);
+    Ok(Html(generate_page(&template.title, &content, &Seo::new(&settings).media("/acting/", &groups))))
+}
+
+// Acting résumé handler: renders the PDF from credits.yaml on each request
+async fn resume_pdf_handler(State(state): State<AppState>) -> Result<Response, AppError> {
+    state.templates.get().ok_or(AppError::Unavailable)?;
+    let resume = credits::read_resume(&state.config.content_dir)
+        .map_err(AppError::Internal)?
+        .ok_or_else(|| AppError::NotFound("There is no résumé yet.".to_string()))?;
+    let pdf = credits::resume_pdf(&resume).map_err(AppError::Internal)?;
+    Ok(([(header::CONTENT_TYPE, "application/pdf")], pdf).into_response())
+}
+
+// Reviews page handler
+async fn reviews_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/reviews/", "reviews")?;
+    let testimonials = content::read_testimonials(&state.config.content_dir).map_err(AppError::Internal)?;
+    let testimonials_html = generate_testimonials_html(&testimonials, "/templates");
+    let content = template.content
+        .replace("{{TESTIMONIALS_HTML}}", &testimonials_html)
+        .replace("{{REVIEW_FORM}}", &generate_review_form_html("/reviews/"));
+    let settings = read_settings(&state.config)?;
+    Ok(Html(generate_page(&template.title, &content, &Seo::new(&settings).reviews(&testimonials))))
+}
+
+// Events page handler: events that have ended move to the archive
+async fn events_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/events/", "events")?;
+    let events = events::read_events(&state.config.content_dir).map_err(AppError::Internal)?;
+    let (upcoming, past) = events::split_events(&events, chrono::Local::now().naive_local());
+    let events_html = generate_events_html(&upcoming, &past, &format!("/{}", events::ICS_NAME));
+    let content = template.content.replace("{{EVENTS}}", &events_html);
+    let settings = read_settings(&state.config)?;
+    Ok(Html(generate_page(&template.title, &content, &Seo::new(&settings).page("/events/"))))
+}
+
+// Calendar feed handler
+async fn events_ics_handler(State(state): State<AppState>) -> Result<Response, AppError> {
+    state.templates.get().ok_or(AppError::Unavailable)?;
+    let events = events::read_events(&state.config.content_dir).map_err(AppError::Internal)?;
+    let ics = events::generate_ics(&events, chrono::Utc::now());
+    Ok(([(header::CONTENT_TYPE, "text/calendar; charset=utf-8")], ics).into_response())
+}
+
+/// Feed and sitemap URLs pointing back at this server, as reached by the
+/// client.
+fn site_urls(headers: &HeaderMap) -> feeds::SiteUrls {
+    let host = headers
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .unwrap_or("127.0.0.1:3000");
+    feeds::SiteUrls::for_server(&format!("http://{}", host))
+}
+
+// Atom feed handler
+async fn atom_feed_handler(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, AppError> {
+    state.templates.get().ok_or(AppError::Unavailable)?;
+    let urls = site_urls(&headers);
+    let entries = feeds::collect_entries(&state.config.content_dir, &urls).map_err(AppError::Internal)?;
+    Ok(([(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")], feeds::atom(&entries, &urls)).into_response())
+}
+
+// JSON Feed handler
+async fn json_feed_handler(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, AppError> {
+    state.templates.get().ok_or(AppError::Unavailable)?;
+    let urls = site_urls(&headers);
+    let entries = feeds::collect_entries(&state.config.content_dir, &urls).map_err(AppError::Internal)?;
+    Ok(([(header::CONTENT_TYPE, "application/feed+json; charset=utf-8")], feeds::json_feed(&entries, &urls)).into_response())
+}
+
+// Sitemap handler: links back to this server, like the feeds
+async fn sitemap_handler(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, AppError> {
+    state.templates.get().ok_or(AppError::Unavailable)?;
+    let settings = read_settings(&state.config)?;
+    let urls = site_urls(&headers);
+    let pages = sitemap::collect_urls(&state.config.content_dir, &urls, &settings.robots_disallow).map_err(AppError::Internal)?;
+    Ok(([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], sitemap::generate_sitemap(&pages, &urls)).into_response())
+}
+
+// Crawler rules handler
+async fn robots_handler(State(state): State<AppState>, headers: HeaderMap) -> Result<Response, AppError> {
+    let settings = read_settings(&state.config)?;
+    let sitemap_url = format!("{}/{}", site_urls(&headers).site, sitemap::SITEMAP_NAME);
+    let robots = sitemap::generate_robots(&settings.robots_disallow, "", Some(&sitemap_url));
+    Ok(([(header::CONTENT_TYPE, "text/plain; charset=utf-8")], robots).into_response())
+}
+
+/// Reads the news posts, with drafts when the server is configured to show
+/// them.
+fn read_posts(config: &ServerConfig) -> Result<Vec<Post>, AppError> {
+    posts::read_posts(&config.content_dir, "/templates", config.drafts).map_err(AppError::Internal)
+}
+
+// News list page handler: `/news/` is page 1
+async fn news_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    render_news_page(&state, 1)
+}
+
+// Later news list pages
+async fn news_list_page_handler(
+    State(state): State<AppState>,
+    axum::extract::Path(page): axum::extract::Path<String>,
+) -> Result<Response, AppError> {
+    match page.parse::<usize>() {
+        Ok(1) => Ok(Redirect::permanent("/news/").into_response()),
+        Ok(page) => Ok(render_news_page(&state, page)?.into_response()),
+        Err(_) => Err(AppError::NotFound("That page doesn't exist.".to_string())),
+    }
+}
+
+fn render_news_page(state: &AppState, page: usize) -> Result<Html<String>, AppError> {
+    let template = state.template("/news/", "news")?;
+    let posts = read_posts(&state.config)?;
+    let page_posts = posts::page(&posts, page).ok_or_else(|| AppError::NotFound("That page doesn't exist.".to_string()))?;
+    let news_html = generate_news_html(page_posts, None, page, posts::page_count(posts.len()), "/news", "/templates");
+    let content = template.content.replace("{{NEWS}}", &news_html);
+    let settings = read_settings(&state.config)?;
+    let route = if page == 1 { "/news/".to_string() } else { format!("/news/page/{}/", page) };
+    Ok(Html(generate_page(&template.title, &content, &Seo::new(&settings).page(&route))))
+}
+
+// News tag page handler
+async fn news_tag_handler(
+    State(state): State<AppState>,
+    axum::extract::Path(tag): axum::extract::Path<String>,
+) -> Result<Html<String>, AppError> {
+    let template = state.template("/news/", "news")?;
+    let posts = read_posts(&state.config)?;
+    let tagged: Vec<Post> = posts.into_iter().filter(|post| post.has_tag(&tag)).collect();
+    let name = tagged
+        .iter()
+        .flat_map(|post| &post.tags)
+        .find(|name| discography::slugify(name) == tag)
+        .ok_or_else(|| AppError::NotFound("No posts have that tag.".to_string()))?;
+    let news_html = generate_news_html(&tagged, Some(&format!("Tagged “{}”", name)), 1, 1, "/news", "/templates");
+    let content = template.content.replace("{{NEWS}}", &news_html);
+    let settings = read_settings(&state.config)?;
+    let meta = Seo::new(&settings).page(&format!("/news/tags/{}/", tag));
+    Ok(Html(generate_page(&format!("News: {}", name), &content, &meta)))
+}
+
+// News post handler
+async fn post_page_handler(
+    State(state): State<AppState>,
+    axum::extract::Path(slug): axum::extract::Path<String>,
+) -> Result<Html<String>, AppError> {
+    state.templates.get().ok_or(AppError::Unavailable)?;
+    let posts = read_posts(&state.config)?;
+    let post = posts
+        .iter()
+        .find(|post| post.slug == slug)
+        .ok_or_else(|| AppError::NotFound("That post doesn't exist.".to_string()))?;
+    let settings = read_settings(&state.config)?;
+    Ok(Html(generate_page(&post.title, &generate_post_html(post, "/news", "/templates"), &Seo::new(&settings).post(post))))
+}
+
+// Behind-the-scenes page handler
+async fn bts_page_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/behind-the-scenes/", "behind-the-scenes")?;
+
+    let images_dir = state.config.content_dir.join("Behind the scenes").join("images");
+    let images = content::list_images(&images_dir, "/templates/Behind the scenes/images");
+    let subtitle = content::read_bts_subtitle(&state.config.content_dir);
+
+    let content = template.content
+        .replace("{{BTS_IMAGES_JSON}}", &generate_images_json(&images))
+        .replace("{{BTS_SUBTITLE}}", &subtitle);
+
+    let settings = read_settings(&state.config)?;
+    Ok(Html(generate_page(&template.title, &content, &Seo::new(&settings).page("/behind-the-scenes/"))))
+}
+
+// Unified modeling page handler
+async fn unified_modeling_handler(State(state): State<AppState>) -> Result<Html<String>, AppError> {
+    let template = state.template("/modeling/", "modeling")?;
+    let categories = content::discover_modeling_categories(&state.config.content_dir, "/templates/modeling");
+    let content = generate_modeling_html(&template.content, &categories, None);
+    let settings = read_settings(&state.config)?;
+    Ok(Html(generate_page(&template.title, &content, &Seo::new(&settings).modeling(&categories))))
+}
+
+// Modeling category page handler: the modeling page opened on one category
+async fn modeling_category_handler(
+    State(state): State<AppState>,
+    axum::extract::Path(slug): axum::extract::Path<String>,
+) -> Result<Html<String>, AppError> {
+    let template = state.template("/modeling/", "modeling")?;
+    let categories = content::discover_modeling_categories(&state.config.content_dir, "/templates/modeling");
+    let (name, category) = categories
+        .iter()
+        .find(|(name, _)| discography::slugify(name) == slug)
+        .ok_or_else(|| AppError::NotFound("That modeling category doesn't exist.".to_string()))?;
+    let content = generate_modeling_html(&template.content, &categories, Some(name));
+    let settings = read_settings(&state.config)?;
+    let meta = Seo::new(&settings).modeling_category(name, category);
+    Ok(Html(generate_page(&format!("Modeling: {}", category.title), &content, &meta)))
+}
+
+/// Client a form submission is counted against.
+fn client_key(connect_info: Option<ConnectInfo<SocketAddr>>) -> String {
+    connect_info.map(|ConnectInfo(addr)| addr.ip().to_string()).unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Turns away rate limited clients. Honeypot submissions are checked by the
+/// caller, which pretends to accept them.
+fn check_rate_limit(state: &AppState, client: &str) -> Result<(), AppError> {
+    if state.form_limiter.allow(client) {
+        return Ok(());
+    }
+    state.metrics.record_spam(true);
+    tracing::warn!(client = %client, "form submission rate limited");
+    Err(AppError::RateLimited(state.form_limiter.window_secs()))
+}
+
+// Contact form submission handler
+async fn contact_form_handler(
+    State(state): State<AppState>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    Form(form): Form<ContactForm>,
+) -> Result<Html<String>, AppError> {
+    check_rate_limit(&state, &client_key(connect_info))?;
+    let sent = forms::notice(true, "Message Sent Successfully!", &format!("Thank you {}, I'll get back to you soon!", form.name), "/contact/", "Send another message");
+    if forms::is_spam(&form.website) {
+        state.metrics.record_spam(false);
+        tracing::warn!(name = %form.name, "contact form honeypot filled in, message dropped");
+        return Ok(sent);
+    }
+
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+
+    let email = form.email.as_deref().unwrap_or("Anonymous");
+    let message_entry = format!(
+        "\n=== Message received at {} ===\nFrom: {} <{}>\nSubject: {}\nMessage:\n{}\n\n",
+        timestamp, form.name, email, form.subject, form.message
+    );
+
+    state.contact.push(message_entry).await.map_err(AppError::Internal)?;
+    state.metrics.record_contact_submission();
+    tracing::info!(name = %form.name, subject = %form.subject, "new contact message queued");
+
+    Ok(sent)
+}
+
+// Review form submission handler: queues the review for moderation
+async fn review_form_handler(
+    State(state): State<AppState>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    Form(form): Form<ReviewForm>,
+) -> Result<Response, AppError> {
+    check_rate_limit(&state, &client_key(connect_info))?;
+    let queued = forms::notice(true, "Thank You for Your Review!", "It will appear on the reviews page once it has been approved.", "/reviews/", "Back to reviews");
+    if forms::is_spam(&form.website) {
+        state.metrics.record_spam(false);
+        tracing::warn!(author = %form.author, "review form honeypot filled in, review dropped");
+        return Ok(queued.into_response());
+    }
+
+    let rating = match form.rating.trim() {
+        "" => None,
+        rating => rating.parse::<u8>().ok().or(Some(0)),
+    };
+    let review = match PendingReview::new(&form.author, &form.title, &form.quote, rating, chrono::Utc::now()) {
+        Ok(review) => review,
+        Err(message) => {
+            let notice = forms::notice(false, "Your Review Could Not Be Sent", &message, "/reviews/", "Try again");
+            return Ok((StatusCode::BAD_REQUEST, notice).into_response());
+        },
+    };
+
+    let pending_file = state.config.pending_reviews_file.clone();
+    let review = tokio::task::spawn_blocking(move || moderation::submit(&pending_file, review))
+        .await
+        .map_err(|e| AppError::Internal(format!("review task failed: {}", e)))?
+        .map_err(AppError::Internal)?;
+    state.metrics.record_review_submission();
+    tracing::info!(id = %review.id, author = %review.author, "new review queued for moderation");
+
+    Ok(queued.into_response())
+}
+
+/// Lets requests through with the admin password in HTTP Basic credentials.
+/// The moderation pages do not exist without a password configured, and
+/// never answer cross-site requests.
+fn check_admin(state: &AppState, headers: &HeaderMap) -> Result<(), AppError> {
+    let Some(password) = state.config.admin_password.as_deref() else {
+        return Err(AppError::NotFound("The page you're looking for doesn't exist or has moved.".to_string()));
+    };
+    if headers.get("sec-fetch-site").is_some_and(|site| site == "cross-site") {
+        return Err(AppError::Unauthorized);
+    }
+
+    let credentials = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Basic "))
+        .and_then(|encoded| base64::engine::general_purpose::STANDARD.decode(encoded.trim()).ok())
+        .and_then(|decoded| String::from_utf8(decoded).ok());
+    match credentials.as_deref().and_then(|credentials| credentials.split_once(':')) {
+        // Constant time, so the time taken does not tell how much of a guess was right
+        Some((_, given)) if bool::from(given.as_bytes().ct_eq(password.as_bytes())) => Ok(()),
+        _ => Err(AppError::Unauthorized),
+    }
+}
+
+// Moderation page handler: lists the reviews waiting for approval
+async fn moderation_page_handler(State(state): State<AppState>, headers: HeaderMap) -> Result<Html<String>, AppError> {
+    check_admin(&state, &headers)?;
+    let pending = moderation::read_pending(&state.config.pending_reviews_file).map_err(AppError::Internal)?;
+    Ok(Html(generate_page("Review Moderation", &generate_moderation_html(&pending, "/admin/reviews"), &PageMeta::noindex())))
+}
+
+// Moderation action handler: approves or rejects a pending review
+async fn moderation_action_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    axum::extract::Path((id, action)): axum::extract::Path<(String, String)>,
+) -> Result<Redirect, AppError> {
+    check_admin(&state, &headers)?;
+    let config = state.config.clone();
+    let task_id = id.clone();
+    let result = match action.as_str() {
+        "approve" => tokio::task::spawn_blocking(move || {
+            moderation::approve(&config.pending_reviews_file, &config.content_dir, &task_id).map(|_| ())
+        })
+        .await,
+        "reject" => tokio::task::spawn_blocking(move || moderation::reject(&config.pending_reviews_file, &task_id).map(|_| ())).await,
+        _ => return Err(AppError::NotFound("Reviews can only be approved or rejected.".to_string())),
+    };
+
+    match result.map_err(|e| AppError::Internal(format!("moderation task failed: {}", e)))? {
+        Ok(()) => tracing::info!(id = %id, action = %action, "review moderated"),
+        // Already handled, e.g. a second click
+        Err(e) => tracing::warn!(id = %id, error = %e, "review not moderated"),
+    }
+    Ok(Redirect::to("/admin/reviews/"))
+}
+
+// Thumbnail handler: serves cached YouTube thumbnails for lite embeds, and
+// the placeholder when one is not cached and cannot be fetched
+async fn thumbnail_handler(
+    State(state): State<AppState>,
+    axum::extract::Path(file): axum::extract::Path<String>,
+) -> Result<Response, AppError> {
+    let placeholder = || ([(header::CONTENT_TYPE, "image/svg+xml")], thumbnails::PLACEHOLDER_SVG).into_response();
+
+    let Some(id) = file.strip_suffix(".jpg").filter(|id| media::is_video_id(id)) else {
+        if file == thumbnails::PLACEHOLDER_NAME {
+            return Ok(placeholder());
+        }
+        return Err(AppError::NotFound("No such thumbnail.".to_string()));
+    };
+
+    let cache = state.config.thumbnails.clone();
+    let id = id.to_string();
+    let thumbnail = tokio::task::spawn_blocking(move || {
+        cache
+            .get(&id)
+            .and_then(|path| std::fs::read(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e)))
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("thumbnail task failed: {}", e)))?;
+
+    match thumbnail {
+        Ok(bytes) => Ok(([(header::CONTENT_TYPE, "image/jpeg")], bytes).into_response()),
+        Err(e) => {
+            tracing::warn!(error = %e, "serving placeholder thumbnail");
+            Ok(placeholder())
+        }
+    }
+}
+
+// Liveness probe: the process is up and serving requests
+async fn healthz_handler() -> &'static str {
+    "ok"
+}
+
+// Readiness probe: the site model has finished loading
+async fn readyz_handler(State(state): State<AppState>) -> (StatusCode, &'static str) {
+    if state.templates.get().is_some() {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "loading")
+    }
+}
+
+/// Builds the application router with all pages, probes and middleware.
+pub fn router(state: AppState) -> Router {
+    let content_dir = state.config.content_dir.clone();
+    let docs_dir = state.config.docs_dir.clone();
+
+    Router::new()
+        .route("/", get(home_page_handler))
+        .route("/bio/", get(bio_page_handler))
+        .route("/acting/", get(acting_page_handler))
+        .route("/acting/resume.pdf", get(resume_pdf_handler))
+        .route("/music/", get(music_page_handler))
+        .route("/music/releases/:slug/", get(release_page_handler))
+        .route("/modeling/", get(unified_modeling_handler))
+        .route("/modeling/:category/", get(modeling_category_handler))
+        .route("/reviews/", get(reviews_page_handler).post(review_form_handler))
+        .route("/events/", get(events_page_handler))
+        .route("/events.ics", get(events_ics_handler))
+        .route("/feed.xml", get(atom_feed_handler))
+        .route("/feed.json", get(json_feed_handler))
+        .route("/sitemap.xml", get(sitemap_handler))
+        .route("/robots.txt", get(robots_handler))
+        .route("/news/", get(news_page_handler))
+        .route("/news/page/:page/", get(news_list_page_handler))
+        .route("/news/tags/:tag/", get(news_tag_handler))
+        .route("/news/:slug/", get(post_page_handler))
+        .route("/behind-the-scenes/", get(bts_page_handler))
+        .route("/contact/", get(contact_page_handler).post(contact_form_handler))
+        .route("/shop/", get(cart::shop_page_handler))
+        .route("/cart/", get(cart::cart_page_handler))
+        .route("/cart/count", get(cart::cart_count_handler))
+        .route("/cart/add", axum::routing::post(cart::add_to_cart_handler))
+        .route("/cart/update", axum::routing::post(cart::update_cart_handler))
+        .route("/cart/checkout", axum::routing::post(cart::checkout_handler))
+        .route("/admin/reviews/", get(moderation_page_handler))
+        .route("/admin/reviews/:id/:action", axum::routing::post(moderation_action_handler))
+        .route("/thumbnails/:file", get(thumbnail_handler))
+        .route("/css/:file", get(styles::stylesheet_handler))
+        .route("/metrics", get(metrics::metrics_handler))
+        .route("/healthz", get(healthz_handler))
+        .route("/readyz", get(readyz_handler))
+        .nest_service("/docs", ServeDir::new(docs_dir))
+        .nest_service("/templates", ServeDir::new(content_dir))
+        .fallback(error::fallback_handler)
+        .layer(middleware::from_fn_with_state(state.clone(), styles::bundle_styles))
+        .layer(middleware::from_fn_with_state(state.clone(), metrics::track_metrics))
+        .with_state(state)
+        .layer(CatchPanicLayer::custom(error::panic_response))
+        .layer(SetResponseHeaderLayer::overriding(
+            header::CACHE_CONTROL,
+            header::HeaderValue::from_static("no-cache, no-store, must-revalidate"),
+        ))
+        .layer(
+            ServiceBuilder::new()
+                .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+                .layer(
+                    TraceLayer::new_for_http()
+                        .make_span_with(|request: &axum::http::Request<_>| {
+                            let request_id = request
+                                .headers()
+                                .get("x-request-id")
+                                .and_then(|value| value.to_str().ok())
+                                .unwrap_or("-");
+                            tracing::info_span!(
+                                "request",
+                                method = %request.method(),
+                                uri = %request.uri(),
+                                request_id = %request_id,
+                            )
+                        })
+                        .on_response(DefaultOnResponse::new().level(Level::INFO)),
+                )
+                .layer(PropagateRequestIdLayer::x_request_id()),
+        )
+}
@@ -0,0 +1,86 @@
+use axum::{
+    body::Body,
+    extract::{Path, Request, State},
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use super::{AppError, AppState};
+use crate::bundles::{self, Bundler};
+
+/// Paths of the directories the server serves files from.
+const FILE_PREFIXES: &[&str] = &["/templates/", "/docs/"];
+
+/// CSS bundles handed out with pages, served on `/css/`. The bundler is
+/// loaded again when the content's styles or themes change, so edits show
+/// on the next reload; only its current bundles are served.
+#[derive(Default)]
+pub struct Stylesheets {
+    /// The bundler and the fingerprint of the sources it was loaded from.
+    current: Mutex<Option<(String, Arc<Bundler>)>>,
+}
+
+impl Stylesheets {
+    /// The bundler for the content as it is now. Checking for changes and
+    /// loading happen off the async executor.
+    async fn bundler(&self, content_dir: PathBuf) -> Result<Arc<Bundler>, String> {
+        let current = self.current.lock().unwrap().clone();
+        let (fingerprint, bundler) = tokio::task::spawn_blocking(move || {
+            let fingerprint = bundles::sources_fingerprint(&content_dir);
+            match current {
+                Some((loaded, bundler)) if loaded == fingerprint => Ok((fingerprint, bundler)),
+                _ => Bundler::load(&content_dir).map(|bundler| (fingerprint, Arc::new(bundler))),
+            }
+        })
+        .await
+        .map_err(|e| format!("style task failed: {}", e))??;
+        *self.current.lock().unwrap() = Some((fingerprint, bundler.clone()));
+        Ok(bundler)
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        let current = self.current.lock().unwrap();
+        current.as_ref()?.1.bundle(name).map(|bundle| bundle.css)
+    }
+}
+
+/// Moves the styles of rendered pages into bundles, as the static build does.
+/// Files served as they are, from `/templates` and `/docs`, are left alone.
+pub async fn bundle_styles(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let route = request.uri().path().to_string();
+    let is_file = FILE_PREFIXES.iter().any(|prefix| route.starts_with(prefix));
+    let response = next.run(request).await;
+    let is_html = !is_file && response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"));
+    if !is_html {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let html = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(e) => return AppError::Internal(format!("failed to read page: {}", e)).into_response(),
+    };
+    let bundler = match state.styles.bundler(state.config.content_dir.clone()).await {
+        Ok(bundler) => bundler,
+        Err(e) => {
+            tracing::warn!(error = %e, "serving page without styles");
+            Arc::new(Bundler::new(""))
+        }
+    };
+    let html = bundler.apply(&html, Some(&route), &format!("/{}", bundles::CSS_DIR));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(html))
+}
+
+// Stylesheet handler: serves the bundles pages were given
+pub async fn stylesheet_handler(State(state): State<AppState>, Path(file): Path<String>) -> Result<Response, AppError> {
+    let css = state.styles.get(&file).ok_or_else(|| AppError::NotFound("No such stylesheet.".to_string()))?;
+    Ok(([(header::CONTENT_TYPE, "text/css")], css).into_response())
+}
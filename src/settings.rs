@@ -0,0 +1,68 @@
+//! Per-site settings read from `site.yaml` in the content directory.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// How YouTube videos are embedded on the music and acting pages.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoEmbedMode {
+    /// A full YouTube player iframe per video.
+    #[default]
+    Iframe,
+    /// A thumbnail with a play button that loads a `youtube-nocookie.com`
+    /// player on click.
+    Lite,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SiteSettings {
+    pub video_embeds: VideoEmbedMode,
+    /// Public URL of the static site, e.g.
+    /// `https://gorfonous.github.io/Website-test`, for absolute links.
+    pub site_url: Option<String>,
+    /// Meta description of pages without their own.
+    pub description: Option<String>,
+    /// Link preview image of pages without their own, relative to the
+    /// content directory.
+    pub share_image: Option<String>,
+    /// The site's X/Twitter account, e.g. `@AmberTechel`.
+    pub twitter: Option<String>,
+    /// Profiles on other sites, listed as `sameAs` in the bio's structured
+    /// data.
+    pub same_as: Vec<String>,
+    /// Per-page overrides keyed by route, e.g. `/bio/`.
+    pub pages: HashMap<String, PageSettings>,
+    /// Route prefixes kept out of search engines via `robots.txt` and left
+    /// out of the sitemap, e.g. `/modeling/boudoir/`.
+    pub robots_disallow: Vec<String>,
+    /// URL of the server that runs the shop's cart and checkout, e.g.
+    /// `https://shop.example.com`. Without it the static site has no cart.
+    pub shop_url: Option<String>,
+}
+
+/// Search and link preview settings of one page.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PageSettings {
+    pub description: Option<String>,
+    /// Link preview image, relative to the content directory.
+    pub image: Option<String>,
+}
+
+/// Reads `site.yaml`, using defaults when the file does not exist.
+pub fn read_site_settings(content_dir: &Path) -> Result<SiteSettings, String> {
+    let path = content_dir.join("site.yaml");
+    if !path.exists() {
+        return Ok(SiteSettings::default());
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    if content.trim().is_empty() {
+        return Ok(SiteSettings::default());
+    }
+    serde_yaml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))
+}
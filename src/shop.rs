@@ -0,0 +1,323 @@
+//! The shop's catalog from `shop/products.yaml`, and the carts visitors
+//! fill from it.
+
+use serde::{Deserialize, Deserializer};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use crate::discography::slugify;
+
+/// Catalog file, relative to the content directory.
+pub const CATALOG_PATH: &str = "shop/products.yaml";
+
+/// Most of one product or variant a cart may hold.
+pub const MAX_QUANTITY: u32 = 20;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProductKind {
+    Print,
+    SignedPhoto,
+    Album,
+    #[default]
+    Merch,
+}
+
+impl ProductKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            ProductKind::Print => "Print",
+            ProductKind::SignedPhoto => "Signed Photo",
+            ProductKind::Album => "Album",
+            ProductKind::Merch => "Merch",
+        }
+    }
+}
+
+/// An amount in cents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Price(pub u64);
+
+impl Price {
+    /// Parses `25`, `25.5` or `25.50`.
+    pub fn parse(value: &str) -> Option<Price> {
+        let (whole, cents) = value.trim().split_once('.').unwrap_or((value.trim(), ""));
+        let digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || !digits(whole) || !digits(cents) || cents.len() > 2 {
+            return None;
+        }
+        let cents = format!("{:0<2}", cents).parse::<u64>().ok()?;
+        whole.parse::<u64>().ok()?.checked_mul(100)?.checked_add(cents).map(Price)
+    }
+
+    /// Formats the amount, with the symbol of the common currencies:
+    /// `$25.00`, `€25.00` or `25.00 CHF`.
+    pub fn display(self, currency: &str) -> String {
+        let amount = format!("{}.{:02}", self.0 / 100, self.0 % 100);
+        match currency {
+            "USD" | "CAD" | "AUD" => format!("${}", amount),
+            "EUR" => format!("€{}", amount),
+            "GBP" => format!("£{}", amount),
+            _ => format!("{} {}", amount, currency),
+        }
+    }
+}
+
+/// Accepts `price: 25`, `price: 25.5` and `price: "25.50"`.
+impl<'de> Deserialize<'de> for Price {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Whole(u64),
+            Decimal(f64),
+            Text(String),
+        }
+
+        let text = match Raw::deserialize(deserializer)? {
+            Raw::Whole(whole) => whole.to_string(),
+            Raw::Decimal(decimal) => decimal.to_string(),
+            Raw::Text(text) => text,
+        };
+        Price::parse(&text).ok_or_else(|| serde::de::Error::custom(format!("invalid price {:?}", text)))
+    }
+}
+
+/// A size, edition or other option of a product.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Variant {
+    pub name: String,
+    /// Identifies the variant in carts and orders; derived from the name.
+    #[serde(skip)]
+    pub slug: String,
+    /// Overrides the product's price.
+    pub price: Option<Price>,
+    /// Units for sale; without it there is no limit.
+    pub stock: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Product {
+    pub name: String,
+    /// Identifies the product in carts and orders; derived from the name
+    /// when omitted.
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default, rename = "type")]
+    pub kind: ProductKind,
+    pub description: Option<String>,
+    /// Image path relative to the content directory.
+    pub image: Option<String>,
+    pub price: Option<Price>,
+    /// Units for sale of a product without variants; without it there is
+    /// no limit.
+    pub stock: Option<u32>,
+    #[serde(default)]
+    pub variants: Vec<Variant>,
+}
+
+impl Product {
+    /// The product itself, or one of its variants by slug. A product with
+    /// variants can only be bought as one of them.
+    pub fn item(&self, variant: &str) -> Option<Item<'_>> {
+        match (variant, self.variants.is_empty()) {
+            ("", true) => Some(Item { product: self, variant: None }),
+            (_, false) => self.variants.iter().find(|candidate| candidate.slug == variant).map(|variant| Item { product: self, variant: Some(variant) }),
+            _ => None,
+        }
+    }
+
+    /// What the product and each of its variants can be bought as.
+    pub fn items(&self) -> Vec<Item<'_>> {
+        if self.variants.is_empty() {
+            return vec![Item { product: self, variant: None }];
+        }
+        self.variants.iter().map(|variant| Item { product: self, variant: Some(variant) }).collect()
+    }
+}
+
+/// Something a cart line or order refers to: a product, or one of its
+/// variants.
+#[derive(Clone, Copy, Debug)]
+pub struct Item<'a> {
+    pub product: &'a Product,
+    pub variant: Option<&'a Variant>,
+}
+
+impl Item<'_> {
+    pub fn key(&self) -> ItemKey {
+        ItemKey { product: self.product.slug.clone(), variant: self.variant.map(|variant| variant.slug.clone()).unwrap_or_default() }
+    }
+
+    /// `Name`, or `Name (Variant)`.
+    pub fn name(&self) -> String {
+        match self.variant {
+            Some(variant) => format!("{} ({})", self.product.name, variant.name),
+            None => self.product.name.clone(),
+        }
+    }
+
+    pub fn price(&self) -> Price {
+        self.variant.and_then(|variant| variant.price).or(self.product.price).unwrap_or_default()
+    }
+
+    pub fn stock(&self) -> Option<u32> {
+        match self.variant {
+            Some(variant) => variant.stock,
+            None => self.product.stock,
+        }
+    }
+
+    /// Units left once `sold` are taken off the stock, or `None` for no
+    /// limit.
+    pub fn available(&self, sold: &HashMap<ItemKey, u32>) -> Option<u32> {
+        self.stock().map(|stock| stock.saturating_sub(sold.get(&self.key()).copied().unwrap_or_default()))
+    }
+}
+
+/// Product and variant slugs; the variant is empty for a product without
+/// variants.
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ItemKey {
+    pub product: String,
+    pub variant: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Catalog {
+    /// ISO 4217 code prices are in.
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    #[serde(default)]
+    pub products: Vec<Product>,
+}
+
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Catalog { currency: default_currency(), products: Vec::new() }
+    }
+}
+
+impl Catalog {
+    pub fn item(&self, key: &ItemKey) -> Option<Item<'_>> {
+        self.products.iter().find(|product| product.slug == key.product)?.item(&key.variant)
+    }
+}
+
+/// Reads `shop/products.yaml`. A missing file means nothing is for sale;
+/// an invalid one is an error.
+pub fn read_catalog(content_dir: &Path) -> Result<Catalog, String> {
+    let path = content_dir.join(CATALOG_PATH);
+    if !path.exists() {
+        return Ok(Catalog::default());
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+    if content.trim().is_empty() {
+        return Ok(Catalog::default());
+    }
+    let mut catalog: Catalog = serde_yaml::from_str(&content).map_err(|e| format!("invalid {}: {}", path.display(), e))?;
+    if catalog.currency.len() != 3 || !catalog.currency.chars().all(|c| c.is_ascii_uppercase()) {
+        return Err(format!("{}: invalid currency {:?}, use a code like USD", path.display(), catalog.currency));
+    }
+
+    let mut slugs = HashSet::new();
+    for product in &mut catalog.products {
+        if product.slug.is_empty() {
+            product.slug = slugify(&product.name);
+        }
+        if product.slug.is_empty() || product.slug != slugify(&product.slug) {
+            return Err(format!("{}: invalid slug {:?} for {:?}", path.display(), product.slug, product.name));
+        }
+        if !slugs.insert(product.slug.clone()) {
+            return Err(format!("{}: duplicate product slug {:?}", path.display(), product.slug));
+        }
+        if !product.variants.is_empty() && product.stock.is_some() {
+            return Err(format!("{}: {:?} has variants, so give each of them its stock instead", path.display(), product.name));
+        }
+
+        let mut variant_slugs = HashSet::new();
+        for variant in &mut product.variants {
+            variant.slug = slugify(&variant.name);
+            if variant.slug.is_empty() || !variant_slugs.insert(variant.slug.clone()) {
+                return Err(format!("{}: invalid or duplicate variant {:?} of {:?}", path.display(), variant.name, product.name));
+            }
+        }
+        if product.items().iter().any(|item| item.variant.and_then(|variant| variant.price).or(item.product.price).is_none()) {
+            return Err(format!("{}: {:?} has no price", path.display(), product.name));
+        }
+    }
+    Ok(catalog)
+}
+
+/// A cart line: so many of one item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CartLine {
+    pub key: ItemKey,
+    pub quantity: u32,
+}
+
+/// A visitor's cart, in the order items were added.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cart {
+    pub lines: Vec<CartLine>,
+}
+
+impl Cart {
+    pub fn quantity(&self, key: &ItemKey) -> u32 {
+        self.lines.iter().find(|line| &line.key == key).map_or(0, |line| line.quantity)
+    }
+
+    /// Sets how many of `key` the cart holds, removing it at zero.
+    pub fn set(&mut self, key: ItemKey, quantity: u32) {
+        match self.lines.iter().position(|line| line.key == key) {
+            Some(index) if quantity == 0 => {
+                self.lines.remove(index);
+            },
+            Some(index) => self.lines[index].quantity = quantity,
+            None if quantity > 0 => self.lines.push(CartLine { key, quantity }),
+            None => {},
+        }
+    }
+
+    /// Number of units in the cart, for the cart indicator.
+    pub fn count(&self) -> u32 {
+        self.lines.iter().map(|line| line.quantity).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The lines whose items are still in the catalog, with those items.
+    pub fn items<'a>(&self, catalog: &'a Catalog) -> Vec<(Item<'a>, u32)> {
+        self.lines.iter().filter_map(|line| Some((catalog.item(&line.key)?, line.quantity))).collect()
+    }
+
+    /// What the lines still in the catalog cost together.
+    pub fn total(&self, catalog: &Catalog) -> Price {
+        Price(self.items(catalog).iter().map(|(item, quantity)| item.price().0 * u64::from(*quantity)).sum())
+    }
+}
+
+/// Checks that `quantity` of `key` can go into a cart, given the units
+/// already `sold`, returning a message for the visitor when not.
+pub fn check_quantity(catalog: &Catalog, key: &ItemKey, quantity: u32, sold: &HashMap<ItemKey, u32>) -> Result<(), String> {
+    let item = catalog.item(key).ok_or_else(|| "That item is no longer for sale.".to_string())?;
+    if quantity > MAX_QUANTITY {
+        return Err(format!("You can order at most {} of {}.", MAX_QUANTITY, item.name()));
+    }
+    match item.available(sold) {
+        Some(0) if quantity > 0 => Err(format!("{} is sold out.", item.name())),
+        Some(available) if quantity > available => Err(format!("Only {} of {} left.", available, item.name())),
+        _ => Ok(()),
+    }
+}
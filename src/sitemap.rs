@@ -0,0 +1,175 @@
+//! `sitemap.xml`, with the gallery images of each page, and `robots.txt`.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::path::{Path, PathBuf};
+
+use crate::content;
+use crate::discography::{self, slugify};
+use crate::feeds::SiteUrls;
+use crate::posts;
+use crate::render::{html_escape, url_encode, url_encode_path};
+
+/// File name of the sitemap, served and written at the site root.
+pub const SITEMAP_NAME: &str = "sitemap.xml";
+/// File name of the crawler rules, served and written at the site root.
+pub const ROBOTS_NAME: &str = "robots.txt";
+
+/// Data files, relative to the content directory, that a page is built
+/// from besides its template.
+const PAGE_SOURCES: &[(&str, &[&str])] = &[
+    ("/", &["events/events.yaml", "reviews/reviews.yaml"]),
+    ("/music/", &["music/media.yaml", "music/videos.yaml", "music/youtubeLinks.txt", "music/albums.yaml"]),
+    ("/acting/", &["acting/media.yaml", "acting/videos.yaml", "acting/youtubeLinks.txt", "acting/credits.yaml"]),
+    ("/reviews/", &["reviews/reviews.yaml"]),
+    ("/events/", &["events/events.yaml"]),
+];
+
+/// One page of the sitemap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SitemapUrl {
+    /// Route of the page, e.g. `/bio/`.
+    pub route: String,
+    /// When its newest source file changed.
+    pub lastmod: Option<DateTime<Utc>>,
+    /// Absolute URLs of the gallery images shown on the page.
+    pub images: Vec<String>,
+}
+
+fn newest(paths: &[PathBuf]) -> Option<DateTime<Utc>> {
+    paths.iter().filter_map(|path| content::modified_time(path)).max()
+}
+
+/// Paths and absolute URLs of the images in `images_dir`.
+fn images(images_dir: &Path, images_url: &str) -> (Vec<PathBuf>, Vec<String>) {
+    content::image_file_names(images_dir)
+        .into_iter()
+        .map(|name| (images_dir.join(&name), format!("{}/{}", images_url, url_encode(&name))))
+        .unzip()
+}
+
+/// Whether `route` is kept out of search by one of the `disallow` rules,
+/// which are route prefixes like `/modeling/boudoir/`.
+pub fn is_disallowed(route: &str, disallow: &[String]) -> bool {
+    disallow.iter().any(|rule| !rule.is_empty() && route.starts_with(rule.as_str()))
+}
+
+/// Lists every page of the site: the pages with a template, modeling
+/// categories, releases, news posts, news list pages and tag pages. Routes
+/// matching a `disallow` rule are left out. Published posts only.
+pub fn collect_urls(content_dir: &Path, urls: &SiteUrls, disallow: &[String]) -> Result<Vec<SitemapUrl>, String> {
+    let mut pages = Vec::new();
+    let categories = content::discover_modeling_categories(content_dir, "");
+    let category_images: Vec<(Vec<PathBuf>, Vec<String>)> = categories
+        .iter()
+        .map(|(name, _)| {
+            let images_url = format!("{}/modeling/{}/images", urls.assets, url_encode(name));
+            images(&content_dir.join("modeling").join(name).join("images"), &images_url)
+        })
+        .collect();
+    let posts = posts::read_posts(content_dir, &urls.assets, false)?;
+    let post_path = |slug: &str| content_dir.join("posts").join(format!("{}.md", slug));
+
+    for (route, segments, _) in content::PAGES {
+        let template = segments.iter().fold(content_dir.to_path_buf(), |path, segment| path.join(segment));
+        if !template.exists() {
+            continue;
+        }
+
+        let mut sources = vec![template];
+        if let Some((_, files)) = PAGE_SOURCES.iter().find(|(page, _)| page == route) {
+            sources.extend(files.iter().map(|file| content_dir.join(file)));
+        }
+        let mut page_images = Vec::new();
+        match *route {
+            "/modeling/" => sources.extend(category_images.iter().flat_map(|(paths, _)| paths.iter().cloned())),
+            "/behind-the-scenes/" => {
+                let (paths, image_urls) = images(&content_dir.join("Behind the scenes").join("images"), &urls.bts_images);
+                sources.extend(paths);
+                page_images = image_urls;
+            },
+            "/news/" => sources.extend(posts.iter().map(|post| post_path(&post.slug))),
+            _ => {},
+        }
+        pages.push(SitemapUrl { route: route.to_string(), lastmod: newest(&sources), images: page_images });
+    }
+
+    let modeling_template = content_dir.join("modeling").join("modeling.html");
+    if modeling_template.exists() {
+        for ((name, _), (paths, image_urls)) in categories.iter().zip(&category_images) {
+            let mut sources = paths.clone();
+            sources.push(modeling_template.clone());
+            pages.push(SitemapUrl {
+                route: format!("/modeling/{}/", slugify(name)),
+                lastmod: newest(&sources),
+                images: image_urls.clone(),
+            });
+        }
+    }
+
+    let albums = content_dir.join("music").join("albums.yaml");
+    for release in discography::read_releases(content_dir)? {
+        pages.push(SitemapUrl {
+            route: format!("/music/releases/{}/", release.slug),
+            lastmod: content::modified_time(&albums),
+            images: release.cover.iter().map(|cover| format!("{}/{}", urls.assets, url_encode_path(cover))).collect(),
+        });
+    }
+
+    if content_dir.join("news").join("news.html").exists() {
+        let page_count = posts::page_count(posts.len());
+        for page in 2..=page_count {
+            let paths: Vec<PathBuf> = posts::page(&posts, page).unwrap_or_default().iter().map(|post| post_path(&post.slug)).collect();
+            pages.push(SitemapUrl { route: format!("/news/page/{}/", page), lastmod: newest(&paths), images: Vec::new() });
+        }
+        for post in &posts {
+            pages.push(SitemapUrl {
+                route: format!("/news/{}/", post.slug),
+                lastmod: content::modified_time(&post_path(&post.slug)),
+                images: post.cover.iter().map(|cover| format!("{}/{}", urls.assets, url_encode_path(cover))).collect(),
+            });
+        }
+        for (_, slug) in posts::tags(&posts) {
+            let paths: Vec<PathBuf> = posts.iter().filter(|post| post.has_tag(&slug)).map(|post| post_path(&post.slug)).collect();
+            pages.push(SitemapUrl { route: format!("/news/tags/{}/", slug), lastmod: newest(&paths), images: Vec::new() });
+        }
+    }
+
+    pages.retain(|page| !is_disallowed(&page.route, disallow));
+    Ok(pages)
+}
+
+/// Renders the pages as a sitemap with the image extension.
+pub fn generate_sitemap(pages: &[SitemapUrl], urls: &SiteUrls) -> String {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str("<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\" xmlns:image=\"http://www.google.com/schemas/sitemap-image/1.1\">\n");
+    for page in pages {
+        xml.push_str("  <url>\n");
+        xml.push_str(&format!("    <loc>{}{}</loc>\n", html_escape(&urls.site), html_escape(&page.route)));
+        if let Some(lastmod) = page.lastmod {
+            xml.push_str(&format!("    <lastmod>{}</lastmod>\n", lastmod.to_rfc3339_opts(SecondsFormat::Secs, true)));
+        }
+        for image in &page.images {
+            xml.push_str(&format!("    <image:image><image:loc>{}</image:loc></image:image>\n", html_escape(image)));
+        }
+        xml.push_str("  </url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// Renders `robots.txt`. Rules are route prefixes; `path_prefix` is where
+/// the site lives on its host, e.g. `/Website-test`.
+pub fn generate_robots(disallow: &[String], path_prefix: &str, sitemap_url: Option<&str>) -> String {
+    let mut lines = vec!["User-agent: *".to_string()];
+    let rules: Vec<&String> = disallow.iter().filter(|rule| !rule.is_empty()).collect();
+    if rules.is_empty() {
+        lines.push("Disallow:".to_string());
+    }
+    lines.extend(rules.into_iter().map(|rule| format!("Disallow: {}{}", path_prefix, rule)));
+    if let Some(sitemap_url) = sitemap_url {
+        lines.push(String::new());
+        lines.push(format!("Sitemap: {}", sitemap_url));
+    }
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,798 @@
+//! Static site generation for GitHub Pages (`generate-static`).
+
+use chrono::{Local, NaiveDateTime, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+use crate::bundles::{self, Bundler};
+use crate::content::{self, CategoryData};
+use crate::credits;
+use crate::discography::{self, Release};
+use crate::events;
+use crate::feeds;
+use crate::render::{self, CartIndicator, EmbedStyle, ShopActions, generate_shop_html, generate_discography_html, generate_events_html, generate_featured_testimonials_html, generate_images_json, generate_media_embeds, generate_modeling_html, generate_news_html, generate_next_event_html, generate_post_html, generate_release_html, generate_resume_html, generate_testimonials_html};
+use crate::settings::{self, SiteSettings, VideoEmbedMode};
+use crate::shop;
+use crate::sitemap;
+use crate::thumbnails::{self, ThumbnailCache};
+use crate::media::{self, MediaGroup, MediaSource, YoutubeTarget};
+use crate::minify;
+use crate::output::{Output, OutputStats, Verbosity};
+use crate::posts::{self, Post};
+use crate::seo::{PageMeta, Seo};
+
+/// Path GitHub Pages serves the site under.
+pub const DEFAULT_BASE_PATH: &str = "/Website-test";
+
+/// Directory the site is built into unless told otherwise.
+pub const DEFAULT_OUT_DIR: &str = "docs";
+
+/// Version reported when none is given and git cannot tell.
+pub const DEFAULT_VERSION: &str = "dev";
+
+/// Inputs and outputs of a static build.
+#[derive(Clone, Debug)]
+pub struct BuildOptions {
+    /// Content directory with page templates, images and data files.
+    pub content_dir: PathBuf,
+    /// Directory the site is written to. Unchanged files are left alone
+    /// and files the build no longer produces are removed.
+    pub out_dir: PathBuf,
+    /// Version string the build reports; CSS bundles are named after
+    /// their contents instead.
+    pub version: String,
+    /// Thumbnail cache for lite video embeds.
+    pub thumbnails: ThumbnailCache,
+    /// Local time of the build; events that ended before it are archived.
+    pub build_time: NaiveDateTime,
+    /// Whether draft news posts are published.
+    pub drafts: bool,
+    /// Path the site is served under, without a trailing slash; empty at
+    /// the root of its host.
+    pub base_path: String,
+    /// Overrides `site_url` from `site.yaml` for absolute links.
+    pub site_url: Option<String>,
+    /// Whether generated pages are minified.
+    pub minify: bool,
+    /// Overrides `shop_url` from `site.yaml`.
+    pub shop_url: Option<String>,
+    pub verbosity: Verbosity,
+}
+
+impl Default for BuildOptions {
+    fn default() -> Self {
+        BuildOptions {
+            content_dir: PathBuf::from(content::DEFAULT_CONTENT_DIR),
+            out_dir: PathBuf::from(DEFAULT_OUT_DIR),
+            version: DEFAULT_VERSION.to_string(),
+            thumbnails: ThumbnailCache::default(),
+            build_time: Local::now().naive_local(),
+            drafts: false,
+            base_path: DEFAULT_BASE_PATH.to_string(),
+            site_url: None,
+            minify: false,
+            shop_url: None,
+            verbosity: Verbosity::Normal,
+        }
+    }
+}
+
+pub fn get_git_hash() -> String {
+    Command::new("git")
+        .args(["rev-parse", "--short", "HEAD"])
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|| DEFAULT_VERSION.to_string())
+}
+
+fn generate_page(title: &str, content: &str, meta: &PageMeta, options: &BuildOptions, bundler: &Bundler) -> String {
+    let base = options.base_path.as_str();
+    // GitHub Pages cannot keep carts, so the cart is the shop server's, if there is one
+    let cart_url = options.shop_url.as_ref().map(|shop_url| format!("{}/cart/", shop_url));
+    let cart = cart_url.as_deref().map_or(CartIndicator::Hidden, CartIndicator::Link);
+    let mut final_html = render::generate_page_with_cart(title, content, meta, cart);
+
+    // Update navigation links for GitHub Pages (static generation)
+    final_html = final_html.replace(
+        r#"<a href="/" class="nav-item">Home</a>"#,
+        &format!(r#"<a href="{}/index.html" class="nav-item">Home</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/bio/" class="nav-item">Bio</a>"#,
+        &format!(r#"<a href="{}/bio/index.html" class="nav-item">Bio</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/acting/" class="nav-item">Acting</a>"#,
+        &format!(r#"<a href="{}/acting/index.html" class="nav-item">Acting</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/music/" class="nav-item">Music</a>"#,
+        &format!(r#"<a href="{}/music/index.html" class="nav-item">Music</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/modeling/" class="nav-item">Modeling</a>"#,
+        &format!(r#"<a href="{}/modeling/index.html" class="nav-item">Modeling</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/reviews/" class="nav-item">Reviews</a>"#,
+        &format!(r#"<a href="{}/reviews/index.html" class="nav-item">Reviews</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/events/" class="nav-item">Events</a>"#,
+        &format!(r#"<a href="{}/events/index.html" class="nav-item">Events</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/news/" class="nav-item">News</a>"#,
+        &format!(r#"<a href="{}/news/index.html" class="nav-item">News</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/behind-the-scenes/" class="nav-item">Behind the Scenes</a>"#,
+        &format!(r#"<a href="{}/behind-the-scenes/index.html" class="nav-item">Behind the Scenes</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/shop/" class="nav-item">Shop</a>"#,
+        &format!(r#"<a href="{}/shop/index.html" class="nav-item">Shop</a>"#, base)
+    );
+    final_html = final_html.replace(
+        r#"<a href="/contact/" class="nav-item">Contact</a>"#,
+        &format!(r#"<a href="{}/contact/index.html" class="nav-item">Contact</a>"#, base)
+    );
+
+    // Update image paths for GitHub Pages deployment
+    final_html = final_html.replace(
+        r#"src="/templates/global-images/"#,
+        &format!(r#"src="{}/global-images/"#, base)
+    );
+
+    // Update background image paths for GitHub Pages deployment
+    final_html = final_html.replace(
+        r#"url('/templates/global-images/"#,
+        &format!(r#"url('{}/global-images/"#, base)
+    );
+
+    // Update feed paths for GitHub Pages deployment
+    final_html = final_html.replace(r#"href="/feed.xml""#, &format!(r#"href="{}/feed.xml""#, base));
+    final_html = final_html.replace(r#"href="/feed.json""#, &format!(r#"href="{}/feed.json""#, base));
+
+    // Move page styles into bundles, which are fingerprinted instead of cache busted
+    final_html = bundler.apply(&final_html, meta.route.as_deref(), &format!("{}/{}", base, bundles::CSS_DIR));
+
+    if options.minify { minify::html(&final_html) } else { final_html }
+}
+
+/// Queues the images in `source_dir` to be copied to `dest_dir`.
+fn copy_images(output: &Output, source_dir: &Path, dest_dir: &Path) {
+    for filename in content::image_file_names(source_dir) {
+        output.copy(&source_dir.join(&filename), &dest_dir.join(&filename));
+    }
+}
+
+/// Copies each modeling category's images and background into the output.
+fn copy_category_images(content_dir: &Path, output: &Output, categories: &[(String, CategoryData)]) {
+    for (category_name, data) in categories {
+        let category_dir = content_dir.join("modeling").join(category_name);
+        let docs_category_dir = output.dir().join("modeling").join(category_name);
+
+        copy_images(output, &category_dir.join("images"), &docs_category_dir.join("images"));
+
+        if data.background.is_some() {
+            copy_images(output, &category_dir.join("Background"), &docs_category_dir.join("Background"));
+        }
+    }
+}
+
+/// Copies the thumbnail of every YouTube video in `groups` into
+/// `thumbnails/`, returning each video ID's thumbnail URL. Videos whose
+/// thumbnail is not cached and cannot be fetched are left out and use the
+/// placeholder.
+fn copy_thumbnails(groups: &[MediaGroup], cache: &ThumbnailCache, output: &Output, base: &str) -> HashMap<String, String> {
+    let thumbnails_dir = output.dir().join("thumbnails");
+
+    let mut urls = HashMap::new();
+    for item in groups.iter().flat_map(|group| &group.items) {
+        let MediaSource::Youtube(YoutubeTarget::Video(id)) = &item.source else {
+            continue;
+        };
+        if urls.contains_key(id) {
+            continue;
+        }
+
+        let file_name = format!("{}.jpg", id);
+        match cache.get(id) {
+            Ok(cached) => {
+                output.copy(&cached, &thumbnails_dir.join(&file_name));
+                urls.insert(id.clone(), format!("{}/thumbnails/{}", base, file_name));
+            },
+            Err(e) => output.note(format_args!("Using placeholder thumbnail: {}", e)),
+        }
+    }
+
+    urls
+}
+
+/// Renders a page folder's media list, with YouTube videos in the site's
+/// embed mode, and copies its audio and video files next to the page.
+fn render_media(content_dir: &Path, folder: &str, groups: &[MediaGroup], settings: &SiteSettings, cache: &ThumbnailCache, output: &Output, base: &str) -> String {
+    let folder_dir = content_dir.join(folder);
+    for file_name in media::local_media_files(&folder_dir) {
+        output.copy(&folder_dir.join(&file_name), &output.dir().join(folder).join(&file_name));
+    }
+
+    match settings.video_embeds {
+        VideoEmbedMode::Iframe => generate_media_embeds(groups, EmbedStyle::Iframe),
+        VideoEmbedMode::Lite => {
+            let urls = copy_thumbnails(groups, cache, output, base);
+            let placeholder = format!("{}/thumbnails/{}", base, thumbnails::PLACEHOLDER_NAME);
+            let thumbnail_url = |target: &YoutubeTarget| match target {
+                YoutubeTarget::Video(id) => urls.get(id).cloned().unwrap_or_else(|| placeholder.clone()),
+                YoutubeTarget::Playlist(_) => placeholder.clone(),
+            };
+            generate_media_embeds(groups, EmbedStyle::Lite(&thumbnail_url))
+        },
+    }
+}
+
+/// Copies a file given relative to the content directory to the same place
+/// in the output, unless it is already there.
+fn copy_content_file(content_dir: &Path, output: &Output, path: &str) {
+    output.copy(&content_dir.join(path), &output.dir().join(path));
+}
+
+/// Writes a detail page for each release under `music/releases/` and copies
+/// covers that are not already part of the output.
+fn generate_release_pages(content_dir: &Path, output: &Output, releases: &[Release], seo: &Seo, options: &BuildOptions, bundler: &Bundler) {
+    let base = options.base_path.as_str();
+    let docs_dir = output.dir();
+    for release in releases {
+        if let Some(cover) = &release.cover {
+            copy_content_file(content_dir, output, cover);
+        }
+
+        let release_dir = docs_dir.join("music").join("releases").join(&release.slug);
+        let content = generate_release_html(release, &format!("{}/music/index.html", base), base);
+        let html = generate_page(&release.title, &content, &seo.release(release), options, bundler);
+        output.write(&release_dir.join("index.html"), html);
+        output.note(format_args!("Generated music/releases/{}/index.html", release.slug));
+    }
+}
+
+/// Writes the news list pages, one page per post and one per tag, and copies
+/// the files posts refer to.
+fn generate_news_pages(content_dir: &Path, output: &Output, template: &str, posts: &[Post], seo: &Seo, options: &BuildOptions, bundler: &Bundler) {
+    let base = options.base_path.as_str();
+    let docs_dir = output.dir();
+    let news_dir = docs_dir.join("news");
+    let write_page = |dir: &Path, title: &str, content: &str, meta: &PageMeta| {
+        output.write(&dir.join("index.html"), generate_page(title, content, meta, options, bundler));
+    };
+
+    let page_count = posts::page_count(posts.len());
+    for page in 1..=page_count {
+        let page_posts = posts::page(posts, page).unwrap_or_default();
+        let news_html = generate_news_html(page_posts, None, page, page_count, &format!("{}/news", base), base);
+        let (dir, route) = if page == 1 {
+            (news_dir.clone(), "/news/".to_string())
+        } else {
+            (news_dir.join("page").join(page.to_string()), format!("/news/page/{}/", page))
+        };
+        write_page(&dir, "News", &template.replace("{{NEWS}}", &news_html), &seo.page(&route));
+    }
+
+    for post in posts {
+        if let Some(cover) = &post.cover {
+            copy_content_file(content_dir, output, cover);
+        }
+        write_page(&news_dir.join(&post.slug), &post.title, &generate_post_html(post, &format!("{}/news", base), base), &seo.post(post));
+    }
+
+    for (name, slug) in posts::tags(posts) {
+        let tagged: Vec<Post> = posts.iter().filter(|post| post.has_tag(&slug)).cloned().collect();
+        let news_html = generate_news_html(&tagged, Some(&format!("Tagged “{}”", name)), 1, 1, &format!("{}/news", base), base);
+        write_page(
+            &news_dir.join("tags").join(&slug),
+            &format!("News: {}", name),
+            &template.replace("{{NEWS}}", &news_html),
+            &seo.page(&format!("/news/tags/{}/", slug)),
+        );
+    }
+
+    // Images and other files posts link to
+    let posts_src = content_dir.join("posts");
+    if let Ok(entries) = fs::read_dir(&posts_src) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() && path.extension().and_then(|e| e.to_str()) != Some("md") {
+                output.copy(&path, &docs_dir.join("posts").join(entry.file_name()));
+            }
+        }
+    }
+
+    output.note(format_args!("Generated news/ ({} posts, {} pages)", posts.len(), page_count));
+}
+
+/// Writes `acting/resume.pdf` from `credits.yaml` and returns the résumé
+/// section for the acting page, or nothing when there are no credits.
+fn generate_resume(content_dir: &Path, output: &Output, acting_dir: &Path, base: &str) -> String {
+    let resume = match credits::read_resume(content_dir) {
+        Ok(Some(resume)) => resume,
+        Ok(None) => return String::new(),
+        Err(e) => {
+            output.fail(format!("Failed to read acting credits: {}", e));
+            return String::new();
+        }
+    };
+
+    // The PDF differs on every render, so it is only redone when the credits change
+    let pdf_path = acting_dir.join(credits::RESUME_PDF_NAME);
+    let pdf_url = output.write_from(&pdf_path, format!("{:?}", resume).as_bytes(), || credits::resume_pdf(&resume)).then(|| {
+        output.note(format_args!("Generated acting/{}", credits::RESUME_PDF_NAME));
+        format!("{}/acting/{}", base, credits::RESUME_PDF_NAME)
+    });
+    generate_resume_html(&resume, pdf_url.as_deref())
+}
+
+/// Outcome of a build.
+#[derive(Clone, Debug, Default)]
+pub struct BuildReport {
+    /// What changed in the output directory; all zero when the build failed.
+    pub stats: OutputStats,
+    /// Everything that went wrong. The output directory is left as it was
+    /// when there is anything here.
+    pub errors: Vec<String>,
+}
+
+impl BuildReport {
+    /// Whether the build succeeded and its output can be deployed.
+    pub fn is_publishable(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Builds the site into a staging directory and, when nothing went wrong,
+/// swaps it in for `options.out_dir`.
+pub fn build(options: &BuildOptions) -> BuildReport {
+    let content_dir = options.content_dir.as_path();
+    let docs_dir = options.out_dir.as_path();
+    let base = options.base_path.as_str();
+
+    let started = Instant::now();
+    let output = match Output::open(docs_dir, options.verbosity) {
+        Ok(output) => output,
+        Err(e) => return BuildReport { stats: OutputStats::default(), errors: vec![e] },
+    };
+    output.note(format_args!("Building with version: {}", options.version));
+
+    // Carry on with defaults, to report any other problems as well
+    let mut settings = settings::read_site_settings(content_dir).unwrap_or_else(|e| {
+        output.fail(format!("Failed to read site settings: {}", e));
+        SiteSettings::default()
+    });
+    if let Some(site_url) = &options.site_url {
+        settings.site_url = Some(site_url.clone());
+    }
+    let options = &BuildOptions {
+        shop_url: options.shop_url.clone().or_else(|| settings.shop_url.clone()).map(|url| url.trim_end_matches('/').to_string()),
+        ..options.clone()
+    };
+    let seo = Seo::new(&settings);
+
+    // Pages link the shared CSS bundle, with the theme, and their own, written out at the end
+    let bundler = Bundler::load(content_dir).unwrap_or_else(|e| {
+        output.fail(format!("Failed to bundle styles: {}", e));
+        Bundler::new("")
+    });
+
+    // Copy global images folder from templates to docs
+    let templates_global_images = content_dir.join("global-images");
+    let docs_global_images = docs_dir.join("global-images");
+
+    if templates_global_images.exists() {
+        copy_images(&output, &templates_global_images, &docs_global_images);
+    }
+
+    // Lite video embeds fall back to this when a thumbnail is unavailable
+    if settings.video_embeds == VideoEmbedMode::Lite {
+        let thumbnails_dir = docs_dir.join("thumbnails");
+        output.write(&thumbnails_dir.join(thumbnails::PLACEHOLDER_NAME), thumbnails::PLACEHOLDER_SVG);
+    }
+
+    // Discover modeling categories and copy their images
+    let categories = content::discover_modeling_categories(content_dir, ".");
+    copy_category_images(content_dir, &output, &categories);
+    output.note("\nModeling categories discovered:");
+    for (name, data) in &categories {
+        output.note(format_args!("  - {} ({} images)", name, data.images.len()));
+    }
+
+    let events = events::read_events(content_dir).unwrap_or_else(|e| {
+        output.fail(format!("Failed to read events: {}", e));
+        Vec::new()
+    });
+    let (upcoming_events, past_events) = events::split_events(&events, options.build_time);
+    let testimonials = content::read_testimonials(content_dir).unwrap_or_else(|e| {
+        output.fail(format!("Failed to read testimonials: {}", e));
+        Vec::new()
+    });
+
+    // Generate home page
+    let home_path = content_dir.join("index.html");
+    match fs::read_to_string(&home_path) {
+        Ok(content) => {
+            let next_event_html = generate_next_event_html(upcoming_events.first().copied(), &format!("{}/events/index.html", base));
+            let featured_html = generate_featured_testimonials_html(&testimonials, &format!("{}/reviews/index.html", base), base);
+            let content = content
+                .replace("{{NEXT_EVENT}}", &next_event_html)
+                .replace("{{FEATURED_TESTIMONIALS}}", &featured_html);
+            let html = generate_page("Home", &content, &seo.home(), options, &bundler);
+            output.write(&docs_dir.join("index.html"), html);
+            output.note("Generated index.html");
+        },
+        Err(e) => {
+            output.fail(format!("Failed to read home template: {}", e));
+        }
+    }
+
+    // Generate unified modeling page
+    let modeling_path = content_dir.join("modeling").join("modeling.html");
+    match fs::read_to_string(&modeling_path) {
+        Ok(content) => {
+            let html = generate_page("Modeling Portfolio", &generate_modeling_html(&content, &categories, None), &seo.modeling(&categories), options, &bundler);
+            output.write(&docs_dir.join("modeling").join("index.html"), html);
+            output.note("Generated modeling/index.html");
+
+            // One page per category, a level deeper, so image URLs go up one
+            let category_pages = content::discover_modeling_categories(content_dir, "..");
+            for (name, category) in &category_pages {
+                let slug = discography::slugify(name);
+                let category_dir = docs_dir.join("modeling").join(&slug);
+                let content = generate_modeling_html(&content, &category_pages, Some(name));
+                let html = generate_page(&format!("Modeling: {}", category.title), &content, &seo.modeling_category(name, category), options, &bundler);
+                output.write(&category_dir.join("index.html"), html);
+                output.note(format_args!("Generated modeling/{}/index.html", slug));
+            }
+        },
+        Err(e) => {
+            output.fail(format!("Failed to read modeling template: {}", e));
+        }
+    }
+
+    // Generate bio page
+    let bio_dir = docs_dir.join("bio");
+
+    // Copy bio background image
+    let bio_bg_src = content_dir.join("bio").join("background");
+    let bio_bg_dest = bio_dir.join("background");
+    if bio_bg_src.exists() {
+        copy_images(&output, &bio_bg_src, &bio_bg_dest);
+    }
+
+    let bio_path = content_dir.join("bio").join("bio.html");
+    if bio_path.exists() {
+        match fs::read_to_string(&bio_path) {
+            Ok(content) => {
+                // Update background image path for GitHub Pages
+                let updated_content = content.replace(
+                    "url('/templates/bio/background/bkgrnd.png')",
+                    &format!("url('{}/bio/background/bkgrnd.png')", base)
+                );
+                let html = generate_page("Bio", &updated_content, &seo.bio(), options, &bundler);
+                let file_path = bio_dir.join("index.html");
+                output.write(&file_path, html);
+                output.note("Generated bio/index.html");
+            },
+            Err(e) => {
+                output.fail(format!("Failed to read bio template: {}", e));
+            }
+        }
+    }
+
+    // Generate music page
+    let music_dir = docs_dir.join("music");
+
+    // Copy music background image
+    let music_bg_src = content_dir.join("music").join("background");
+    let music_bg_dest = music_dir.join("background");
+    if music_bg_src.exists() {
+        copy_images(&output, &music_bg_src, &music_bg_dest);
+    }
+
+    let releases = discography::read_releases(content_dir).unwrap_or_else(|e| {
+        output.fail(format!("Failed to read discography: {}", e));
+        Vec::new()
+    });
+
+    let music_path = content_dir.join("music").join("music.html");
+    if music_path.exists() {
+        match fs::read_to_string(&music_path) {
+            Ok(content) => {
+                // Generate media embeds and the discography
+                let groups = media::read_media(content_dir, "music", ".").unwrap_or_else(|e| {
+                    output.fail(format!("Failed to read music media: {}", e));
+                    Vec::new()
+                });
+                let embeds_html = render_media(content_dir, "music", &groups, &settings, &options.thumbnails, &output, base);
+                let content = content
+                    .replace("{{YOUTUBE_EMBEDS}}", &embeds_html)
+                    .replace("{{DISCOGRAPHY}}", &generate_discography_html(&releases, &format!("{}/music/releases", base), base));
+
+                // Update background image path for GitHub Pages
+                let updated_content = content.replace(
+                    "url('/templates/music/background/bkgrnd.png')",
+                    &format!("url('{}/music/background/bkgrnd.png')", base)
+                );
+                let html = generate_page("Music", &updated_content, &seo.music(&groups, &releases), options, &bundler);
+                let file_path = music_dir.join("index.html");
+                output.write(&file_path, html);
+                output.note("Generated music/index.html");
+            },
+            Err(e) => {
+                output.fail(format!("Failed to read music template: {}", e));
+            }
+        }
+    }
+
+    generate_release_pages(content_dir, &output, &releases, &seo, options, &bundler);
+
+    // Generate contact page
+    let contact_dir = docs_dir.join("contact");
+
+    let contact_path = content_dir.join("contact").join("contact.html");
+    if contact_path.exists() {
+        match fs::read_to_string(&contact_path) {
+            Ok(content) => {
+                let html = generate_page("Contact", &content, &seo.page("/contact/"), options, &bundler);
+                let file_path = contact_dir.join("index.html");
+                output.write(&file_path, html);
+                output.note("Generated contact/index.html");
+            },
+            Err(e) => {
+                output.fail(format!("Failed to read contact template: {}", e));
+            }
+        }
+    }
+
+    // Generate events page and calendar feed
+    let events_path = content_dir.join("events").join("events.html");
+    if events_path.exists() {
+        match fs::read_to_string(&events_path) {
+            Ok(content) => {
+                let events_html = generate_events_html(&upcoming_events, &past_events, &format!("{}/{}", base, events::ICS_NAME));
+                let content = content.replace("{{EVENTS}}", &events_html);
+                let html = generate_page("Events", &content, &seo.page("/events/"), options, &bundler);
+                let events_dir = docs_dir.join("events");
+                output.write(&events_dir.join("index.html"), html);
+                output.note(format_args!("Generated events/index.html ({} upcoming, {} past)", upcoming_events.len(), past_events.len()));
+            },
+            Err(e) => {
+                output.fail(format!("Failed to read events template: {}", e));
+            }
+        }
+    }
+    // Stamped with the events file's time so that unchanged events give an unchanged calendar
+    let ics_stamp = content::modified_time(&content_dir.join("events").join("events.yaml")).unwrap_or_else(Utc::now);
+    output.write(&docs_dir.join(events::ICS_NAME), events::generate_ics(&events, ics_stamp));
+    output.note(format_args!("Generated {}", events::ICS_NAME));
+
+    // Generate news pages
+    let news_path = content_dir.join("news").join("news.html");
+    if news_path.exists() {
+        match (fs::read_to_string(&news_path), posts::read_posts(content_dir, base, options.drafts)) {
+            (Ok(template), Ok(posts)) => generate_news_pages(content_dir, &output, &template, &posts, &seo, options, &bundler),
+            (Err(e), _) => output.fail(format!("Failed to read news template: {}", e)),
+            (_, Err(e)) => output.fail(format!("Failed to read posts: {}", e)),
+        }
+    }
+
+    // Generate acting page
+    let acting_dir = docs_dir.join("acting");
+
+    // Copy acting background image
+    let acting_bg_src = content_dir.join("acting").join("Background");
+    let acting_bg_dest = acting_dir.join("Background");
+    if acting_bg_src.exists() {
+        copy_images(&output, &acting_bg_src, &acting_bg_dest);
+    }
+
+    let acting_path = content_dir.join("acting").join("acting.html");
+    if acting_path.exists() {
+        match fs::read_to_string(&acting_path) {
+            Ok(content) => {
+                // Generate media embeds
+                let groups = media::read_media(content_dir, "acting", ".").unwrap_or_else(|e| {
+                    output.fail(format!("Failed to read acting media: {}", e));
+                    Vec::new()
+                });
+                let embeds_html = render_media(content_dir, "acting", &groups, &settings, &options.thumbnails, &output, base);
+                let resume_html = generate_resume(content_dir, &output, &acting_dir, base);
+                let content = content
+                    .replace("{{ACTING_YOUTUBE_EMBEDS}}", &embeds_html)
+                    .replace("{{ACTING_RESUME}}", &resume_html);
+
+                // Update background image path for GitHub Pages
+                let updated_content = content.replace(
+                    "url('/templates/acting/Background/bckgrnd.png')",
+                    &format!("url('{}/acting/Background/bckgrnd.png')", base)
+                );
+                let html = generate_page("Acting", &updated_content, &seo.media("/acting/", &groups), options, &bundler);
+                let file_path = acting_dir.join("index.html");
+                output.write(&file_path, html);
+                output.note("Generated acting/index.html");
+            },
+            Err(e) => {
+                output.fail(format!("Failed to read acting template: {}", e));
+            }
+        }
+    }
+
+    // Generate reviews page
+    let reviews_dir = docs_dir.join("reviews");
+    for photo in testimonials.iter().filter_map(|testimonial| testimonial.photo.as_deref()) {
+        copy_content_file(content_dir, &output, photo);
+    }
+
+    let reviews_path = content_dir.join("reviews").join("reviews.html");
+    if reviews_path.exists() {
+        match fs::read_to_string(&reviews_path) {
+            Ok(mut content) => {
+                let testimonials_html = generate_testimonials_html(&testimonials, base);
+                // Reviews are submitted to the server; GitHub Pages has none
+                content = content.replace("{{TESTIMONIALS_HTML}}", &testimonials_html).replace("{{REVIEW_FORM}}", "");
+                let html = generate_page("Reviews", &content, &seo.reviews(&testimonials), options, &bundler);
+                let file_path = reviews_dir.join("index.html");
+                output.write(&file_path, html);
+                output.note("Generated reviews/index.html");
+            },
+            Err(e) => {
+                output.fail(format!("Failed to read reviews template: {}", e));
+            }
+        }
+    }
+
+    // Generate behind-the-scenes page
+    let bts_dir = docs_dir.join("behind-the-scenes");
+
+    // Copy BTS images
+    let bts_images_src = content_dir.join("Behind the scenes").join("images");
+    let bts_images_dest = bts_dir.join("images");
+    if bts_images_src.exists() {
+        copy_images(&output, &bts_images_src, &bts_images_dest);
+    }
+
+    // Copy BTS background image
+    let bts_bg_src = content_dir.join("Behind the scenes").join("background");
+    let bts_bg_dest = bts_dir.join("background");
+    if bts_bg_src.exists() {
+        copy_images(&output, &bts_bg_src, &bts_bg_dest);
+    }
+
+    let bts_path = content_dir.join("Behind the scenes").join("behind-the-scenes.html");
+    if bts_path.exists() {
+        match fs::read_to_string(&bts_path) {
+            Ok(content) => {
+                let subtitle = content::read_bts_subtitle(content_dir);
+                let images = content::list_images(&bts_images_src, "./images");
+
+                // Update background image path for GitHub Pages
+                let updated_content = content
+                    .replace("{{BTS_IMAGES_JSON}}", &generate_images_json(&images))
+                    .replace("{{BTS_SUBTITLE}}", &subtitle)
+                    .replace(
+                        "url('/templates/Behind the scenes/background/bkgrnd.png')",
+                        &format!("url('{}/behind-the-scenes/background/bkgrnd.png')", base)
+                    );
+
+                let html = generate_page("Behind the Scenes", &updated_content, &seo.page("/behind-the-scenes/"), options, &bundler);
+                let file_path = bts_dir.join("index.html");
+                output.write(&file_path, html);
+                output.note("Generated behind-the-scenes/index.html");
+            },
+            Err(e) => {
+                output.fail(format!("Failed to read behind-the-scenes template: {}", e));
+            }
+        }
+    }
+
+    // Generate shop page; orders go to the shop server, the site only lists the products
+    let shop_path = content_dir.join("shop").join("shop.html");
+    if shop_path.exists() {
+        match (fs::read_to_string(&shop_path), shop::read_catalog(content_dir)) {
+            (Ok(content), Ok(catalog)) => {
+                for image in catalog.products.iter().filter_map(|product| product.image.as_deref()) {
+                    copy_content_file(content_dir, &output, image);
+                }
+                let shop_url = options.shop_url.as_ref().map(|shop_url| format!("{}/shop/", shop_url));
+                let actions = shop_url.as_deref().map_or(ShopActions::Disabled, ShopActions::Link);
+                let products_html = generate_shop_html(&catalog, &HashMap::new(), actions, base);
+                let html = generate_page("Shop", &content.replace("{{PRODUCTS}}", &products_html), &seo.page("/shop/"), options, &bundler);
+                output.write(&docs_dir.join("shop").join("index.html"), html);
+                output.note(format_args!("Generated shop/index.html ({} products)", catalog.products.len()));
+            },
+            (Err(e), _) => output.fail(format!("Failed to read shop template: {}", e)),
+            (_, Err(e)) => output.fail(format!("Failed to read products: {}", e)),
+        }
+    }
+
+    // Generate the Atom and JSON feeds, which need absolute URLs
+    match &settings.site_url {
+        Some(site_url) => {
+            let urls = feeds::SiteUrls::for_static_site(site_url);
+            match feeds::collect_entries(content_dir, &urls) {
+                Ok(entries) => {
+                    output.write(&docs_dir.join(feeds::ATOM_NAME), feeds::atom(&entries, &urls));
+                    output.write(&docs_dir.join(feeds::JSON_FEED_NAME), feeds::json_feed(&entries, &urls));
+                    output.note(format_args!("Generated {} and {} ({} entries)", feeds::ATOM_NAME, feeds::JSON_FEED_NAME, entries.len()));
+                },
+                Err(e) => output.fail(format!("Failed to collect feed entries: {}", e)),
+            }
+        },
+        None => output.note("Skipping feeds: site_url is not set in site.yaml"),
+    }
+
+    // Generate the sitemap, which also needs absolute URLs, and robots.txt
+    let sitemap_url = settings.site_url.as_deref().and_then(|site_url| {
+        let urls = feeds::SiteUrls::for_static_site(site_url);
+        match sitemap::collect_urls(content_dir, &urls, &settings.robots_disallow) {
+            Ok(pages) => {
+                output.write(&docs_dir.join(sitemap::SITEMAP_NAME), sitemap::generate_sitemap(&pages, &urls));
+                output.note(format_args!("Generated {} ({} pages)", sitemap::SITEMAP_NAME, pages.len()));
+                Some(format!("{}/{}", urls.site, sitemap::SITEMAP_NAME))
+            },
+            Err(e) => {
+                output.fail(format!("Failed to collect sitemap pages: {}", e));
+                None
+            }
+        }
+    });
+    let robots = sitemap::generate_robots(&settings.robots_disallow, base, sitemap_url.as_deref());
+    output.write(&docs_dir.join(sitemap::ROBOTS_NAME), robots);
+    output.note(format_args!("Generated {}", sitemap::ROBOTS_NAME));
+
+    // Generate 404 page (served by GitHub Pages for any unknown path)
+    let not_found_content = include_str!("../templates/404.html")
+        .replace("{{MESSAGE}}", "The page you're looking for doesn't exist or has moved.")
+        .replace(
+            r#"<a href="/" class="error-home-link">"#,
+            &format!(r#"<a href="{}/index.html" class="error-home-link">"#, base)
+        );
+    let not_found_html = generate_page("404 - Page Not Found", &not_found_content, &PageMeta::noindex(), options, &bundler);
+    output.write(&docs_dir.join("404.html"), not_found_html);
+    output.note("Generated 404.html");
+
+    let css_dir = docs_dir.join(bundles::CSS_DIR);
+    let bundles = bundler.bundles();
+    for bundle in &bundles {
+        output.write(&css_dir.join(&bundle.name), &bundle.css);
+    }
+    output.note(format_args!("Generated {}/ ({} bundles)", bundles::CSS_DIR, bundles.len()));
+
+    let rendered = started.elapsed();
+    let stats = match output.finish() {
+        Ok(stats) => stats,
+        Err(errors) => {
+            eprintln!("\nBuild failed with {} error(s); {} was left unchanged:", errors.len(), docs_dir.display());
+            for error in &errors {
+                eprintln!("  - {}", error);
+            }
+            return BuildReport { stats: OutputStats::default(), errors };
+        }
+    };
+    let total = started.elapsed();
+    if options.verbosity >= Verbosity::Normal {
+        println!(
+            "\nStatic files generated successfully in {:.2}s (pages {:.2}s, files {:.2}s): {} written, {} copied, {} unchanged, {} stale removed",
+            total.as_secs_f64(),
+            rendered.as_secs_f64(),
+            (total - rendered).as_secs_f64(),
+            stats.written,
+            stats.copied,
+            stats.unchanged,
+            stats.removed
+        );
+    }
+    BuildReport { stats, errors: Vec::new() }
+}
@@ -0,0 +1,202 @@
+//! Design tokens from `theme.yaml`, compiled into CSS custom properties.
+//!
+//! Tokens are grouped into colors, fonts, radii and backgrounds and become
+//! `--color-<name>`, `--font-<name>`, `--radius-<name>` and
+//! `--background-<name>` on `:root`, which the stylesheets use through
+//! `var()`. A theme can build on another with `extends` and give a `dark`
+//! set of tokens for visitors whose system prefers a dark color scheme.
+//!
+//! Pages are themed by route: an entry under `pages` in `theme.yaml`, or a
+//! `theme.yaml` in a modeling category's folder next to its `Background/`,
+//! changes the tokens of that page only.
+
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::content;
+use crate::discography;
+
+/// File name of the site theme and of a category's own.
+pub const THEME_NAME: &str = "theme.yaml";
+
+/// The theme of a content directory without a `theme.yaml`.
+pub const DEFAULT_THEME: &str = include_str!("../templates/theme.yaml");
+
+/// One set of token values, each group keyed by token name.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tokens {
+    pub colors: BTreeMap<String, String>,
+    pub fonts: BTreeMap<String, String>,
+    pub radii: BTreeMap<String, String>,
+    pub backgrounds: BTreeMap<String, String>,
+}
+
+impl Tokens {
+    fn groups(&self) -> [(&'static str, &BTreeMap<String, String>); 4] {
+        [("color", &self.colors), ("font", &self.fonts), ("radius", &self.radii), ("background", &self.backgrounds)]
+    }
+
+    /// Overrides these tokens with those `other` sets.
+    fn extend(&mut self, other: &Tokens) {
+        self.colors.extend(other.colors.clone());
+        self.fonts.extend(other.fonts.clone());
+        self.radii.extend(other.radii.clone());
+        self.backgrounds.extend(other.backgrounds.clone());
+    }
+
+    fn is_empty(&self) -> bool {
+        self.groups().iter().all(|(_, tokens)| tokens.is_empty())
+    }
+
+    /// The tokens as declarations of a `:root` rule.
+    fn declarations(&self, indent: &str) -> Result<String, String> {
+        let mut css = String::new();
+        for (group, tokens) in self.groups() {
+            for (name, value) in tokens {
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                    return Err(format!("invalid {} token name {:?}", group, name));
+                }
+                let unbalanced = value.matches('(').count() != value.matches(')').count();
+                if value.trim().is_empty() || value.contains([';', '{', '}']) || unbalanced {
+                    return Err(format!("invalid value {:?} for {} token {:?}", value, group, name));
+                }
+                let _ = writeln!(css, "{}--{}-{}: {};", indent, group, name, value.trim());
+            }
+        }
+        Ok(css)
+    }
+}
+
+/// A named theme, or the override of one page. The token groups are its
+/// own fields rather than a flattened [`Tokens`], so that a misspelt group
+/// is an error instead of being ignored.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    /// Theme whose tokens this one starts from. Page overrides start from
+    /// the site's theme unless they name another.
+    pub extends: Option<String>,
+    pub colors: BTreeMap<String, String>,
+    pub fonts: BTreeMap<String, String>,
+    pub radii: BTreeMap<String, String>,
+    pub backgrounds: BTreeMap<String, String>,
+    /// Tokens that change when the visitor prefers a dark color scheme.
+    pub dark: Tokens,
+}
+
+impl Theme {
+    /// The theme's light tokens.
+    pub fn tokens(&self) -> Tokens {
+        Tokens {
+            colors: self.colors.clone(),
+            fonts: self.fonts.clone(),
+            radii: self.radii.clone(),
+            backgrounds: self.backgrounds.clone(),
+        }
+    }
+}
+
+/// The contents of `theme.yaml`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeSettings {
+    /// Theme every page uses unless overridden.
+    pub theme: String,
+    pub themes: BTreeMap<String, Theme>,
+    /// Overrides keyed by route, e.g. `/bio/` or `/modeling/glamour/`.
+    pub pages: BTreeMap<String, Theme>,
+}
+
+impl Default for ThemeSettings {
+    fn default() -> Self {
+        ThemeSettings { theme: "default".to_string(), themes: BTreeMap::new(), pages: BTreeMap::new() }
+    }
+}
+
+/// The site's theme as CSS, and the CSS of each page with an override.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ThemeStyles {
+    pub site: String,
+    pub pages: BTreeMap<String, String>,
+}
+
+impl ThemeSettings {
+    /// Light and dark tokens of the named theme, with those of the themes
+    /// it extends underneath.
+    fn resolve(&self, name: &str, seen: &mut HashSet<String>) -> Result<(Tokens, Tokens), String> {
+        if !seen.insert(name.to_string()) {
+            return Err(format!("theme {:?} extends itself", name));
+        }
+        let theme = self.themes.get(name).ok_or_else(|| format!("unknown theme {:?}", name))?;
+        let (mut light, mut dark) = match &theme.extends {
+            Some(parent) => self.resolve(parent, seen)?,
+            None => (Tokens::default(), Tokens::default()),
+        };
+        light.extend(&theme.tokens());
+        dark.extend(&theme.dark);
+        Ok((light, dark))
+    }
+
+    /// Compiles the site's theme and every page override.
+    pub fn stylesheets(&self) -> Result<ThemeStyles, String> {
+        let (light, dark) = self.resolve(&self.theme, &mut HashSet::new())?;
+        let site = stylesheet(&light, &dark)?;
+        let mut pages = BTreeMap::new();
+        for (route, theme) in &self.pages {
+            let base = theme.extends.as_deref().unwrap_or(&self.theme);
+            let (mut light, mut dark) = self.resolve(base, &mut HashSet::new()).map_err(|e| format!("{}: {}", route, e))?;
+            light.extend(&theme.tokens());
+            dark.extend(&theme.dark);
+            pages.insert(route.clone(), stylesheet(&light, &dark).map_err(|e| format!("{}: {}", route, e))?);
+        }
+        Ok(ThemeStyles { site, pages })
+    }
+}
+
+/// `:root` rules setting the tokens, the dark ones behind a media query.
+/// A page with dark tokens repeats them all, so that its light tokens do
+/// not win over the site's dark ones.
+fn stylesheet(light: &Tokens, dark: &Tokens) -> Result<String, String> {
+    let mut css = format!(":root {{\n{}}}\n", light.declarations("    ")?);
+    if !dark.is_empty() {
+        let _ = write!(css, "@media (prefers-color-scheme: dark) {{\n    :root {{\n{}    }}\n}}\n", dark.declarations("        ")?);
+    }
+    Ok(css)
+}
+
+fn parse(content: &str, path: &Path) -> Result<ThemeSettings, String> {
+    if content.trim().is_empty() {
+        return Ok(ThemeSettings::default());
+    }
+    serde_yaml::from_str(content).map_err(|e| format!("invalid {}: {}", path.display(), e))
+}
+
+/// Reads `theme.yaml`, falling back to the built-in theme when there is
+/// none, and adds the overrides of modeling categories that have their own.
+pub fn read_theme(content_dir: &Path) -> Result<ThemeSettings, String> {
+    let path = content_dir.join(THEME_NAME);
+    let mut settings = if path.exists() {
+        let content = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+        parse(&content, &path)?
+    } else {
+        parse(DEFAULT_THEME, &path)?
+    };
+
+    for (name, _) in content::discover_modeling_categories(content_dir, "") {
+        let category_path = content_dir.join("modeling").join(&name).join(THEME_NAME);
+        if !category_path.exists() {
+            continue;
+        }
+        let content = fs::read_to_string(&category_path).map_err(|e| format!("failed to read {}: {}", category_path.display(), e))?;
+        let theme: Theme = serde_yaml::from_str(&content).map_err(|e| format!("invalid {}: {}", category_path.display(), e))?;
+        let route = format!("/modeling/{}/", discography::slugify(&name));
+        if settings.pages.insert(route.clone(), theme).is_some() {
+            return Err(format!("{} and pages in {} both theme {}", category_path.display(), path.display(), route));
+        }
+    }
+    Ok(settings)
+}
@@ -0,0 +1,93 @@
+//! YouTube thumbnails for lite embeds. Thumbnails are downloaded once into a
+//! local cache so visitors never load images from YouTube.
+
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Shown in place of a thumbnail that is not cached and cannot be fetched,
+/// and for playlists, which have no thumbnail of their own.
+pub const PLACEHOLDER_SVG: &str = include_str!("../templates/video-placeholder.svg");
+pub const PLACEHOLDER_NAME: &str = "placeholder.svg";
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+/// Largest thumbnail accepted, in bytes.
+pub const MAX_THUMBNAIL_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Where thumbnails are cached and whether missing ones may be downloaded.
+#[derive(Clone, Debug)]
+pub struct ThumbnailCache {
+    pub dir: PathBuf,
+    /// When false, only already cached thumbnails are used.
+    pub fetch: bool,
+}
+
+impl Default for ThumbnailCache {
+    fn default() -> Self {
+        ThumbnailCache {
+            dir: PathBuf::from(".thumbnail-cache"),
+            fetch: true,
+        }
+    }
+}
+
+impl ThumbnailCache {
+    /// Returns the cached thumbnail for `video_id`, downloading it first if
+    /// it is missing and fetching is enabled.
+    pub fn get(&self, video_id: &str) -> Result<PathBuf, String> {
+        let path = self.dir.join(format!("{}.jpg", video_id));
+        if path.exists() {
+            return Ok(path);
+        }
+        if !self.fetch {
+            return Err(format!("thumbnail for {} is not cached", video_id));
+        }
+
+        // Failed downloads are not cached, so they are tried again next time
+        let bytes = fetch_thumbnail(video_id)?;
+        fs::create_dir_all(&self.dir).map_err(|e| format!("failed to create {}: {}", self.dir.display(), e))?;
+        // Written next to its place first, so a crash never leaves half an image
+        let staging = self.dir.join(format!(".{}.{}.tmp", video_id, uuid::Uuid::new_v4()));
+        let written = fs::write(&staging, bytes)
+            .map_err(|e| format!("failed to write {}: {}", staging.display(), e))
+            .and_then(|()| fs::rename(&staging, &path).map_err(|e| format!("failed to move {} into place: {}", path.display(), e)));
+        if written.is_err() {
+            let _ = fs::remove_file(&staging);
+        }
+        written.map(|()| path)
+    }
+}
+
+/// Reads a thumbnail download: a 2xx `image/*` response of at most
+/// 2 MB. Anything else is an error rather than something to cache.
+pub fn read_thumbnail(status: u16, content_type: &str, body: impl Read) -> Result<Vec<u8>, String> {
+    if !(200..300).contains(&status) {
+        return Err(format!("thumbnail request failed with status {}", status));
+    }
+    if !content_type.trim().to_ascii_lowercase().starts_with("image/") {
+        return Err(format!("thumbnail response is {:?}, not an image", content_type));
+    }
+
+    let mut bytes = Vec::new();
+    body.take(MAX_THUMBNAIL_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("failed to read thumbnail: {}", e))?;
+    if bytes.len() as u64 > MAX_THUMBNAIL_BYTES {
+        return Err(format!("thumbnail is larger than {} bytes", MAX_THUMBNAIL_BYTES));
+    }
+    Ok(bytes)
+}
+
+fn fetch_thumbnail(video_id: &str) -> Result<Vec<u8>, String> {
+    let url = format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", video_id);
+    let response = ureq::AgentBuilder::new()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .get(&url)
+        .call()
+        .map_err(|e| format!("failed to fetch thumbnail: {}", e))?;
+
+    let (status, content_type) = (response.status(), response.header("content-type").unwrap_or_default().to_string());
+    read_thumbnail(status, &content_type, response.into_reader()).map_err(|e| format!("{}: {}", url, e))
+}
@@ -0,0 +1,81 @@
+use website_test::bundles::{Bundler, extract_styles};
+
+const SHARED: &str = "
+@import url('fonts.css');
+/* layout */
+.header { color: red; }
+.unused, .logo:hover { color: blue; }
+.gone { color: green; }
+.header { color: red; }
+@media (max-width: 600px) {
+    .gone { display: none; }
+    .logo { display: block; }
+}
+@media print { .gone { display: none; } }
+@keyframes fade { from { opacity: 0; } }
+";
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Page</title>
+</head>
+<body>
+<style>
+    .header { color: red; }
+    .card:not(.hidden), [data-x=".nope"] { margin: 0; }
+    .missing { margin: 1px; }
+</style>
+<style id="dynamicBackground">
+    .main-content { background: none; }
+</style>
+<div class="card">Card</div>
+</body>
+</html>
+"#;
+
+#[test]
+fn shared_bundle_drops_duplicate_rules() {
+    let bundler = Bundler::new(SHARED);
+    assert_eq!(
+        bundler.shared().css,
+        "@import url('fonts.css');\n.unused,.logo:hover{color:blue}\n.gone{color:green}\n.header{color:red}\n@media (max-width:600px){.gone{display:none}.logo{display:block}}\n@media print{.gone{display:none}}\n@keyframes fade{from{opacity:0}}",
+        "rules no markup uses yet are kept for pages rendered later"
+    );
+    assert!(bundler.shared().name.starts_with("shared.") && bundler.shared().name.ends_with(".css"));
+    assert_eq!(Bundler::new(SHARED).shared().name, bundler.shared().name, "names follow the contents");
+    assert_ne!(Bundler::new(".header{}").shared().name, bundler.shared().name);
+}
+
+#[test]
+fn pages_link_bundles_of_their_own_styles() {
+    let bundler = Bundler::new(SHARED);
+    let html = bundler.apply(PAGE, Some("/page/"), "/site/css");
+
+    let bundles = bundler.bundles();
+    assert_eq!(bundles.len(), 2);
+    let page = &bundles[1];
+    assert!(page.name.starts_with("page."));
+    assert_eq!(page.css, r#".card:not(.hidden),[data-x=".nope"]{margin:0}"#, "rules the shared bundle ends with are left to it");
+
+    let links = format!(
+        "    <link rel=\"stylesheet\" href=\"/site/css/{}\">\n    <link rel=\"stylesheet\" href=\"/site/css/{}\">\n</head>",
+        bundler.shared().name,
+        page.name
+    );
+    assert!(html.contains(&links), "{}", html);
+    assert!(!html.contains("<style>"));
+    assert!(html.contains(r#"<style id="dynamicBackground">"#), "styles scripts change stay inline");
+
+    // A page without styles of its own only links the shared bundle
+    let plain = bundler.apply("<head>\n</head>\n<p>Text</p>", None, "/css");
+    assert_eq!(plain, format!("<head>\n    <link rel=\"stylesheet\" href=\"/css/{}\">\n</head>\n<p>Text</p>", bundler.shared().name));
+    assert_eq!(bundler.bundles().len(), 2);
+}
+
+#[test]
+fn only_plain_style_elements_are_extracted() {
+    let (page, css) = extract_styles("<style>a{}</style>\n<p>x</p><style media=\"print\">b{}</style><style>c{}</style>");
+    assert_eq!(page, "<p>x</p><style media=\"print\">b{}</style>");
+    assert_eq!(css, "a{}\nc{}\n");
+}
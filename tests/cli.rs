@@ -0,0 +1,101 @@
+use chrono::NaiveDate;
+use std::fs;
+use std::path::{Path, PathBuf};
+use website_test::cli::{self, Command, NewItem};
+use website_test::output::Verbosity;
+use website_test::static_site::{BuildOptions, DEFAULT_VERSION};
+use website_test::{posts, scaffold};
+
+fn parse(args: &[&str]) -> Result<cli::Cli, String> {
+    cli::parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+}
+
+#[test]
+fn build_is_the_default_command() {
+    let cli = parse(&[]).unwrap();
+    let Command::Build(options) = cli.command else {
+        panic!("expected build, got {:?}", cli.command);
+    };
+    assert_eq!(options.out_dir, PathBuf::from("docs"));
+    assert_eq!(options.base_path, "/Website-test");
+    assert!(!options.drafts && !options.minify);
+    assert_eq!(options.shop_url, None);
+    assert_eq!(cli.verbosity, Verbosity::Normal);
+    assert_eq!(BuildOptions::default().version, DEFAULT_VERSION, "the default does not ask git");
+}
+
+#[test]
+fn build_options_are_parsed() {
+    let cli = parse(&["build", "--out=site", "--base-url", "https://example.com/portfolio/", "--version", "abc", "--drafts", "--minify", "--shop-url", "https://shop.example.com", "-q"]).unwrap();
+    let Command::Build(options) = cli.command else {
+        panic!("expected build, got {:?}", cli.command);
+    };
+    assert_eq!(options.out_dir, PathBuf::from("site"));
+    assert_eq!(options.base_path, "/portfolio");
+    assert_eq!(options.site_url.as_deref(), Some("https://example.com/portfolio"));
+    assert_eq!(options.version, "abc");
+    assert!(options.drafts && options.minify);
+    assert_eq!(options.shop_url.as_deref(), Some("https://shop.example.com"));
+    assert_eq!((cli.verbosity, options.verbosity), (Verbosity::Quiet, Verbosity::Quiet));
+
+    assert_eq!(cli::parse_base_url("/").unwrap(), (String::new(), None));
+    assert_eq!(cli::parse_base_url("https://example.com").unwrap(), (String::new(), Some("https://example.com".to_string())));
+}
+
+#[test]
+fn other_commands_are_parsed() {
+    assert!(matches!(parse(&["clean", "--out", "site"]).unwrap().command, Command::Clean { out_dir } if out_dir == Path::new("site")));
+    assert!(matches!(parse(&["check", "-v"]).unwrap().command, Command::Check { content_dir } if content_dir == Path::new("templates")));
+    assert!(matches!(
+        parse(&["serve", "--port", "8080", "--base-url", "/"]).unwrap().command,
+        Command::Serve { port: 8080, base_path, .. } if base_path.is_empty()
+    ));
+    let Command::New { item, .. } = parse(&["new", "post", "Summer", "Tour"]).unwrap().command else {
+        panic!("expected new");
+    };
+    assert_eq!(item, NewItem::Post("Summer Tour".to_string()));
+    assert!(matches!(parse(&["--help"]).unwrap().command, Command::Help));
+}
+
+#[test]
+fn check_flag_is_the_check_command() {
+    assert!(matches!(
+        parse(&["--check", "--content", "tests/fixtures/site"]).unwrap().command,
+        Command::Check { content_dir } if content_dir == Path::new("tests/fixtures/site")
+    ));
+    assert_eq!(parse(&["build", "--check"]).unwrap_err(), "build does not take --check");
+}
+
+#[test]
+fn bad_arguments_are_rejected() {
+    for (args, error) in [
+        (&["deploy"][..], "unknown command \"deploy\""),
+        (&["clean", "--drafts"], "clean does not take --drafts"),
+        (&["build", "--out"], "--out needs a value"),
+        (&["build", "--drafts=yes"], "--drafts does not take a value"),
+        (&["build", "extra"], "unexpected argument \"extra\""),
+        (&["serve", "--port", "high"], "--port must be a port number, not \"high\""),
+        (&["build", "--base-url", "example.com"], "--base-url must be a path starting with / or an http(s) URL, not \"example.com\""),
+        (&["new", "page", "About"], "cannot create \"page\"; use post or category"),
+    ] {
+        assert_eq!(parse(args).unwrap_err(), error, "for {:?}", args);
+    }
+}
+
+#[test]
+fn new_content_is_scaffolded() {
+    let content = tempfile::tempdir().unwrap();
+    let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+
+    let path = scaffold::new_post(content.path(), "Tour: Part 2", date).unwrap();
+    assert_eq!(path, content.path().join("posts/tour-part-2.md"));
+    let post = posts::read_post(&path, "").unwrap();
+    assert_eq!((post.title.as_str(), post.date, post.draft), ("Tour: Part 2", date, true));
+    assert!(scaffold::new_post(content.path(), "Tour Part 2", date).unwrap_err().ends_with("already exists"));
+    assert!(scaffold::new_post(content.path(), "Tags", date).is_err());
+
+    let images = scaffold::new_category(content.path(), "Street Style").unwrap();
+    assert_eq!(images, content.path().join("modeling/Street Style/images"));
+    assert_eq!(fs::read_to_string(images.join("Links.txt")).unwrap(), "");
+    assert!(scaffold::new_category(content.path(), "../outside").is_err());
+}
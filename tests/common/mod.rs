@@ -0,0 +1,159 @@
+#![allow(dead_code)]
+
+use axum::Router;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use chrono::NaiveDate;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use tokio::task::JoinHandle;
+use tower::ServiceExt;
+use website_test::server::{self, AppState, ContactQueue, ServerConfig};
+use website_test::output::Verbosity;
+use website_test::static_site::{self, BuildOptions};
+use website_test::thumbnails::ThumbnailCache;
+
+/// Path of the fixture content directory.
+pub fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("site")
+}
+
+/// Copies the fixture content into `dest`, for tests that need to change it.
+pub fn copy_fixture(dest: &Path) {
+    fn copy_dir(from: &Path, to: &Path) {
+        std::fs::create_dir_all(to).unwrap();
+        for entry in std::fs::read_dir(from).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                copy_dir(&path, &to.join(entry.file_name()));
+            } else {
+                std::fs::copy(&path, to.join(entry.file_name())).unwrap();
+            }
+        }
+    }
+    copy_dir(&fixture_dir(), dest);
+}
+
+/// Options for a quiet, reproducible build of `content_dir`.
+pub fn build_options(content_dir: &Path, out_dir: &Path) -> BuildOptions {
+    BuildOptions {
+        content_dir: content_dir.to_path_buf(),
+        out_dir: out_dir.to_path_buf(),
+        version: "test".to_string(),
+        thumbnails: offline_thumbnails(&content_dir.join(".thumbnail-cache")),
+        build_time: NaiveDate::from_ymd_opt(2050, 1, 1).unwrap().and_hms_opt(12, 0, 0).unwrap(),
+        drafts: false,
+        base_path: static_site::DEFAULT_BASE_PATH.to_string(),
+        site_url: None,
+        minify: false,
+        shop_url: None,
+        verbosity: Verbosity::Quiet,
+    }
+}
+
+/// An offline thumbnail cache in `dir`.
+pub fn offline_thumbnails(dir: &Path) -> ThumbnailCache {
+    ThumbnailCache { dir: dir.to_path_buf(), fetch: false }
+}
+
+/// A router over the fixture content, writing messages into a temp dir.
+pub struct TestApp {
+    pub router: Router,
+    pub state: AppState,
+    pub contact_writer: JoinHandle<()>,
+    pub scratch: TempDir,
+}
+
+impl TestApp {
+    /// Builds the app without loading the site model.
+    pub fn unloaded() -> Self {
+        Self::unloaded_with(fixture_dir())
+    }
+
+    /// Builds the app over `content_dir` without loading the site model.
+    pub fn unloaded_with(content_dir: PathBuf) -> Self {
+        Self::unloaded_configured(content_dir, |_| {})
+    }
+
+    /// Builds the app over `content_dir` without loading the site model,
+    /// letting `configure` adjust the config first.
+    pub fn unloaded_configured(content_dir: PathBuf, configure: impl FnOnce(&mut ServerConfig)) -> Self {
+        let scratch = tempfile::tempdir().expect("create temp dir");
+        let mut config = ServerConfig {
+            content_dir,
+            docs_dir: scratch.path().join("docs"),
+            messages_file: scratch.path().join("messages.txt"),
+            pending_reviews_file: scratch.path().join("pending-reviews.yaml"),
+            orders_file: scratch.path().join("orders.yaml"),
+            admin_password: None,
+            thumbnails: offline_thumbnails(&scratch.path().join("thumbnails")),
+            drafts: true,
+        };
+        configure(&mut config);
+        let (contact, contact_writer) = ContactQueue::spawn(config.messages_file.clone());
+        let state = AppState::new(config, contact);
+        let router = server::router(state.clone());
+        TestApp { router, state, contact_writer, scratch }
+    }
+
+    /// Builds the app with the site model loaded, as after startup.
+    pub fn new() -> Self {
+        Self::with_content(fixture_dir())
+    }
+
+    /// Builds the app over `content_dir` with the site model loaded.
+    pub fn with_content(content_dir: PathBuf) -> Self {
+        Self::configured(content_dir, |_| {})
+    }
+
+    /// Builds the app over `content_dir` with the site model loaded, letting
+    /// `configure` adjust the config first.
+    pub fn configured(content_dir: PathBuf, configure: impl FnOnce(&mut ServerConfig)) -> Self {
+        let app = Self::unloaded_configured(content_dir, configure);
+        let model = server::load_site_model(&app.state.config).expect("load fixture site");
+        app.state.templates.set(model).expect("site model set once");
+        app
+    }
+
+    pub async fn request(&self, request: Request<Body>) -> (StatusCode, axum::http::HeaderMap, String) {
+        let response = self.router.clone().oneshot(request).await.expect("router is infallible");
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("read body");
+        (status, headers, String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    pub async fn get(&self, uri: &str) -> (StatusCode, axum::http::HeaderMap, String) {
+        self.request(Request::get(uri).body(Body::empty()).unwrap()).await
+    }
+
+    pub async fn post_form(&self, uri: &str, form: &str) -> (StatusCode, axum::http::HeaderMap, String) {
+        let request = Request::post(uri)
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(Body::from(form.to_string()))
+            .unwrap();
+        self.request(request).await
+    }
+
+    /// Drops the router so the contact writer drains, then returns the
+    /// messages file contents.
+    pub async fn finish(self) -> String {
+        let TestApp { router, state, contact_writer, scratch } = self;
+        let messages_file = state.config.messages_file.clone();
+        drop(router);
+        drop(state);
+        contact_writer.await.expect("contact writer finished");
+        let messages = std::fs::read_to_string(&messages_file).unwrap_or_default();
+        drop(scratch);
+        messages
+    }
+}
+
+/// Extracts the contents of `<main class="main-content">`.
+pub fn main_content(html: &str) -> &str {
+    let start = html.find(r#"<main class="main-content">"#).expect("page has main content");
+    let end = html.find("</main>").expect("main is closed");
+    html[start..end].trim()
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::{copy_fixture, fixture_dir};
+use website_test::credits;
+
+#[test]
+fn credits_are_grouped_by_type_newest_first() {
+    let resume = credits::read_resume(&fixture_dir()).unwrap().unwrap();
+    assert_eq!(resume.name.as_deref(), Some("Fixture Performer"));
+    assert_eq!(resume.stats.entries(), [("Height", "5'6\""), ("Hair", "Brown"), ("Eyes", "Green")]);
+
+    let groups: Vec<(&str, Vec<&str>)> = resume
+        .credits_by_type()
+        .into_iter()
+        .map(|(kind, credits)| (kind, credits.iter().map(|credit| credit.project.as_str()).collect()))
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("Film", vec!["Horse Camp 3", "Horse Camp"]),
+            ("Television", vec!["Finesse"]),
+            ("Theatre", vec!["Our Town"]),
+        ]
+    );
+}
+
+#[test]
+fn director_and_company_share_a_column() {
+    let resume = credits::read_resume(&fixture_dir()).unwrap().unwrap();
+    let columns: Vec<String> = resume.credits.iter().map(|credit| credit.director_company()).collect();
+    assert_eq!(columns, ["Dir. Pat Doe", "Fixture Studios", "Dir. Pat Doe / Barn & Sons", "Community Playhouse"]);
+}
+
+#[test]
+fn missing_credits_file_means_no_resume() {
+    let content = tempfile::tempdir().unwrap();
+    assert!(credits::read_resume(content.path()).unwrap().is_none());
+}
+
+#[test]
+fn invalid_credits_are_reported() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    std::fs::write(content.path().join("acting/credits.yaml"), "credits:\n  - project: A\n    type: Film\n").unwrap();
+    let error = credits::read_resume(content.path()).unwrap_err();
+    assert!(error.contains("missing field `role`"), "{}", error);
+}
+
+#[test]
+fn resume_pdf_spans_pages_when_needed() {
+    let mut resume = credits::read_resume(&fixture_dir()).unwrap().unwrap();
+    let short = credits::resume_pdf(&resume).unwrap();
+    assert!(short.starts_with(b"%PDF"));
+    assert_eq!(count_pages(&short), 1);
+
+    let credit = resume.credits[0].clone();
+    resume.credits.extend(std::iter::repeat_n(credit, 80));
+    assert!(count_pages(&credits::resume_pdf(&resume).unwrap()) > 1);
+}
+
+fn count_pages(pdf: &[u8]) -> usize {
+    String::from_utf8_lossy(pdf).matches("/Type/Page/").count()
+}
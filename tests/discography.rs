@@ -0,0 +1,57 @@
+mod common;
+
+use common::{copy_fixture, fixture_dir};
+use website_test::discography::{self, ReleaseKind, display_date, format_duration, parse_duration, slugify};
+
+#[test]
+fn releases_are_read_newest_first_with_derived_slugs() {
+    let releases = discography::read_releases(&fixture_dir()).unwrap();
+    let slugs: Vec<&str> = releases.iter().map(|release| release.slug.as_str()).collect();
+    assert_eq!(slugs, ["night-day", "first-steps"]);
+
+    let album = &releases[0];
+    assert_eq!(album.kind, ReleaseKind::Album);
+    assert_eq!(album.total_seconds(), Some(585));
+    assert_eq!(album.links[0].platform(), "spotify");
+    assert_eq!(album.links[1].platform(), "amazon");
+    assert_eq!(releases[1].kind, ReleaseKind::Single);
+    assert_eq!(releases[1].released.as_deref(), Some("2021"));
+}
+
+#[test]
+fn missing_albums_file_means_no_releases() {
+    let content = tempfile::tempdir().unwrap();
+    assert!(discography::read_releases(content.path()).unwrap().is_empty());
+}
+
+#[test]
+fn invalid_albums_are_reported() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let albums = content.path().join("music/albums.yaml");
+
+    for (yaml, expected) in [
+        ("releases:\n  - title: A\n  - title: a\n", "duplicate release slug \"a\""),
+        ("releases:\n  - title: A\n    released: March 2024\n", "invalid release date"),
+        ("releases:\n  - title: A\n    tracks:\n      - title: T\n        duration: \"3:75\"\n", "invalid duration"),
+        ("releases:\n  - title: A\n    colour: red\n", "unknown field `colour`"),
+    ] {
+        std::fs::write(&albums, yaml).unwrap();
+        let error = discography::read_releases(content.path()).unwrap_err();
+        assert!(error.contains(expected), "{:?} should mention {:?}", error, expected);
+    }
+}
+
+#[test]
+fn formats_titles_durations_and_dates() {
+    assert_eq!(slugify("Life Hits 4 The Heart"), "life-hits-4-the-heart");
+    assert_eq!(slugify("  Rock & Roll!! "), "rock-roll");
+    assert_eq!(parse_duration("3:05"), Some(185));
+    assert_eq!(parse_duration("1:02:03"), Some(3723));
+    assert_eq!(parse_duration("3:60"), None);
+    assert_eq!(format_duration(185), "3:05");
+    assert_eq!(format_duration(3723), "1:02:03");
+    assert_eq!(display_date("2024-03-01"), "March 1, 2024");
+    assert_eq!(display_date("2024-11"), "November 2024");
+    assert_eq!(display_date("2024"), "2024");
+}
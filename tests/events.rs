@@ -0,0 +1,92 @@
+mod common;
+
+use chrono::{NaiveDate, NaiveDateTime, TimeZone, Utc};
+use common::{copy_fixture, fixture_dir};
+use website_test::events::{self, EventTime};
+
+fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap().and_hms_opt(hour, minute, 0).unwrap()
+}
+
+#[test]
+fn event_times_accept_dates_and_local_times() {
+    let date = NaiveDate::from_ymd_opt(2026, 5, 2).unwrap();
+    assert_eq!(EventTime::parse("2026-05-02"), Some(EventTime::Date(date)));
+    assert_eq!(EventTime::parse("2026-05-02 19:30"), Some(EventTime::DateTime(at(2026, 5, 2, 19, 30))));
+    assert_eq!(EventTime::parse("2026-05-02T19:30"), Some(EventTime::DateTime(at(2026, 5, 2, 19, 30))));
+    assert_eq!(EventTime::parse("May 2"), None);
+    assert_eq!(EventTime::parse("2026-02-30"), None);
+}
+
+#[test]
+fn events_move_to_the_archive_once_over() {
+    let events = events::read_events(&fixture_dir()).unwrap();
+    let titles = |list: Vec<&events::Event>| list.iter().map(|event| event.title.clone()).collect::<Vec<_>>();
+
+    let (upcoming, past) = events::split_events(&events, at(2050, 1, 1, 0, 0));
+    assert_eq!(titles(upcoming), ["Release Show", "Comic Con, Day One"]);
+    assert_eq!(titles(past), ["Summer Shoot", "First Gig"]);
+
+    // Timed events end at their end time, all-day events after their last day
+    let (upcoming, _) = events::split_events(&events, at(2099, 5, 2, 21, 59));
+    assert_eq!(upcoming.len(), 2);
+    let (upcoming, _) = events::split_events(&events, at(2099, 5, 2, 22, 0));
+    assert_eq!(titles(upcoming), ["Comic Con, Day One"]);
+    let (upcoming, _) = events::split_events(&events, at(2099, 7, 12, 23, 59));
+    assert_eq!(upcoming.len(), 1);
+    let (upcoming, _) = events::split_events(&events, at(2099, 7, 13, 0, 0));
+    assert!(upcoming.is_empty());
+}
+
+#[test]
+fn event_dates_are_displayed_as_ranges() {
+    let events = events::read_events(&fixture_dir()).unwrap();
+    let when: Vec<String> = events.iter().map(|event| event.display_when()).collect();
+    assert_eq!(
+        when,
+        [
+            "Sat, January 8, 2000 · 8:00 PM",
+            "Fri, June 15, 2001",
+            "Sat, May 2, 2099 · 7:30 PM – 10:00 PM",
+            "Fri, July 10, 2099 – Sun, July 12, 2099",
+        ]
+    );
+}
+
+#[test]
+fn ics_lines_are_folded_and_stamped() {
+    let mut events = events::read_events(&fixture_dir()).unwrap();
+    events[0].description = Some("word ".repeat(30));
+    let stamp = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+    let ics = events::generate_ics(&events, stamp);
+
+    assert!(ics.contains("DTSTAMP:20261018T120000Z\r\n"));
+    for line in ics.split("\r\n") {
+        assert!(line.len() <= 75, "{:?} is too long", line);
+    }
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains(&format!("DESCRIPTION:{}\r\n", "word ".repeat(30))));
+}
+
+#[test]
+fn missing_events_file_means_no_events() {
+    let content = tempfile::tempdir().unwrap();
+    assert!(events::read_events(content.path()).unwrap().is_empty());
+}
+
+#[test]
+fn invalid_events_are_reported() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let path = content.path().join("events/events.yaml");
+
+    for (yaml, expected) in [
+        ("events:\n  - title: A\n    start: next friday\n", "invalid date \"next friday\""),
+        ("events:\n  - title: A\n    start: 2026-05-02\n    end: 2026-05-01\n", "\"A\" ends before it starts"),
+        ("events:\n  - title: A\n    start: 2026-05-02\n    price: 10\n", "unknown field `price`"),
+    ] {
+        std::fs::write(&path, yaml).unwrap();
+        let error = events::read_events(content.path()).unwrap_err();
+        assert!(error.contains(expected), "{:?} should mention {:?}", error, expected);
+    }
+}
@@ -0,0 +1,132 @@
+mod common;
+
+use chrono::{DateTime, TimeZone, Utc};
+use common::{copy_fixture, fixture_dir};
+use std::fs::File;
+use std::path::Path;
+use website_test::feeds::{self, SiteUrls};
+
+fn utc(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+}
+
+fn touch(path: &Path, time: DateTime<Utc>) {
+    File::options().write(true).open(path).unwrap().set_modified(time.into()).unwrap();
+}
+
+/// A copy of the fixture with pinned file modification times.
+fn dated_fixture() -> tempfile::TempDir {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    touch(&content.path().join("modeling/Glamour/images/a.png"), utc(2024, 4, 1));
+    touch(&content.path().join("modeling/Glamour/images/b shot.jpg"), utc(2023, 1, 1));
+    touch(&content.path().join("Behind the scenes/images/set day.jpg"), utc(2024, 2, 1));
+    touch(&content.path().join("music/youtubeLinks.txt"), utc(2022, 1, 1));
+    touch(&content.path().join("acting/media.yaml"), utc(2022, 6, 1));
+    touch(&content.path().join("reviews/reviews.yaml"), utc(2021, 1, 1));
+    content
+}
+
+#[test]
+fn entries_cover_posts_photos_videos_and_reviews() {
+    let content = dated_fixture();
+    let urls = SiteUrls::for_static_site("https://fixture.example/site/");
+    let entries = feeds::collect_entries(content.path(), &urls).unwrap();
+    let ids: Vec<_> = entries.iter().map(|entry| entry.id.as_str()).collect();
+
+    assert_eq!(
+        ids[..6],
+        [
+            "https://fixture.example/site/news/summer-tour/",
+            "https://www.youtube.com/watch?v=actingCCCC3&t=65s",
+            "https://fixture.example/site/modeling/Glamour/images/a.png",
+            "https://fixture.example/site/news/album-launch/",
+            "https://fixture.example/site/behind-the-scenes/images/set%20day.jpg",
+            "https://fixture.example/site/modeling/Glamour/images/b%20shot.jpg",
+        ]
+    );
+    // Drafts are never announced
+    assert!(!ids.iter().any(|id| id.contains("secret-project")));
+    // Only YouTube items become video entries
+    assert!(ids.contains(&"https://www.youtube.com/playlist?list=PLfixturePlaylist"));
+    assert!(!ids.iter().any(|id| id.contains("vimeo") || id.contains("clip.mp4")));
+    // Reviews are dated by their `date`, or else by reviews.yaml
+    let reviews: Vec<_> = entries.iter().rev().take(2).collect();
+    assert_eq!(reviews[0].title, "Review from Pat");
+    assert_eq!(reviews[0].updated, utc(2020, 6, 1));
+    assert_eq!(reviews[0].id, "https://fixture.example/site/reviews/#pat-photographer");
+    assert_eq!(reviews[0].image.as_deref(), Some("https://fixture.example/site/global-images/1.png"));
+    assert_eq!(reviews[1].title, "Review from Tom & Jerry");
+    assert_eq!(reviews[1].updated, utc(2021, 1, 1));
+
+    let post = &entries[3];
+    assert_eq!(post.title, "The Album Is <Out>");
+    assert_eq!(post.updated, utc(2024, 3, 1));
+    assert_eq!(post.image.as_deref(), Some("https://fixture.example/site/global-images/1.png"));
+    assert!(post.content_html.contains(r#"src="https://fixture.example/site/posts/stage.png""#));
+
+    let video = &entries[1];
+    assert_eq!(video.title, "Showreel 2024");
+    assert_eq!(video.url, "https://fixture.example/site/acting/");
+    assert_eq!(video.image.as_deref(), Some("https://i.ytimg.com/vi/actingCCCC3/hqdefault.jpg"));
+    assert_eq!(video.summary.as_deref(), Some("Drama & comedy highlights"));
+}
+
+#[test]
+fn server_urls_point_at_the_content_directory() {
+    let content = dated_fixture();
+    let urls = SiteUrls::for_server("http://localhost:3000");
+    let entries = feeds::collect_entries(content.path(), &urls).unwrap();
+    let ids: Vec<_> = entries.iter().map(|entry| entry.id.as_str()).collect();
+    assert!(ids.contains(&"http://localhost:3000/templates/modeling/Glamour/images/a.png"));
+    assert!(ids.contains(&"http://localhost:3000/templates/Behind%20the%20scenes/images/set%20day.jpg"));
+    assert!(ids.contains(&"http://localhost:3000/news/album-launch/"));
+}
+
+#[test]
+fn entries_are_capped() {
+    let content = tempfile::tempdir().unwrap();
+    let images = content.path().join("Behind the scenes").join("images");
+    std::fs::create_dir_all(&images).unwrap();
+    for n in 0..feeds::MAX_ENTRIES + 5 {
+        std::fs::copy(fixture_dir().join("global-images/1.png"), images.join(format!("{}.png", n))).unwrap();
+    }
+
+    let entries = feeds::collect_entries(content.path(), &SiteUrls::for_static_site("https://fixture.example")).unwrap();
+    assert_eq!(entries.len(), feeds::MAX_ENTRIES);
+}
+
+#[test]
+fn atom_feed_escapes_entries() {
+    let content = dated_fixture();
+    let urls = SiteUrls::for_static_site("https://fixture.example/site");
+    let entries = feeds::collect_entries(content.path(), &urls).unwrap();
+    let atom = feeds::atom(&entries, &urls);
+
+    assert!(atom.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n"));
+    assert!(atom.contains(r#"<link rel="self" type="application/atom+xml" href="https://fixture.example/site/feed.xml"/>"#));
+    assert!(atom.contains("<updated>2024-06-10T00:00:00+00:00</updated>"));
+    assert!(atom.contains("<title>The Album Is &lt;Out&gt;</title>"));
+    assert!(atom.contains("<title>Behind the &lt;scenes&gt;</title>"));
+    assert!(atom.contains("<summary>&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp; more</summary>"));
+    assert!(!atom.contains("<script>"));
+    assert_eq!(atom.matches("<entry>").count(), entries.len());
+    assert!(atom.ends_with("</feed>\n"));
+}
+
+#[test]
+fn json_feed_lists_entries() {
+    let content = dated_fixture();
+    let urls = SiteUrls::for_static_site("https://fixture.example/site");
+    let entries = feeds::collect_entries(content.path(), &urls).unwrap();
+    let feed: serde_json::Value = serde_json::from_str(&feeds::json_feed(&entries, &urls)).unwrap();
+
+    assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+    assert_eq!(feed["feed_url"], "https://fixture.example/site/feed.json");
+    let items = feed["items"].as_array().unwrap();
+    assert_eq!(items.len(), entries.len());
+    assert_eq!(items[0]["id"], "https://fixture.example/site/news/summer-tour/");
+    assert_eq!(items[0]["date_published"], "2024-06-10T00:00:00+00:00");
+    assert_eq!(items[2]["image"], "https://fixture.example/site/modeling/Glamour/images/a.png");
+    assert!(items[2].get("summary").is_none());
+}
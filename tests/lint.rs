@@ -0,0 +1,62 @@
+mod common;
+
+use common::{copy_fixture, fixture_dir};
+use std::fs;
+use std::path::Path;
+use website_test::lint::{self, Diagnostic, Severity};
+
+/// Diagnostics as `relative/path:line: severity: message` lines.
+fn report(content_dir: &Path) -> Vec<String> {
+    lint::check_content(content_dir)
+        .iter()
+        .map(|diagnostic| {
+            let relative = Diagnostic { path: diagnostic.path.strip_prefix(content_dir).unwrap().to_path_buf(), ..diagnostic.clone() };
+            relative.to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn fixture_problems_are_reported_with_lines() {
+    assert_eq!(report(&fixture_dir()), [
+        "music/youtubeLinks.txt:4: error: unsupported media URL \"not a youtube link\"",
+        "acting/youtubeLinks.txt: warning: ignored because media.yaml exists",
+        "modeling/Drafts: warning: category has no images/ folder and is not shown",
+        "modeling/Glamour/images/Links.txt:1: warning: \"a.png\" should be given without its extension",
+    ]);
+}
+
+#[test]
+fn broken_content_is_reported() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let dir = content.path();
+    fs::write(dir.join("reviews/reviews.yaml"), "testimonials:\n  - quote: a\n    author: A\n    title: T\n    stars: 5\n").unwrap();
+    fs::write(dir.join("music/youtubeLinks.txt"), "https://youtu.be/SXPYP0uh5p4\n\nhttps://www.youtube.com/watch?v=SXPYP0uh5p4&t=30\n").unwrap();
+    fs::write(dir.join("modeling/Glamour/images/Links.txt"), "a, https://example.com/a\nmissing, https://example.com/m\nno comma here\na, https://example.com/again\n").unwrap();
+    fs::write(dir.join("contact/contact.html"), "<p>Hi</p>\n<img src=\"/templates/global-images/1.png\">\n").unwrap();
+    fs::write(dir.join("acting/media.yaml"), "media:\n  - url: https://youtu.be/SXPYP0uh5p4\n  - url: https://example.com/video\n  - file: missing.mp3\n").unwrap();
+    fs::write(dir.join("theme.yaml"), "theme: missing\nthemes:\n  default: {}\n").unwrap();
+    fs::write(dir.join("shop/products.yaml"), "products:\n  - name: Poster\n    price: 10\n    image: shop/poster.jpg\n").unwrap();
+    fs::write(dir.join("global-images/huge.jpg"), vec![0u8; lint::MAX_IMAGE_SIZE as usize + 1]).unwrap();
+
+    let report = report(dir);
+    for expected in [
+        "reviews/reviews.yaml:5: error: testimonials[0]: unknown field `stars`",
+        "music/youtubeLinks.txt:3: warning: duplicate YouTube video SXPYP0uh5p4, first listed on line 1",
+        "modeling/Glamour/images/Links.txt:2: warning: \"missing\" matches no image in this category",
+        "modeling/Glamour/images/Links.txt:3: error: expected `image name, URL`",
+        "modeling/Glamour/images/Links.txt:4: warning: \"a\" is already linked on line 1",
+        "contact/contact.html:2: warning: <img> without alt text",
+        "global-images/huge.jpg: warning: image is 2.0 MB; keep images under 2 MB",
+        "theme.yaml: error: unknown theme \"missing\"",
+        "acting/media.yaml:3: error: unsupported media URL \"https://example.com/video\"",
+        "acting/media.yaml:4: error: \"missing.mp3\" does not exist in acting/",
+        "shop/products.yaml:4: error: \"shop/poster.jpg\" does not exist in the content directory",
+    ] {
+        assert!(report.iter().any(|line| line.starts_with(expected)), "missing {:?} in {:#?}", expected, report);
+    }
+
+    let diagnostics = lint::check_content(dir);
+    assert!(diagnostics.iter().any(|diagnostic| diagnostic.severity == Severity::Error));
+}
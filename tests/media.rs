@@ -0,0 +1,169 @@
+mod common;
+
+use common::{copy_fixture, fixture_dir};
+use website_test::media::{self, MediaSource, YoutubeRef, YoutubeTarget, parse_media_url, parse_timestamp, parse_youtube_url};
+
+fn video(id: &str, start: Option<u32>) -> Option<YoutubeRef> {
+    Some(YoutubeRef { target: YoutubeTarget::Video(id.to_string()), start })
+}
+
+#[test]
+fn parses_supported_url_shapes() {
+    assert_eq!(parse_youtube_url("https://youtu.be/wrb6hyxT6Ck"), video("wrb6hyxT6Ck", None));
+    assert_eq!(parse_youtube_url("https://youtu.be/wrb6hyxT6Ck?si=abc&t=42"), video("wrb6hyxT6Ck", Some(42)));
+    assert_eq!(
+        parse_youtube_url("https://www.youtube.com/watch?v=RlZyh_b7p2Q&list=OLAK5uy&index=1"),
+        video("RlZyh_b7p2Q", None)
+    );
+    assert_eq!(parse_youtube_url("https://m.youtube.com/watch?v=RlZyh_b7p2Q&t=1m5s"), video("RlZyh_b7p2Q", Some(65)));
+    assert_eq!(parse_youtube_url("https://music.youtube.com/watch?v=RlZyh_b7p2Q"), video("RlZyh_b7p2Q", None));
+    assert_eq!(parse_youtube_url("https://youtube.com/shorts/abcdefghijk?feature=share"), video("abcdefghijk", None));
+    assert_eq!(parse_youtube_url("https://www.youtube.com/embed/-QzF_V_jXUA?start=90"), video("-QzF_V_jXUA", Some(90)));
+    assert_eq!(parse_youtube_url("https://www.youtube-nocookie.com/embed/-QzF_V_jXUA"), video("-QzF_V_jXUA", None));
+    assert_eq!(parse_youtube_url("https://www.youtube.com/live/abcdefghijk?si=x"), video("abcdefghijk", None));
+    assert_eq!(parse_youtube_url("youtube.com/watch?v=RlZyh_b7p2Q#t=1h0m2s"), video("RlZyh_b7p2Q", Some(3602)));
+    assert_eq!(
+        parse_youtube_url("https://www.youtube.com/playlist?list=OLAK5uy_nYxkyUAwr2K"),
+        Some(YoutubeRef { target: YoutubeTarget::Playlist("OLAK5uy_nYxkyUAwr2K".to_string()), start: None })
+    );
+}
+
+#[test]
+fn recognizes_other_providers() {
+    assert_eq!(parse_media_url("https://vimeo.com/76979871#t=1m"), Some((MediaSource::Vimeo("76979871".to_string()), Some(60))));
+    assert_eq!(parse_media_url("https://player.vimeo.com/video/76979871"), Some((MediaSource::Vimeo("76979871".to_string()), None)));
+    assert_eq!(
+        parse_media_url("https://m.soundcloud.com/artist/song?in=artist/sets/demo"),
+        Some((MediaSource::SoundCloud("https://soundcloud.com/artist/song".to_string()), None))
+    );
+    assert_eq!(
+        parse_media_url("https://open.spotify.com/intl-de/track/4cOdK2wGLETKBW3PvgPWqT?si=x"),
+        Some((MediaSource::Spotify { kind: "track".to_string(), id: "4cOdK2wGLETKBW3PvgPWqT".to_string() }, None))
+    );
+    assert_eq!(
+        parse_media_url("https://artist.bandcamp.com/album/debut"),
+        Some((MediaSource::Bandcamp("https://artist.bandcamp.com/album/debut".to_string()), None))
+    );
+    assert_eq!(
+        parse_media_url("https://www.youtube.com/shorts/abcdefghijk"),
+        Some((MediaSource::Youtube(YoutubeTarget::Video("abcdefghijk".to_string())), None))
+    );
+    assert_eq!(parse_media_url("https://vimeo.com/channels/staffpicks"), None);
+    assert_eq!(parse_media_url("https://open.spotify.com/user/someone"), None);
+    assert_eq!(parse_media_url("https://example.com/song.mp3"), None);
+}
+
+#[test]
+fn rejects_other_urls() {
+    assert_eq!(parse_youtube_url("https://vimeo.com/12345"), None);
+    assert_eq!(parse_youtube_url("https://youtu.be/pF-V0JSJZgQhttps://youtu.be/SXPYP0uh5p4"), None);
+    assert_eq!(parse_youtube_url("https://www.youtube.com/channel/UC6I94NF5Oo8igrtPWvD7Qkw"), None);
+    assert_eq!(parse_youtube_url("not a url"), None);
+}
+
+#[test]
+fn parses_timestamps() {
+    assert_eq!(parse_timestamp("75"), Some(75));
+    assert_eq!(parse_timestamp("75s"), Some(75));
+    assert_eq!(parse_timestamp("2m"), Some(120));
+    assert_eq!(parse_timestamp("1h1m1s"), Some(3661));
+    assert_eq!(parse_timestamp("1x"), None);
+    assert_eq!(parse_timestamp("12m3"), None);
+    assert_eq!(parse_timestamp("9999999h"), None);
+    assert_eq!(parse_timestamp("71582789m"), None);
+    assert_eq!(parse_timestamp("1193046h4294967295s"), None);
+    assert_eq!(parse_youtube_url("https://youtu.be/wrb6hyxT6Ck?t=9999999h"), video("wrb6hyxT6Ck", None));
+}
+
+#[test]
+fn media_yaml_is_grouped_by_section_order_with_featured_first() {
+    let groups = media::read_media(&fixture_dir(), "acting", "/templates/acting").unwrap();
+    let layout: Vec<(Option<&str>, Vec<MediaSource>)> = groups
+        .iter()
+        .map(|group| (group.section.as_deref(), group.items.iter().map(|item| item.source.clone()).collect()))
+        .collect();
+    let youtube = |id: &str| MediaSource::Youtube(YoutubeTarget::Video(id.to_string()));
+
+    assert_eq!(
+        layout,
+        vec![
+            (None, vec![youtube("mobileEEE55")]),
+            (
+                Some("Reels"),
+                vec![
+                    youtube("actingCCCC3"),
+                    MediaSource::Youtube(YoutubeTarget::Playlist("PLfixturePlaylist".to_string())),
+                    MediaSource::Video("/templates/acting/clip.mp4".to_string()),
+                ]
+            ),
+            (Some("Short Films"), vec![youtube("shortDDDD44")]),
+        ]
+    );
+    assert_eq!(groups[0].items[0].start, Some(90));
+    assert_eq!(groups[1].items[0].title.as_deref(), Some("Showreel 2024"));
+    assert_eq!(groups[1].items[0].start, Some(65));
+    assert!(groups[1].items[0].featured);
+    assert_eq!(groups[1].items[2].start, Some(5));
+}
+
+#[test]
+fn youtube_links_txt_is_the_fallback_and_local_files_are_appended() {
+    let groups = media::read_media(&fixture_dir(), "music", ".").unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].section, None);
+    let urls: Vec<Option<String>> = groups[0].items.iter().map(|item| item.embed_url()).collect();
+    assert_eq!(
+        urls,
+        vec![
+            Some("https://www.youtube.com/embed/musicAAAAA1".to_string()),
+            Some("https://www.youtube.com/embed/musicBBBBB2?start=30".to_string()),
+            Some("https://player.vimeo.com/video/76979871?dnt=1".to_string()),
+            Some("https://open.spotify.com/embed/album/4aawyAB9vmqN3uQ7FjRGTy".to_string()),
+            Some("https://w.soundcloud.com/player/?url=https%3A%2F%2Fsoundcloud.com%2Ffixture-artist%2Ffirst-song".to_string()),
+            None,
+            None,
+        ]
+    );
+
+    let demo = groups[0].items.last().unwrap();
+    assert_eq!(demo.source, MediaSource::Audio("./demo_take-1.mp3".to_string()));
+    assert_eq!(demo.title.as_deref(), Some("demo take 1"));
+}
+
+#[test]
+fn videos_yaml_is_read_without_media_yaml() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let yaml = std::fs::read_to_string(content.path().join("acting/media.yaml")).unwrap();
+    std::fs::remove_file(content.path().join("acting/media.yaml")).unwrap();
+    std::fs::write(content.path().join("acting/videos.yaml"), yaml.replacen("media:", "videos:", 1)).unwrap();
+
+    let groups = media::read_media(content.path(), "acting", ".").unwrap();
+    assert_eq!(groups.iter().map(|group| group.items.len()).sum::<usize>(), 5);
+    let diagnostics: Vec<String> = media::check_media(content.path(), "acting").iter().map(|d| d.to_string()).collect();
+    assert!(diagnostics.iter().any(|d| d.contains("videos.yaml: warning: rename to media.yaml")), "{:?}", diagnostics);
+}
+
+#[test]
+fn broken_media_yaml_is_an_error() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    std::fs::write(content.path().join("acting/media.yaml"), "media: [").unwrap();
+    let error = media::read_media(content.path(), "acting", ".").unwrap_err();
+    assert!(error.contains("media.yaml"), "{}", error);
+}
+
+#[test]
+fn media_items_no_player_can_show_are_errors() {
+    for (item, error) in [
+        ("url: https://example.com/video", "unsupported media URL \"https://example.com/video\""),
+        ("file: missing.mp3", "\"missing.mp3\" does not exist in acting/"),
+        ("file: notes.txt", "\"notes.txt\" is not an audio or video file"),
+    ] {
+        let content = tempfile::tempdir().unwrap();
+        copy_fixture(content.path());
+        std::fs::write(content.path().join("acting/media.yaml"), format!("media:\n  - url: https://youtu.be/SXPYP0uh5p4\n  - {}\n", item)).unwrap();
+        let e = media::read_media(content.path(), "acting", ".").unwrap_err();
+        assert!(e.contains("media.yaml") && e.contains(error), "{}: {}", item, e);
+    }
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use common::{build_options, fixture_dir};
+use std::fs;
+use std::path::Path;
+use website_test::minify;
+use website_test::static_site;
+
+/// Tokens of a stylesheet or script: words, quoted strings and single
+/// punctuation characters, without whitespace or comments.
+fn tokens(source: &str, css: bool) -> Vec<String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i = (i + 2..chars.len()).find(|&j| chars[j - 1] == '*' && chars[j] == '/' && j > i + 2).map_or(chars.len(), |j| j + 1);
+        } else if !css && c == '/' && chars.get(i + 1) == Some(&'/') {
+            i = (i..chars.len()).find(|&j| chars[j] == '\n').unwrap_or(chars.len());
+        } else if matches!(c, '"' | '\'' | '`') {
+            let start = i;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(chars.len());
+            tokens.push(chars[start..i].iter().collect());
+        } else if c.is_alphanumeric() || matches!(c, '_' | '$' | '-' | '.' | '#' | '%') {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '$' | '-' | '.' | '#' | '%')) {
+                i += 1;
+            }
+            tokens.push(chars[start..i].iter().collect());
+        } else {
+            // The last declaration of a CSS block needs no semicolon
+            if css && c == '}' && tokens.last().is_some_and(|last| last == ";") {
+                tokens.pop();
+            }
+            tokens.push(c.to_string());
+            i += 1;
+        }
+    }
+    tokens
+}
+
+/// A page as a browser would build it, one node per line: tags as written,
+/// text with whitespace collapsed and the tokens of styles and scripts.
+fn dom(html: &str) -> Vec<String> {
+    let mut nodes = Vec::new();
+    let mut rest = html;
+    let text = |nodes: &mut Vec<String>, text: &str| {
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        match (collapsed.is_empty(), text.is_empty()) {
+            (false, _) => nodes.push(format!("text: {}", collapsed)),
+            (true, false) => nodes.push("text: ".to_string()),
+            (true, true) => {},
+        }
+    };
+    while let Some(start) = rest.find('<') {
+        text(&mut nodes, &rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with("<!--") {
+            rest = &rest[rest.find("-->").unwrap() + 3..];
+            continue;
+        }
+        let end = rest.find('>').unwrap() + 1;
+        let tag = &rest[..end];
+        nodes.push(tag.to_string());
+        rest = &rest[end..];
+        for raw in ["style", "script"] {
+            if tag.starts_with(&format!("<{}", raw)) {
+                let close = rest.find(&format!("</{}", raw)).unwrap();
+                nodes.push(format!("{}: {}", raw, tokens(&rest[..close], raw == "style").join(" ")));
+                rest = &rest[close..];
+            }
+        }
+    }
+    text(&mut nodes, rest);
+    // Whitespace between tags renders as at most one space either way
+    nodes.dedup();
+    nodes
+}
+
+fn html_files(root: &Path, dir: &Path, files: &mut Vec<String>) {
+    for entry in fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            html_files(root, &path, files);
+        } else if path.extension().is_some_and(|extension| extension == "html") {
+            files.push(path.strip_prefix(root).unwrap().to_string_lossy().into_owned());
+        }
+    }
+    files.sort();
+}
+
+#[test]
+fn minified_pages_have_the_same_dom() {
+    let full = tempfile::tempdir().unwrap();
+    let minified = tempfile::tempdir().unwrap();
+    assert!(static_site::build(&build_options(&fixture_dir(), full.path())).is_publishable());
+    let mut options = build_options(&fixture_dir(), minified.path());
+    options.minify = true;
+    assert!(static_site::build(&options).is_publishable());
+
+    let mut pages = Vec::new();
+    html_files(full.path(), full.path(), &mut pages);
+    assert!(pages.len() > 10);
+    for page in &pages {
+        let before = fs::read_to_string(full.path().join(page)).unwrap();
+        let after = fs::read_to_string(minified.path().join(page)).unwrap();
+        assert!(after.len() < before.len(), "{} is smaller", page);
+        let (before, after) = (dom(&before), dom(&after));
+        if let Some(index) = (0..before.len().max(after.len())).find(|&index| before.get(index) != after.get(index)) {
+            panic!("{} differs at node {}:\nbefore: {:?}\nafter:  {:?}", page, index, before.get(index), after.get(index));
+        }
+    }
+
+    // Bundles are minified either way
+    for name in fs::read_dir(full.path().join("css")).unwrap().flatten().map(|entry| entry.file_name()) {
+        let css = fs::read_to_string(full.path().join("css").join(&name)).unwrap();
+        assert_eq!(fs::read_to_string(minified.path().join("css").join(&name)).unwrap(), css);
+        assert_eq!(minify::css(&css), css.replace('\n', ""));
+    }
+
+    let home = fs::read_to_string(minified.path().join("index.html")).unwrap();
+    insta::assert_snapshot!("minified_home", dom(&home).join("\n"));
+}
+
+#[test]
+fn css_keeps_what_matters() {
+    assert_eq!(
+        minify::css("/* theme */\na :hover ,\nb > c {\n  color: red ;\n  content: \"a  /* b */ ;\";\n  width: calc(100% - 2px);\n}\n@media screen and (max-width: 600px) { p { margin: 0 } }\n"),
+        "a :hover,b>c{color:red;content:\"a  /* b */ ;\";width:calc(100% - 2px)}@media screen and (max-width:600px){p{margin:0}}"
+    );
+}
+
+#[test]
+fn js_keeps_what_matters() {
+    assert_eq!(
+        minify::js("// setup\nconst a = b - -c; /* note */ let d = a + +e\nconst url = 'http://x//y';\nconst re = /\\/\\/ [a/b]/g;\nconst t = `  keep ${ a /* not a comment */ }  `;\nif (a) {\n    return /x/.test(t)\n}\n"),
+        "const a=b- -c;let d=a+ +e\nconst url='http://x//y';\nconst re=/\\/\\/ [a/b]/g;\nconst t=`  keep ${ a /* not a comment */ }  `;\nif(a){\nreturn/x/.test(t)\n}"
+    );
+}
+
+#[test]
+fn html_keeps_what_matters() {
+    assert_eq!(
+        minify::html("<!-- note -->\n<div class=\"a  b\">\n    Hello   <b>there</b>\n</div>\n<pre>\n  keep   this\n</pre>\n<textarea>  and  this </textarea>\n<script type=\"application/ld+json\">{ \"a\": 1 }</script>\n"),
+        "\n<div class=\"a  b\">\nHello <b>there</b>\n</div>\n<pre>\n  keep   this\n</pre>\n<textarea>  and  this </textarea>\n<script type=\"application/ld+json\">{ \"a\": 1 }</script>\n"
+    );
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use common::copy_fixture;
+use website_test::content;
+use website_test::moderation::{self, PendingReview};
+
+fn review(author: &str, quote: &str) -> PendingReview {
+    PendingReview::new(author, "", quote, Some(4), Utc.with_ymd_and_hms(2026, 5, 2, 19, 30, 0).unwrap()).unwrap()
+}
+
+#[test]
+fn submissions_are_checked() {
+    let review = review("  Sam  ", " Lovely show ");
+    assert_eq!(review.id, "20260502193000-sam");
+    assert_eq!(review.submitted, "2026-05-02T19:30:00Z");
+    assert_eq!(review.author, "Sam");
+    assert_eq!(review.title, "Visitor");
+    assert_eq!(review.quote, "Lovely show");
+    assert_eq!(review.date(), NaiveDate::from_ymd_opt(2026, 5, 2));
+
+    let now = Utc::now();
+    assert!(PendingReview::new("", "", "Hi", None, now).is_err());
+    assert!(PendingReview::new("Sam", "", "   ", None, now).is_err());
+    assert!(PendingReview::new("Sam", "", "Hi", Some(6), now).is_err());
+    assert!(PendingReview::new("Sam", "", &"a".repeat(moderation::MAX_REVIEW_LENGTH + 1), None, now).is_err());
+    assert!(PendingReview::new(&"a".repeat(moderation::MAX_NAME_LENGTH + 1), "", "Hi", None, now).is_err());
+}
+
+#[test]
+fn pending_reviews_get_unique_ids() {
+    let scratch = tempfile::tempdir().unwrap();
+    let pending = scratch.path().join("pending-reviews.yaml");
+    assert!(moderation::read_pending(&pending).unwrap().is_empty());
+
+    let first = moderation::submit(&pending, review("Sam", "One")).unwrap();
+    let second = moderation::submit(&pending, review("Sam", "Two")).unwrap();
+    assert_eq!(first.id, "20260502193000-sam");
+    assert_eq!(second.id, "20260502193000-sam-2");
+    assert_eq!(moderation::read_pending(&pending).unwrap(), [first, second]);
+}
+
+#[test]
+fn approved_reviews_are_published_and_rejected_ones_dropped() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let pending = content.path().join("pending-reviews.yaml");
+    let kept = moderation::submit(&pending, review("Sam & Co", "Great <b>energy</b>")).unwrap();
+    let dropped = moderation::submit(&pending, review("Spammer", "Buy now")).unwrap();
+
+    let testimonial = moderation::approve(&pending, content.path(), &kept.id).unwrap();
+    assert_eq!(testimonial.author, "Sam & Co");
+    assert_eq!(moderation::reject(&pending, &dropped.id).unwrap().author, "Spammer");
+    assert!(moderation::read_pending(&pending).unwrap().is_empty());
+    assert!(moderation::approve(&pending, content.path(), &kept.id).unwrap_err().contains("no pending review"));
+
+    // Approved reviews follow the hand-written ones
+    let testimonials = content::read_testimonials(content.path()).unwrap();
+    let authors: Vec<&str> = testimonials.iter().map(|testimonial| testimonial.author.as_str()).collect();
+    assert_eq!(authors, ["Tom & Jerry", "Pat", "Sam & Co"]);
+    let approved = &testimonials[2];
+    assert_eq!(approved.quote, "Great <b>energy</b>");
+    assert_eq!(approved.rating, Some(4));
+    assert_eq!(approved.date, NaiveDate::from_ymd_opt(2026, 5, 2));
+
+    let yaml = std::fs::read_to_string(content.path().join("reviews/approved.yaml")).unwrap();
+    assert!(yaml.contains("date: 2026-05-02"));
+    assert!(!yaml.contains("featured"), "unset fields are left out:\n{}", yaml);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::{copy_fixture, fixture_dir};
+use website_test::posts::{self, POSTS_PER_PAGE};
+
+#[test]
+fn posts_are_read_newest_first_without_drafts() {
+    let posts = posts::read_posts(&fixture_dir(), "/assets", false).unwrap();
+    let slugs: Vec<&str> = posts.iter().map(|post| post.slug.as_str()).collect();
+    assert_eq!(slugs, ["summer-tour", "album-launch"]);
+
+    let with_drafts = posts::read_posts(&fixture_dir(), "/assets", true).unwrap();
+    assert_eq!(with_drafts[0].slug, "secret-project");
+    assert!(with_drafts[0].draft);
+}
+
+#[test]
+fn front_matter_and_markdown_are_parsed() {
+    let posts = posts::read_posts(&fixture_dir(), "/assets", false).unwrap();
+    let post = posts.iter().find(|post| post.slug == "album-launch").unwrap();
+    assert_eq!(post.title, "The Album Is <Out>");
+    assert_eq!(post.display_date(), "March 1, 2024");
+    assert_eq!(post.tags, ["Music", "Announcements"]);
+    assert_eq!(post.cover.as_deref(), Some("global-images/1.png"));
+    assert_eq!(post.summary, "The new album is finally here, and it’s everything we hoped.");
+    assert!(post.body_html.contains("<em>everything</em>"));
+    assert!(post.body_html.contains(r#"<img src="/assets/posts/stage.png" alt="On stage" />"#));
+    assert!(post.body_html.contains(r#"<a href="https://open.spotify.com/album/fixture">"#));
+    assert!(post.body_html.contains(r#"<a href="/assets/posts/summer-tour-notes.txt">"#));
+
+    let tour = posts.iter().find(|post| post.slug == "summer-tour").unwrap();
+    assert_eq!(tour.summary, "Dates for the summer tour & more.");
+    assert!(tour.body_html.contains("<table>"));
+}
+
+#[test]
+fn tags_are_collected_in_order_of_first_use() {
+    let posts = posts::read_posts(&fixture_dir(), "/assets", false).unwrap();
+    let tags: Vec<(String, String)> = posts::tags(&posts);
+    assert_eq!(
+        tags,
+        [
+            ("Music".to_string(), "music".to_string()),
+            ("Tour".to_string(), "tour".to_string()),
+            ("Announcements".to_string(), "announcements".to_string()),
+        ]
+    );
+    assert!(posts[0].has_tag("tour"));
+    assert!(!posts[1].has_tag("tour"));
+}
+
+#[test]
+fn posts_are_paginated() {
+    let content = tempfile::tempdir().unwrap();
+    let posts_dir = content.path().join("posts");
+    std::fs::create_dir_all(&posts_dir).unwrap();
+    for day in 1..=POSTS_PER_PAGE + 2 {
+        std::fs::write(
+            posts_dir.join(format!("post-{:02}.md", day)),
+            format!("---\ntitle: Post {}\ndate: 2024-01-{:02}\n---\nBody\n", day, day),
+        )
+        .unwrap();
+    }
+
+    let posts = posts::read_posts(content.path(), "", false).unwrap();
+    assert_eq!(posts::page_count(posts.len()), 2);
+    assert_eq!(posts::page(&posts, 1).unwrap().len(), POSTS_PER_PAGE);
+    assert_eq!(posts::page(&posts, 2).unwrap()[0].title, "Post 2");
+    assert!(posts::page(&posts, 0).is_none());
+    assert!(posts::page(&posts, 3).is_none());
+    assert_eq!(posts::page_count(0), 1);
+    assert_eq!(posts::page(&[], 1).unwrap().len(), 0);
+}
+
+#[test]
+fn missing_posts_folder_means_no_posts() {
+    let content = tempfile::tempdir().unwrap();
+    assert!(posts::read_posts(content.path(), "", true).unwrap().is_empty());
+}
+
+#[test]
+fn invalid_posts_are_reported() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let posts_dir = content.path().join("posts");
+
+    for (name, source, expected) in [
+        ("no-front-matter.md", "# Just markdown\n", "missing --- front matter"),
+        ("bad-date.md", "---\ntitle: A\ndate: March 2024\n---\n", "invalid date \"March 2024\""),
+        ("unknown-field.md", "---\ntitle: A\ndate: 2024-03-01\nauthor: Me\n---\n", "unknown field `author`"),
+        ("Not A Slug.md", "---\ntitle: A\ndate: 2024-03-01\n---\n", "must be a lowercase slug"),
+        ("tags.md", "---\ntitle: A\ndate: 2024-03-01\n---\n", "must be a lowercase slug"),
+    ] {
+        let path = posts_dir.join(name);
+        std::fs::write(&path, source).unwrap();
+        let error = posts::read_posts(content.path(), "", true).unwrap_err();
+        assert!(error.contains(expected), "{:?} should mention {:?}", error, expected);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use common::{build_options, fixture_dir};
+use std::fs;
+use std::path::Path;
+use tower::ServiceExt;
+use website_test::preview;
+use website_test::static_site::{self, DEFAULT_BASE_PATH};
+
+fn build_fixture(out_dir: &Path) {
+    assert!(static_site::build(&build_options(&fixture_dir(), out_dir)).is_publishable());
+}
+
+async fn get(out_dir: &Path, uri: &str) -> (StatusCode, String, String) {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = preview::router(out_dir, DEFAULT_BASE_PATH).oneshot(request).await.unwrap();
+    let status = response.status();
+    let content_type = response.headers().get(header::CONTENT_TYPE).map(|value| value.to_str().unwrap().to_string()).unwrap_or_default();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, content_type, String::from_utf8_lossy(&body).into_owned())
+}
+
+#[tokio::test]
+async fn serves_files_and_folder_indexes_under_the_base_path() {
+    let parent = tempfile::tempdir().unwrap();
+    let out = parent.path().join("docs");
+    build_fixture(&out);
+
+    let (status, content_type, body) = get(&out, "/Website-test/").await;
+    assert_eq!((status, content_type.as_str()), (StatusCode::OK, "text/html"));
+    assert!(body.contains("Fixture Home"));
+
+    for uri in ["/Website-test/bio/", "/Website-test/bio/index.html", "/Website-test/modeling/glamour/"] {
+        assert_eq!(get(&out, uri).await.0, StatusCode::OK, "GET {}", uri);
+    }
+    let shared = fs::read_dir(out.join("css")).unwrap().flatten().map(|entry| entry.file_name().into_string().unwrap()).find(|name| name.starts_with("shared.")).unwrap();
+    let (status, content_type, _) = get(&out, &format!("/Website-test/css/{}", shared)).await;
+    assert_eq!((status, content_type.as_str()), (StatusCode::OK, "text/css"));
+    assert_eq!(get(&out, "/Website-test/modeling/Glamour/images/b%20shot.jpg").await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn everything_else_gets_the_404_page() {
+    let parent = tempfile::tempdir().unwrap();
+    let out = parent.path().join("docs");
+    build_fixture(&out);
+
+    for uri in [
+        "/",
+        "/bio/",
+        "/Website-test",
+        "/Website-test/bio",
+        "/Website-test/missing.html",
+        "/Website-test/../docs/index.html",
+        "/Website-test/%2e%2e/docs/index.html",
+        "/Website-testing/",
+    ] {
+        let (status, content_type, body) = get(&out, uri).await;
+        assert_eq!((status, content_type.as_str()), (StatusCode::NOT_FOUND, "text/html"), "GET {}", uri);
+        assert!(body.contains("404 - Page Not Found"), "GET {} serves 404.html", uri);
+    }
+}
+
+#[tokio::test]
+async fn the_404_page_ignores_conditional_and_range_headers() {
+    let parent = tempfile::tempdir().unwrap();
+    let out = parent.path().join("docs");
+    build_fixture(&out);
+    let page = fs::read_to_string(out.join("404.html")).unwrap();
+
+    let request = Request::get("/Website-test/missing.html")
+        .header(header::IF_NONE_MATCH, "*")
+        .header(header::IF_MODIFIED_SINCE, "Fri, 01 Jan 2100 00:00:00 GMT")
+        .header(header::RANGE, "bytes=0-9")
+        .body(Body::empty())
+        .unwrap();
+    let response = preview::router(&out, DEFAULT_BASE_PATH).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(response.headers().get(header::CONTENT_RANGE).is_none());
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(String::from_utf8_lossy(&body), page);
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::fixture_dir;
+use serde_json::Value;
+use website_test::content;
+use website_test::discography;
+use website_test::media;
+use website_test::seo::{PageMeta, Seo};
+use website_test::settings::{self, SiteSettings};
+
+fn fixture_settings() -> SiteSettings {
+    settings::read_site_settings(&fixture_dir()).unwrap()
+}
+
+fn json_ld_types(meta: &PageMeta) -> Vec<String> {
+    meta.json_ld.iter().map(|object| object["@type"].as_str().unwrap().to_string()).collect()
+}
+
+#[test]
+fn head_tags_are_escaped() {
+    let meta = PageMeta {
+        description: Some(r#"Songs & "stories""#.to_string()),
+        image: Some("https://fixture.example/a.png".to_string()),
+        canonical: Some("https://fixture.example/bio/".to_string()),
+        og_type: Some("profile"),
+        twitter_site: Some("@fixture".to_string()),
+        json_ld: vec![serde_json::json!({ "@type": "Person", "name": "</script><b>" })],
+        noindex: false,
+        route: Some("/bio/".to_string()),
+    };
+    let head = meta.head_html("Bio <1>");
+
+    assert!(head.contains(r#"<meta name="description" content="Songs &amp; &quot;stories&quot;">"#));
+    assert!(head.contains(r#"<link rel="canonical" href="https://fixture.example/bio/">"#));
+    assert!(head.contains(r#"<meta property="og:type" content="profile">"#));
+    assert!(head.contains(r#"<meta property="og:title" content="Bio &lt;1&gt;">"#));
+    assert!(head.contains(r#"<meta property="og:image" content="https://fixture.example/a.png">"#));
+    assert!(head.contains(r#"<meta name="twitter:card" content="summary_large_image">"#));
+    assert!(head.contains(r#"<meta name="twitter:site" content="@fixture">"#));
+    assert!(head.contains(r#"<script type="application/ld+json">{"@type":"Person","name":"<\/script><b>"}</script>"#));
+    assert!(!head.contains("robots"));
+
+    let bare = PageMeta::noindex().head_html("404");
+    assert!(bare.contains(r#"<meta name="robots" content="noindex">"#));
+    assert!(bare.contains(r#"<meta name="twitter:card" content="summary">"#));
+    assert!(!bare.contains("canonical"));
+    assert!(!bare.contains("og:image"));
+}
+
+#[test]
+fn pages_use_overrides_then_site_defaults() {
+    let settings = fixture_settings();
+    let seo = Seo::new(&settings);
+
+    let events = seo.page("/events/");
+    assert_eq!(events.description.as_deref(), Some("Fixture performer & friends"));
+    assert_eq!(events.canonical.as_deref(), Some("https://fixture.example/site/events/"));
+    assert_eq!(events.image.as_deref(), Some("https://fixture.example/site/global-images/1.png"));
+
+    let bio = seo.bio();
+    assert_eq!(bio.description.as_deref(), Some(r#"All about the "fixture" performer"#));
+    assert_eq!(json_ld_types(&bio), ["Person"]);
+    assert_eq!(bio.json_ld[0]["sameAs"][0], "https://example.com/fixture-performer");
+    assert_eq!(bio.json_ld[0]["url"], "https://fixture.example/site/bio/");
+
+    // Without a site URL nothing can be made absolute
+    let local = SiteSettings { description: Some("Local".to_string()), share_image: Some("a.png".to_string()), ..SiteSettings::default() };
+    let meta = Seo::new(&local).page("/bio/");
+    assert_eq!(meta.description.as_deref(), Some("Local"));
+    assert_eq!(meta.canonical, None);
+    assert_eq!(meta.image, None);
+}
+
+#[test]
+fn media_pages_describe_videos_and_albums() {
+    let settings = fixture_settings();
+    let seo = Seo::new(&settings);
+    let groups = media::read_media(&fixture_dir(), "acting", ".").unwrap();
+
+    let acting = seo.media("/acting/", &groups);
+    // Playlists and non-YouTube items have no VideoObject
+    assert_eq!(json_ld_types(&acting), ["VideoObject", "VideoObject", "VideoObject"]);
+    let showreel = acting.json_ld.iter().find(|video| video["name"] == "Showreel 2024").unwrap();
+    assert_eq!(showreel["uploadDate"], "2024-05-20");
+    assert_eq!(showreel["thumbnailUrl"], "https://i.ytimg.com/vi/actingCCCC3/hqdefault.jpg");
+    assert_eq!(showreel["embedUrl"], "https://www.youtube.com/embed/actingCCCC3?start=65");
+
+    let releases = discography::read_releases(&fixture_dir()).unwrap();
+    let music = seo.music(&media::read_media(&fixture_dir(), "music", ".").unwrap(), &releases);
+    let albums: Vec<&Value> = music.json_ld.iter().filter(|object| object["@type"] == "MusicAlbum").collect();
+    assert_eq!(albums.len(), 2);
+    let single = albums.iter().find(|album| album["name"] == "First Steps").unwrap();
+    assert_eq!(single["albumReleaseType"], "https://schema.org/SingleRelease");
+    let album = albums.iter().find(|album| album["name"] == "Night & Day").unwrap();
+    assert_eq!(album["image"], "https://fixture.example/site/global-images/1.png");
+    assert_eq!(album["track"][1]["duration"], "PT5M15S");
+
+    let release = seo.release(releases.iter().find(|release| release.slug == "night-day").unwrap());
+    assert_eq!(release.og_type, Some("music.album"));
+    assert_eq!(release.description.as_deref(), Some(r#"A "fixture" album."#));
+    assert_eq!(release.canonical.as_deref(), Some("https://fixture.example/site/music/releases/night-day/"));
+}
+
+#[test]
+fn modeling_and_reviews_metadata() {
+    let settings = fixture_settings();
+    let seo = Seo::new(&settings);
+
+    let categories = content::discover_modeling_categories(&fixture_dir(), ".");
+    let modeling = seo.modeling(&categories);
+    assert_eq!(modeling.image.as_deref(), Some("https://fixture.example/site/modeling/Glamour/Background/bkgrnd.png"));
+
+    let reviews = seo.reviews(&content::read_testimonials(&fixture_dir()).unwrap());
+    assert_eq!(json_ld_types(&reviews), ["Person", "Review", "Review"]);
+    let aggregate = &reviews.json_ld[0]["aggregateRating"];
+    assert_eq!(aggregate["ratingValue"], 4.5);
+    assert_eq!(aggregate["ratingCount"], 2);
+    assert_eq!(aggregate["bestRating"], 5);
+    // Sorted by `order`
+    assert_eq!(reviews.json_ld[1]["author"]["name"], "Tom & Jerry");
+    assert_eq!(reviews.json_ld[1]["itemReviewed"]["name"], "Amber Techel");
+    assert_eq!(reviews.json_ld[1]["reviewRating"]["ratingValue"], 4);
+    assert!(reviews.json_ld[1].get("datePublished").is_none());
+    let pat = &reviews.json_ld[2];
+    assert_eq!(pat["datePublished"], "2020-06-01");
+    assert_eq!(pat["url"], "https://example.com/reviews/pat?id=1&page=2");
+    assert_eq!(pat["author"]["image"], "https://fixture.example/site/global-images/1.png");
+
+    // Without ratings there is nothing to aggregate
+    assert!(content::aggregate_rating(&[]).is_none());
+}
@@ -0,0 +1,771 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{HeaderMap, Request, StatusCode, header};
+use common::{TestApp, copy_fixture, fixture_dir, main_content};
+use website_test::bundles::Bundler;
+use website_test::moderation;
+
+const NAV_LINKS: &[&str] = &[
+    r#"<a href="/" class="nav-item">Home</a>"#,
+    r#"<a href="/bio/" class="nav-item">Bio</a>"#,
+    r#"<a href="/acting/" class="nav-item">Acting</a>"#,
+    r#"<a href="/music/" class="nav-item">Music</a>"#,
+    r#"<a href="/modeling/" class="nav-item">Modeling</a>"#,
+    r#"<a href="/reviews/" class="nav-item">Reviews</a>"#,
+    r#"<a href="/events/" class="nav-item">Events</a>"#,
+    r#"<a href="/news/" class="nav-item">News</a>"#,
+    r#"<a href="/behind-the-scenes/" class="nav-item">Behind the Scenes</a>"#,
+    r#"<a href="/contact/" class="nav-item">Contact</a>"#,
+];
+
+async fn get_page(app: &TestApp, uri: &str, title: &str) -> String {
+    let (status, headers, body) = app.get(uri).await;
+    assert_eq!(status, StatusCode::OK, "GET {}", uri);
+    assert!(
+        headers[header::CONTENT_TYPE].to_str().unwrap().starts_with("text/html"),
+        "GET {} is html",
+        uri
+    );
+    assert!(
+        body.contains(&format!("<title>{} - 4AmberTechel</title>", title)),
+        "GET {} has title {:?}",
+        uri,
+        title
+    );
+    for link in NAV_LINKS {
+        assert!(body.contains(link), "GET {} is missing nav link {}", uri, link);
+    }
+    body
+}
+
+#[tokio::test]
+async fn home_page_renders_template() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/", "Home").await;
+    assert!(main_content(&body).contains("Fixture Home"));
+    assert!(body.contains(r#"src="/templates/global-images/1.png""#));
+}
+
+#[tokio::test]
+async fn bio_page_renders_template() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/bio/", "Bio").await;
+    assert!(main_content(&body).contains("Fixture Bio"));
+}
+
+#[tokio::test]
+async fn music_page_embeds_media_links() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/music/", "Music").await;
+    let content = main_content(&body);
+    assert!(content.contains("https://www.youtube.com/embed/musicAAAAA1"));
+    assert!(content.contains("https://www.youtube.com/embed/musicBBBBB2?start=30\""));
+    assert!(content.contains(r#"<iframe src="https://player.vimeo.com/video/76979871?dnt=1""#));
+    assert_eq!(content.matches("youtube-video-wrapper").count(), 3);
+    assert!(content.contains(r#"<iframe src="https://open.spotify.com/embed/album/4aawyAB9vmqN3uQ7FjRGTy" height="352""#));
+    assert!(content.contains("https://w.soundcloud.com/player/?url=https%3A%2F%2Fsoundcloud.com%2Ffixture-artist%2Ffirst-song"));
+    assert!(content.contains(r#"<a class="media-link" href="https://fixture.bandcamp.com/album/debut""#));
+    assert!(content.contains(r#"<audio class="media-audio" src="/templates/music/demo_take-1.mp3" controls"#));
+    assert!(content.contains(r#"<h4 class="media-title">demo take 1</h4>"#));
+    assert!(!content.contains("{{YOUTUBE_EMBEDS}}"));
+}
+
+#[tokio::test]
+async fn music_page_lists_discography() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/music/", "Music").await;
+    let content = main_content(&body);
+    assert!(content.contains(r#"<a href="/music/releases/night-day/" class="release-card">"#));
+    assert!(content.contains(r#"<img src="/templates/global-images/1.png" alt="Night &amp; Day Cover""#));
+    assert!(content.contains("Album · March 1, 2024 · 2 tracks · 9:45"));
+    assert!(content.find("night-day").unwrap() < content.find("first-steps").unwrap());
+    assert!(!content.contains("{{DISCOGRAPHY}}"));
+}
+
+#[tokio::test]
+async fn release_pages_render_tracklist_lyrics_and_credits() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/music/releases/night-day/", "Night &amp; Day").await;
+    let content = main_content(&body);
+    assert!(content.contains(r#"<a href="/music/" class="release-back-link">"#));
+    assert!(content.contains(r#"<a href="https://youtu.be/musicAAAAA1" target="_blank" class="track-item">"#));
+    assert!(content.contains(r#"<span class="track-duration">5:15</span>"#));
+    assert!(content.contains("Line one<br>\nLine &lt;two&gt;"));
+    assert!(content.contains("<dt>Producer</dt><dd>Sam &amp; Co</dd>"));
+    assert!(content.contains(r#"<a href="https://music.amazon.com/albums/fixture?a=1&amp;b=2" target="_blank" class="stream-btn amazon">"#));
+
+    let (status, _, _) = app.get("/music/releases/no-such-release/").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, headers, _) = app.get("/music/releases/first-steps").await;
+    assert_eq!(status, StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(headers[header::LOCATION], "/music/releases/first-steps/");
+}
+
+#[tokio::test]
+async fn acting_page_renders_media_sections() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/acting/", "Acting").await;
+    let content = main_content(&body);
+    assert!(content.contains(r#"<h3 class="media-group-title">Reels</h3>"#));
+    assert!(content.find("Reels").unwrap() < content.find("Short Films").unwrap());
+    assert!(content.contains(r#"<figure class="media-card featured">"#));
+    assert!(content.contains(r#"<iframe src="https://www.youtube.com/embed/actingCCCC3?start=65" title="Showreel 2024""#));
+    assert!(content.contains(r#"<p class="media-description">Drama &amp; comedy highlights</p>"#));
+    assert!(content.contains(r#"<h4 class="media-title">Behind the &lt;scenes&gt;</h4>"#));
+    assert!(content.contains(r#"<video class="media-video" src="/templates/acting/clip.mp4#t=5" controls"#));
+    assert!(!content.contains("Unknown host"));
+    assert!(!content.contains("{{ACTING_YOUTUBE_EMBEDS}}"));
+}
+
+#[tokio::test]
+async fn acting_page_renders_resume_and_pdf() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/acting/", "Acting").await;
+    let content = main_content(&body);
+    assert!(content.contains(r#"<a href="/acting/resume.pdf" class="resume-download" download>"#));
+    assert!(content.contains("<div><dt>Height</dt><dd>5&#39;6&quot;</dd></div>"));
+    assert!(content.contains("<td>Recurring &lt;Guest&gt;</td><td>Fixture Studios</td><td>2024</td>"));
+    assert!(content.contains("<td>Dir. Pat Doe / Barn &amp; Sons</td>"));
+    assert!(content.contains(r#"<ul class="resume-skills"><li>Horseback riding</li><li>Singing (soprano)</li></ul>"#));
+    assert!(!content.contains("{{ACTING_RESUME}}"));
+
+    let (status, headers, body) = app.get("/acting/resume.pdf").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "application/pdf");
+    assert!(body.starts_with("%PDF"));
+}
+
+#[tokio::test]
+async fn acting_page_has_no_resume_without_credits() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    std::fs::remove_file(content.path().join("acting/credits.yaml")).unwrap();
+    let app = TestApp::with_content(content.path().to_path_buf());
+
+    let body = get_page(&app, "/acting/", "Acting").await;
+    assert!(!main_content(&body).contains("resume.pdf"));
+    let (status, _, _) = app.get("/acting/resume.pdf").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn lite_embeds_link_to_proxied_thumbnails() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    std::fs::write(content.path().join("site.yaml"), "video_embeds: lite\n").unwrap();
+    let app = TestApp::with_content(content.path().to_path_buf());
+
+    let body = get_page(&app, "/music/", "Music").await;
+    let content = main_content(&body);
+    assert!(content.contains(r#"<img src="/thumbnails/musicAAAAA1.jpg""#));
+    assert!(content.contains(r#"data-embed="https://www.youtube-nocookie.com/embed/musicAAAAA1?autoplay=1""#));
+    assert!(!content.contains(r#"<iframe src="https://www.youtube.com"#));
+    assert!(content.contains("https://player.vimeo.com/video/76979871"), "other providers are unaffected");
+}
+
+#[tokio::test]
+async fn thumbnails_fall_back_to_placeholder() {
+    let app = TestApp::new();
+    std::fs::create_dir_all(app.state.config.thumbnails.dir.as_path()).unwrap();
+    std::fs::write(app.state.config.thumbnails.dir.join("musicAAAAA1.jpg"), "jpeg bytes").unwrap();
+
+    let (status, headers, body) = app.get("/thumbnails/musicAAAAA1.jpg").await;
+    assert_eq!((status, body.as_str()), (StatusCode::OK, "jpeg bytes"));
+    assert_eq!(headers[header::CONTENT_TYPE], "image/jpeg");
+
+    let (status, headers, body) = app.get("/thumbnails/musicBBBBB2.jpg").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "image/svg+xml");
+    assert!(body.starts_with("<svg"));
+
+    let (status, _, _) = app.get("/thumbnails/..%2Fsecret.jpg").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn pages_link_their_style_bundles() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/bio/", "Bio").await;
+    assert!(!body.contains("<style>"));
+    let prefix = r#"<link rel="stylesheet" href="/css/"#;
+    let names: Vec<&str> = body
+        .match_indices(prefix)
+        .map(|(index, _)| {
+            let rest = &body[index + prefix.len()..];
+            &rest[..rest.find('"').unwrap()]
+        })
+        .collect();
+    assert_eq!(names.len(), 2);
+    assert_eq!(names[0], Bundler::load(&fixture_dir()).unwrap().shared().name, "same shared bundle as the static build");
+
+    let (status, headers, css) = app.get(&format!("/css/{}", names[1])).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "text/css");
+    assert_eq!(css, ".main-content{background-image:url('/templates/bio/background/bkgrnd.png')}");
+
+    let (status, _, _) = app.get("/css/shared.0000000000000000.css").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+/// The shared bundle a page links.
+fn shared_bundle(body: &str) -> String {
+    let start = body.find("/css/shared.").unwrap() + "/css/".len();
+    body[start..start + body[start..].find('"').unwrap()].to_string()
+}
+
+#[tokio::test]
+async fn style_bundles_follow_content_edits() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let app = TestApp::with_content(content.path().to_path_buf());
+
+    let first = shared_bundle(&get_page(&app, "/bio/", "Bio").await);
+    assert_eq!(shared_bundle(&get_page(&app, "/contact/", "Contact").await), first);
+
+    let styles = content.path().join("styles.css");
+    let css = std::fs::read_to_string(&styles).unwrap();
+    std::fs::write(&styles, format!("{}\nbody {{ margin: 3px; }}\n", css)).unwrap();
+    let second = shared_bundle(&get_page(&app, "/bio/", "Bio").await);
+    assert_ne!(second, first);
+
+    let (status, _, css) = app.get(&format!("/css/{}", second)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(css.contains("body{margin:3px}"));
+    let (status, _, _) = app.get(&format!("/css/{}", first)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "only the current bundles are kept");
+}
+
+#[tokio::test]
+async fn modeling_page_injects_categories_json() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/modeling/", "Modeling Portfolio").await;
+    let content = main_content(&body);
+    assert!(content.contains(r#""Glamour": {"title": "Glamour", "subtitle": "Glamour \"fixture\" shots""#));
+    assert!(content.contains(
+        r#""images": ["/templates/modeling/Glamour/images/a.png", "/templates/modeling/Glamour/images/b%20shot.jpg"]"#
+    ));
+    assert!(content.contains(r#""links": {"a.png": "https://example.com/a"}"#));
+    assert!(content.contains(r#""background": "/templates/modeling/Glamour/Background/bkgrnd.png""#));
+    assert!(!content.contains("Drafts"), "categories without images/ are skipped");
+    assert!(content.contains("const initialCategory = null;"));
+}
+
+#[tokio::test]
+async fn modeling_categories_have_their_own_pages() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/modeling/glamour/", "Modeling: Glamour").await;
+    assert!(main_content(&body).contains(r#"const initialCategory = "Glamour";"#));
+    assert!(body.contains(r#"<link rel="canonical" href="https://fixture.example/site/modeling/glamour/">"#));
+    assert!(body.contains(r#"<meta name="description" content="Glamour &quot;fixture&quot; shots">"#));
+
+    let (status, _, _) = app.get("/modeling/drafts/").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, headers, _) = app.get("/modeling/glamour").await;
+    assert_eq!(status, StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(headers[header::LOCATION], "/modeling/glamour/");
+}
+
+#[tokio::test]
+async fn sitemap_and_robots_are_served() {
+    let app = TestApp::new();
+    let (status, headers, sitemap) = app.get("/sitemap.xml").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "application/xml; charset=utf-8");
+    assert!(sitemap.contains("<loc>http://127.0.0.1:3000/modeling/glamour/</loc>"));
+    assert!(sitemap.contains("<image:loc>http://127.0.0.1:3000/templates/modeling/Glamour/images/a.png</image:loc>"));
+    assert!(sitemap.contains("<loc>http://127.0.0.1:3000/news/album-launch/</loc>"));
+    assert!(!sitemap.contains("secret-project"));
+
+    let (status, headers, robots) = app.get("/robots.txt").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "text/plain; charset=utf-8");
+    assert_eq!(robots, "User-agent: *\nDisallow:\n\nSitemap: http://127.0.0.1:3000/sitemap.xml\n");
+}
+
+#[tokio::test]
+async fn reviews_page_escapes_testimonials() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/reviews/", "Reviews").await;
+    let content = main_content(&body);
+    assert_eq!(content.matches("testimonial-card").count(), 2);
+    assert!(content.contains("A true professional."));
+    assert!(content.contains("&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt; &amp; more"));
+    assert!(content.contains("Tom &amp; Jerry"));
+    assert!(content.contains("Fan &quot;#1&quot;"));
+    assert!(!content.contains("<script>alert"));
+
+    // Grouped by category, with a filter button per category
+    assert!(content.contains(r#"<button type="button" data-category="fan-friends">Fan &amp; Friends</button>"#));
+    assert!(content.contains(r#"<section class="testimonials-group" data-category="photographer">"#));
+    assert!(content.find(r#"id="tom-jerry-fan-1""#) < content.find(r#"id="pat-photographer""#));
+    assert!(content.contains(r#"<div class="testimonial-rating" aria-label="5 out of 5 stars">★★★★★</div>"#));
+    assert!(content.contains(r#"<img src="/templates/global-images/1.png" alt="Pat" class="testimonial-photo" loading="lazy">"#));
+    assert!(content.contains(r#"<a href="https://example.com/reviews/pat?id=1&amp;page=2" target="_blank" rel="noopener">Original review</a>"#));
+    assert!(body.contains(r#""aggregateRating":{"#));
+}
+
+#[tokio::test]
+async fn events_page_lists_upcoming_and_archives_past() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/events/", "Events").await;
+    let content = main_content(&body);
+    let archive = content.find(r#"<details class="events-archive">"#).unwrap();
+    assert!(content.find("Release Show").unwrap() < content.find("Comic Con").unwrap());
+    assert!(content.find("Comic Con").unwrap() < archive);
+    assert!(content[archive..].contains("<summary>Past Events (2)</summary>"));
+    assert!(content[archive..].find("Summer Shoot").unwrap() < content[archive..].find("First Gig").unwrap());
+    assert!(content.contains(r#"<p class="event-venue">The &lt;Blue&gt; Note</p>"#));
+    assert!(content.contains("Sat, May 2, 2099 · 7:30 PM – 10:00 PM"));
+    assert!(content.contains(r#"<a href="https://example.com/tickets?show=1&amp;seat=2" target="_blank" class="event-link">"#));
+    assert!(!content.contains("https://example.com/old"), "past events drop their ticket links");
+    assert!(content.contains(r#"<a href="/events.ics" class="events-subscribe">"#));
+    assert!(!content.contains("{{EVENTS}}"));
+}
+
+#[tokio::test]
+async fn home_page_shows_next_event() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/", "Home").await;
+    let content = main_content(&body);
+    assert!(content.contains(r#"<a href="/events/" class="next-event">"#));
+    assert!(content.contains(r#"<span class="next-event-title">Release Show</span>"#));
+    assert!(!content.contains("{{NEXT_EVENT}}"));
+}
+
+#[tokio::test]
+async fn home_page_shows_featured_testimonials() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/", "Home").await;
+    let content = main_content(&body);
+    assert_eq!(content.matches(r#"<figure class="featured-testimonial">"#).count(), 2);
+    assert!(content.contains(r#"<a href="/reviews/" class="featured-testimonials-more">"#));
+    assert!(!content.contains("{{FEATURED_TESTIMONIALS}}"));
+}
+
+#[tokio::test]
+async fn broken_reviews_file_is_a_server_error() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    std::fs::write(content.path().join("reviews/reviews.yaml"), "testimonials: [").unwrap();
+    let app = TestApp::with_content(content.path().to_path_buf());
+
+    for path in ["/reviews/", "/"] {
+        let (status, _, body) = app.get(path).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR, "{}", path);
+        assert!(!main_content(&body).contains("testimonial-card"));
+    }
+}
+
+#[tokio::test]
+async fn broken_media_list_is_a_server_error() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    std::fs::write(content.path().join("acting/media.yaml"), "media: [").unwrap();
+    let app = TestApp::with_content(content.path().to_path_buf());
+
+    let (status, _, _) = app.get("/acting/").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn events_feed_is_icalendar() {
+    let app = TestApp::new();
+    let (status, headers, body) = app.get("/events.ics").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "text/calendar; charset=utf-8");
+    assert!(body.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(body.contains("UID:20990502-release-show@4ambertechel\r\n"));
+    assert!(body.contains("DTSTART:20990502T193000\r\nDTEND:20990502T220000\r\n"));
+    assert!(body.contains("DTSTART;VALUE=DATE:20990710\r\nDTEND;VALUE=DATE:20990713\r\n"));
+    assert!(body.contains("SUMMARY:Comic Con\\, Day One\r\n"));
+    assert!(body.contains("DESCRIPTION:Live set\\; all ages\r\n"));
+    assert!(body.ends_with("END:VCALENDAR\r\n"));
+}
+
+#[tokio::test]
+async fn feeds_link_back_to_the_requested_host() {
+    let app = TestApp::new();
+    let request = axum::http::Request::get("/feed.xml")
+        .header(header::HOST, "localhost:8080")
+        .body(axum::body::Body::empty())
+        .unwrap();
+    let (status, headers, atom) = app.request(request).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "application/atom+xml; charset=utf-8");
+    assert!(atom.contains(r#"<link rel="self" type="application/atom+xml" href="http://localhost:8080/feed.xml"/>"#));
+    assert!(atom.contains("<id>http://localhost:8080/news/album-launch/</id>"));
+    assert!(atom.contains("<id>http://localhost:8080/templates/modeling/Glamour/images/a.png</id>"));
+    // Drafts are shown by the dev server but never announced
+    assert!(!atom.contains("secret-project"));
+
+    let (status, headers, json) = app.get("/feed.json").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[header::CONTENT_TYPE], "application/feed+json; charset=utf-8");
+    let feed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(feed["home_page_url"], "http://127.0.0.1:3000/");
+
+    let home = get_page(&app, "/", "Home").await;
+    assert!(home.contains(r#"<link rel="alternate" type="application/atom+xml" title="4AmberTechel" href="/feed.xml">"#));
+}
+
+#[tokio::test]
+async fn pages_have_search_and_share_metadata() {
+    let app = TestApp::new();
+    let bio = get_page(&app, "/bio/", "Bio").await;
+    assert!(bio.contains(r#"<link rel="canonical" href="https://fixture.example/site/bio/">"#));
+    assert!(bio.contains(r#"<meta name="description" content="All about the &quot;fixture&quot; performer">"#));
+    assert!(bio.contains(r#"<meta property="og:image" content="https://fixture.example/site/global-images/1.png">"#));
+    assert!(bio.contains(r#"<script type="application/ld+json">{"@context":"https://schema.org","@type":"Person""#));
+
+    let post = get_page(&app, "/news/album-launch/", "The Album Is &lt;Out&gt;").await;
+    assert!(post.contains(r#"<meta property="og:type" content="article">"#));
+    assert!(post.contains(r#"<link rel="canonical" href="https://fixture.example/site/news/album-launch/">"#));
+
+    let (status, _, missing) = app.get("/no-such-page/").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(missing.contains(r#"<meta name="robots" content="noindex">"#));
+}
+
+#[tokio::test]
+async fn news_page_lists_posts_with_drafts_in_dev_mode() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/news/", "News").await;
+    let content = main_content(&body);
+    assert!(content.find("Secret Project").unwrap() < content.find("Summer Tour").unwrap());
+    assert!(content.contains(r#"<span class="post-draft-badge">Draft</span>"#));
+    assert!(content.contains(r#"<h2 class="post-card-title"><a href="/news/album-launch/">The Album Is &lt;Out&gt;</a></h2>"#));
+    assert!(content.contains(r#"<img src="/templates/global-images/1.png" alt="" class="post-card-cover""#));
+    assert!(content.contains(r#"<a href="/news/tags/music/" class="post-tag">#Music</a>"#));
+    assert!(content.contains(r#"<p class="post-summary">Dates for the summer tour &amp; more.</p>"#));
+    assert!(!content.contains("pagination"));
+    assert!(!content.contains("{{NEWS}}"));
+
+    let published = TestApp::configured(fixture_dir(), |config| config.drafts = false);
+    let body = get_page(&published, "/news/", "News").await;
+    assert!(!body.contains("Secret Project"));
+    let (status, _, _) = published.get("/news/secret-project/").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn news_post_pages_render_markdown() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/news/album-launch/", "The Album Is &lt;Out&gt;").await;
+    let content = main_content(&body);
+    assert!(content.contains(r#"<a href="/news/" class="post-back-link">"#));
+    assert!(content.contains(r#"<time datetime="2024-03-01">March 1, 2024</time>"#));
+    assert!(content.contains(r#"<img src="/templates/posts/stage.png" alt="On stage" />"#));
+    assert!(content.contains(r#"<img src="/templates/global-images/1.png" alt="" class="post-cover""#));
+
+    let (status, _, _) = app.get("/news/no-such-post/").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, headers, _) = app.get("/news/summer-tour").await;
+    assert_eq!(status, StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(headers[header::LOCATION], "/news/summer-tour/");
+    let (status, _, _) = app.get("/news/no-such-post").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn news_tag_pages_filter_posts() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/news/tags/tour/", "News: Tour").await;
+    let content = main_content(&body);
+    assert!(content.contains("<h2>Tagged “Tour”</h2>"));
+    assert!(content.contains("Summer Tour"));
+    assert!(!content.contains("The Album Is"));
+
+    let (status, _, _) = app.get("/news/tags/no-such-tag/").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn news_list_pages_are_paginated() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    for day in 1..=10 {
+        std::fs::write(
+            content.path().join(format!("posts/extra-{:02}.md", day)),
+            format!("---\ntitle: Extra {}\ndate: 2023-01-{:02}\n---\nBody\n", day, day),
+        )
+        .unwrap();
+    }
+    let app = TestApp::with_content(content.path().to_path_buf());
+
+    let first = get_page(&app, "/news/", "News").await;
+    assert!(first.contains(r#"<a href="/news/page/2/" class="pagination-older" rel="next">"#));
+    assert!(first.contains("Page 1 of 2"));
+    let second = get_page(&app, "/news/page/2/", "News").await;
+    assert!(second.contains(r#"<a href="/news/" class="pagination-newer" rel="prev">"#));
+    assert!(second.contains("Extra 1<"));
+
+    let (status, headers, _) = app.get("/news/page/1/").await;
+    assert_eq!(status, StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(headers[header::LOCATION], "/news/");
+    for uri in ["/news/page/3/", "/news/page/0/", "/news/page/two/"] {
+        let (status, _, _) = app.get(uri).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "GET {}", uri);
+    }
+}
+
+#[tokio::test]
+async fn behind_the_scenes_page_injects_images_and_subtitle() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/behind-the-scenes/", "Behind the Scenes").await;
+    let content = main_content(&body);
+    assert!(content.contains("Fixture set photos"));
+    assert!(content.contains(r#"const btsImages = ["/templates/Behind the scenes/images/set%20day.jpg"];"#));
+}
+
+#[tokio::test]
+async fn contact_page_renders_template() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/contact/", "Contact").await;
+    assert!(main_content(&body).contains("Fixture Contact"));
+}
+
+#[tokio::test]
+async fn contact_form_queues_message_and_escapes_name() {
+    let app = TestApp::new();
+    let (status, _, body) = app
+        .post_form("/contact/", "name=%3Cb%3EAmy%3C%2Fb%3E&email=amy%40example.com&subject=Hello&message=Hi+there")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Message Sent Successfully!"));
+    assert!(body.contains("Thank you &lt;b&gt;Amy&lt;/b&gt;"));
+
+    let messages = app.finish().await;
+    assert!(messages.contains("From: <b>Amy</b> <amy@example.com>"));
+    assert!(messages.contains("Subject: Hello"));
+    assert!(messages.contains("Message:\nHi there"));
+}
+
+#[tokio::test]
+async fn contact_form_without_email_is_anonymous() {
+    let app = TestApp::new();
+    let (status, _, _) = app.post_form("/contact/", "name=Sam&subject=Hi&message=Hello").await;
+    assert_eq!(status, StatusCode::OK);
+    let messages = app.finish().await;
+    assert!(messages.contains("From: Sam <Anonymous>"));
+}
+
+#[tokio::test]
+async fn contact_form_honeypot_drops_message() {
+    let app = TestApp::new();
+    let (status, _, body) = app.post_form("/contact/", "name=Bot&subject=Hi&message=Buy+now&website=http%3A%2F%2Fspam.example").await;
+    // Bots are told it worked
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Message Sent Successfully!"));
+
+    let (_, _, metrics) = app.get("/metrics").await;
+    assert!(metrics.contains(r#"form_spam_rejections_total{reason="honeypot"} 1"#));
+    assert!(metrics.contains("contact_submissions_total 0"));
+    assert_eq!(app.finish().await, "");
+}
+
+#[tokio::test]
+async fn forms_are_rate_limited() {
+    let app = TestApp::new();
+    for _ in 0..3 {
+        let (status, _, _) = app.post_form("/contact/", "name=Sam&subject=Hi&message=Hello").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    for _ in 0..2 {
+        let (status, _, _) = app.post_form("/reviews/", "author=Sam&quote=Lovely").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    // The limit is shared by both forms
+    let (status, headers, _) = app.post_form("/reviews/", "author=Sam&quote=Lovely").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(headers[header::RETRY_AFTER], "600");
+    let (status, _, _) = app.post_form("/contact/", "name=Sam&subject=Hi&message=Hello").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+    let (_, _, metrics) = app.get("/metrics").await;
+    assert!(metrics.contains(r#"form_spam_rejections_total{reason="rate_limit"} 2"#));
+    assert!(metrics.contains("review_submissions_total 2"));
+}
+
+#[tokio::test]
+async fn reviews_page_has_review_form() {
+    let app = TestApp::new();
+    let body = get_page(&app, "/reviews/", "Reviews").await;
+    let content = main_content(&body);
+    assert!(content.contains(r#"<form class="review-form" method="post" action="/reviews/">"#));
+    assert!(content.contains(r#"<input type="text" id="review-website" name="website" tabindex="-1" autocomplete="off">"#));
+    assert!(!content.contains("{{REVIEW_FORM}}"));
+}
+
+#[tokio::test]
+async fn review_form_queues_reviews_for_moderation() {
+    let app = TestApp::new();
+    let (status, _, body) = app
+        .post_form("/reviews/", "author=%3Cb%3ESam%3C%2Fb%3E&title=Director&rating=4&quote=Great+energy&website=")
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Thank You for Your Review!"));
+
+    let pending = moderation::read_pending(&app.state.config.pending_reviews_file).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].author, "<b>Sam</b>");
+    assert_eq!(pending[0].title, "Director");
+    assert_eq!(pending[0].rating, Some(4));
+    // Not shown before it is approved
+    let body = get_page(&app, "/reviews/", "Reviews").await;
+    assert!(!body.contains("Great energy"));
+
+    for (form, message) in [
+        ("author=Sam&quote=+", "Please enter your name and your review."),
+        ("author=Sam&quote=Hi&rating=9", "Ratings go from 1 to 5 stars."),
+        ("author=Sam&quote=Hi&rating=lots", "Ratings go from 1 to 5 stars."),
+    ] {
+        let (status, _, body) = app.post_form("/reviews/", form).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", form);
+        assert!(body.contains(message), "{}", form);
+    }
+
+    let (status, _, _) = app.post_form("/reviews/", "author=Bot&quote=Spam&website=x").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(moderation::read_pending(&app.state.config.pending_reviews_file).unwrap().len(), 1);
+}
+
+/// `Authorization` header value for the admin password.
+fn basic_auth(password: &str) -> String {
+    use base64::Engine;
+    format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(format!("admin:{}", password)))
+}
+
+async fn admin_request(app: &TestApp, method: &str, uri: &str, password: &str) -> (StatusCode, HeaderMap, String) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, basic_auth(password))
+        .body(Body::empty())
+        .unwrap();
+    app.request(request).await
+}
+
+#[tokio::test]
+async fn moderation_is_off_without_a_password() {
+    let app = TestApp::new();
+    let (status, _, _) = admin_request(&app, "GET", "/admin/reviews/", "").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn reviews_are_approved_or_rejected_from_the_admin_page() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let app = TestApp::configured(content.path().to_path_buf(), |config| config.admin_password = Some("s3cret".to_string()));
+    app.post_form("/reviews/", "author=Sam&quote=Great+energy").await;
+    app.post_form("/reviews/", "author=Bot&quote=Buy+now").await;
+    let pending = moderation::read_pending(&app.state.config.pending_reviews_file).unwrap();
+
+    let (status, headers, _) = app.get("/admin/reviews/").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert!(headers[header::WWW_AUTHENTICATE].to_str().unwrap().starts_with("Basic "));
+    for wrong in ["wrong", "s3cre", "s3cret!"] {
+        let (status, _, _) = admin_request(&app, "GET", "/admin/reviews/", wrong).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    let (status, _, body) = admin_request(&app, "GET", "/admin/reviews/", "s3cret").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains(r#"<meta name="robots" content="noindex">"#));
+    assert!(body.contains("2 pending"));
+    assert!(body.contains(&format!(r#"action="/admin/reviews/{}/approve""#, pending[0].id)));
+
+    // Actions need the password too
+    let (status, _, _) = app.post_form(&format!("/admin/reviews/{}/approve", pending[0].id), "").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, headers, _) = admin_request(&app, "POST", &format!("/admin/reviews/{}/approve", pending[0].id), "s3cret").await;
+    assert_eq!(status, StatusCode::SEE_OTHER);
+    assert_eq!(headers[header::LOCATION], "/admin/reviews/");
+    let (status, _, _) = admin_request(&app, "POST", &format!("/admin/reviews/{}/reject", pending[1].id), "s3cret").await;
+    assert_eq!(status, StatusCode::SEE_OTHER);
+
+    let (_, _, body) = admin_request(&app, "GET", "/admin/reviews/", "s3cret").await;
+    assert!(body.contains("No reviews are waiting for moderation."));
+    let body = get_page(&app, "/reviews/", "Reviews").await;
+    assert!(body.contains("Great energy"));
+    assert!(!body.contains("Buy now"));
+}
+
+#[tokio::test]
+async fn missing_trailing_slash_redirects() {
+    let app = TestApp::new();
+    let (status, headers, _) = app.get("/bio?ref=nav").await;
+    assert_eq!(status, StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(headers[header::LOCATION], "/bio/?ref=nav");
+
+    let (status, headers, _) = app.get("/behind-the-scenes").await;
+    assert_eq!(status, StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(headers[header::LOCATION], "/behind-the-scenes/");
+
+    for missing in ["/modeling/nope", "/music/releases/nope", "/news/page/9", "/news/tags/nope", "/news/page", "/news/tags"] {
+        let (status, _, _) = app.get(missing).await;
+        assert_eq!(status, StatusCode::NOT_FOUND, "GET {}", missing);
+    }
+    let (status, headers, _) = app.get("/news/tags/tour").await;
+    assert_eq!(status, StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(headers[header::LOCATION], "/news/tags/tour/");
+
+    for page in ["/shop", "/cart"] {
+        let (status, headers, _) = app.get(page).await;
+        assert_eq!(status, StatusCode::PERMANENT_REDIRECT, "GET {}", page);
+        assert_eq!(headers[header::LOCATION], format!("{}/", page));
+    }
+}
+
+#[tokio::test]
+async fn unknown_route_renders_branded_404() {
+    let app = TestApp::new();
+    let (status, _, body) = app.get("/no-such-page/").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert!(body.contains("<title>404 - Page Not Found - 4AmberTechel</title>"));
+    assert!(body.contains(r#"<span class="error-code">404</span>"#));
+    assert!(body.contains(NAV_LINKS[0]));
+}
+
+#[tokio::test]
+async fn pages_are_unavailable_until_site_model_loads() {
+    let app = TestApp::unloaded();
+    let (status, _, _) = app.get("/bio/").await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    let (status, _, body) = app.get("/readyz").await;
+    assert_eq!((status, body.as_str()), (StatusCode::SERVICE_UNAVAILABLE, "loading"));
+    let (status, _, body) = app.get("/healthz").await;
+    assert_eq!((status, body.as_str()), (StatusCode::OK, "ok"));
+}
+
+#[tokio::test]
+async fn readiness_reports_loaded_site() {
+    let app = TestApp::new();
+    let (status, _, body) = app.get("/readyz").await;
+    assert_eq!((status, body.as_str()), (StatusCode::OK, "ready"));
+}
+
+#[tokio::test]
+async fn responses_carry_request_id() {
+    let app = TestApp::new();
+    let (_, headers, _) = app.get("/").await;
+    assert!(headers.contains_key("x-request-id"));
+}
+
+#[tokio::test]
+async fn metrics_count_requests_and_contact_submissions() {
+    let app = TestApp::new();
+    app.get("/bio/").await;
+    app.get("/templates/global-images/1.png").await;
+    app.post_form("/contact/", "name=Sam&subject=Hi&message=Hello").await;
+
+    let (status, _, body) = app.get("/metrics").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains(r#"http_requests_total{route="/bio/",method="GET",status="200"} 1"#));
+    assert!(body.contains(r#"http_request_duration_seconds_count{route="/bio/"} 1"#));
+    assert!(body.contains("contact_submissions_total 1"));
+    assert!(body.contains("image_cache_misses_total 1"));
+}
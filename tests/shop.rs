@@ -0,0 +1,340 @@
+mod common;
+
+use axum::body::Body;
+use axum::http::{Request, StatusCode, header};
+use chrono::{TimeZone, Utc};
+use common::{TestApp, build_options, copy_fixture, fixture_dir, main_content};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+use website_test::orders::{self, CheckoutError, FakePayments, Order, Orders, PaymentProvider};
+use website_test::render::{self, ShopActions};
+use website_test::shop::{self, Cart, Catalog, ItemKey, Price, Product, ProductKind, Variant};
+use website_test::server::{Carts, MAX_CARTS};
+use website_test::static_site;
+
+fn key(product: &str, variant: &str) -> ItemKey {
+    ItemKey { product: product.to_string(), variant: variant.to_string() }
+}
+
+const CUSTOMER: (&str, &str, &str) = ("Jo Fan", "jo@example.com", "1 Main St\nSpringfield");
+
+#[test]
+fn catalog_reads_prices_variants_and_stock() {
+    let catalog = shop::read_catalog(&fixture_dir()).unwrap();
+    assert_eq!(catalog.currency, "USD");
+    let slugs: Vec<&str> = catalog.products.iter().map(|product| product.slug.as_str()).collect();
+    assert_eq!(slugs, ["signed-headshot", "tour-t-shirt", "album"]);
+    assert_eq!(catalog.products[0].kind, ProductKind::SignedPhoto);
+
+    let xl = catalog.item(&key("tour-t-shirt", "xl")).unwrap();
+    assert_eq!((xl.name(), xl.price(), xl.stock()), ("Tour T-Shirt (XL)".to_string(), Price(2250), Some(0)));
+    assert_eq!(catalog.item(&key("tour-t-shirt", "small")).unwrap().price(), Price(2000), "variants default to the product's price");
+    assert!(catalog.item(&key("tour-t-shirt", "")).is_none(), "products with variants are bought as one of them");
+    assert_eq!(catalog.item(&key("album", "")).unwrap().available(&HashMap::new()), None);
+
+    assert_eq!(Price::parse("12.5"), Some(Price(1250)));
+    assert_eq!(Price::parse("12.345"), None);
+    assert_eq!(Price(2250).display("USD"), "$22.50");
+    assert_eq!(Price(2250).display("CHF"), "22.50 CHF");
+
+    let sold = HashMap::from([(key("signed-headshot", ""), 1)]);
+    assert_eq!(shop::check_quantity(&catalog, &key("signed-headshot", ""), 2, &sold).unwrap_err(), "Only 1 of Signed Headshot left.");
+    assert_eq!(shop::check_quantity(&catalog, &key("tour-t-shirt", "xl"), 1, &sold).unwrap_err(), "Tour T-Shirt (XL) is sold out.");
+    assert_eq!(shop::check_quantity(&catalog, &key("album", ""), 21, &sold).unwrap_err(), "You can order at most 20 of Debut Album.");
+    assert!(shop::check_quantity(&catalog, &key("poster", ""), 1, &sold).is_err());
+}
+
+#[test]
+fn broken_catalogs_are_errors() {
+    for (yaml, error) in [
+        ("products:\n  - name: A\n    price: 1\n  - name: a\n    price: 2\n", "duplicate product slug \"a\""),
+        ("products:\n  - name: A\n", "\"A\" has no price"),
+        ("products:\n  - name: A\n    variants:\n      - name: S\n        price: 1\n      - name: M\n", "\"A\" has no price"),
+        ("products:\n  - name: A\n    price: 1\n    stock: 2\n    variants:\n      - name: S\n", "\"A\" has variants"),
+        ("products:\n  - name: A\n    price: 1.999\n", "invalid price \"1.999\""),
+        ("currency: dollars\n", "invalid currency \"dollars\""),
+    ] {
+        let content = tempfile::tempdir().unwrap();
+        fs::create_dir(content.path().join("shop")).unwrap();
+        fs::write(content.path().join(shop::CATALOG_PATH), yaml).unwrap();
+        let e = shop::read_catalog(content.path()).unwrap_err();
+        assert!(e.contains(error), "{}: {}", yaml, e);
+    }
+    assert!(shop::read_catalog(tempfile::tempdir().unwrap().path()).unwrap().products.is_empty());
+}
+
+#[test]
+fn orders_take_payment_and_count_against_stock() {
+    let catalog = shop::read_catalog(&fixture_dir()).unwrap();
+    let scratch = tempfile::tempdir().unwrap();
+    let path = scratch.path().join("orders.yaml");
+    let orders = Orders::new(&path);
+    let placed = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+    let mut cart = Cart::default();
+    cart.set(key("signed-headshot", ""), 2);
+    cart.set(key("tour-t-shirt", "small"), 1);
+
+    let declined = orders.place(&catalog, &cart, CUSTOMER, &FakePayments { decline: true }, placed);
+    assert!(matches!(declined, Err(CheckoutError::Rejected(message)) if message.contains("declined")));
+    assert!(!path.exists(), "declined orders are not recorded");
+
+    let order = orders.place(&catalog, &cart, CUSTOMER, &FakePayments::default(), placed).unwrap();
+    assert_eq!(order.id, "20250301-120000");
+    assert_eq!((order.total, order.provider.as_str(), order.payment.as_str()), (7000, "fake", "fake-20250301-120000"));
+    assert_eq!(orders::read_orders(&path).unwrap(), [order]);
+    assert_eq!(orders.read_sold().unwrap()[&key("signed-headshot", "")], 2);
+
+    let mut again = Cart::default();
+    again.set(key("signed-headshot", ""), 1);
+    let sold_out = orders.place(&catalog, &again, CUSTOMER, &FakePayments::default(), placed);
+    assert_eq!(sold_out, Err(CheckoutError::Rejected("Signed Headshot is sold out.".to_string())));
+
+    again.set(key("signed-headshot", ""), 0);
+    again.set(key("album", ""), 1);
+    let second = orders.place(&catalog, &again, CUSTOMER, &FakePayments::default(), placed).unwrap();
+    assert_eq!(second.id, "20250301-120000-2");
+
+    let empty = orders.place(&catalog, &Cart::default(), CUSTOMER, &FakePayments::default(), placed);
+    assert_eq!(empty, Err(CheckoutError::Rejected("Your cart is empty.".to_string())));
+    let anonymous = orders.place(&catalog, &again, ("", "nobody", ""), &FakePayments::default(), placed);
+    assert!(matches!(anonymous, Err(CheckoutError::Rejected(_))));
+}
+
+/// Tries to buy the last signed headshots while its own payment is being
+/// taken, as a second visitor checking out at the same moment would.
+struct RacingPayments<'a> {
+    orders: &'a Orders,
+    catalog: &'a Catalog,
+    other: Mutex<Option<Result<Order, CheckoutError>>>,
+}
+
+impl PaymentProvider for RacingPayments<'_> {
+    fn name(&self) -> &str {
+        "racing"
+    }
+
+    fn charge(&self, order: &Order) -> Result<String, String> {
+        let mut cart = Cart::default();
+        cart.set(key("signed-headshot", ""), 1);
+        let placed = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 1).unwrap();
+        let other = self.orders.place(self.catalog, &cart, CUSTOMER, &FakePayments::default(), placed);
+        *self.other.lock().unwrap() = Some(other);
+        Ok(format!("racing-{}", order.id))
+    }
+}
+
+#[test]
+fn stock_is_set_aside_while_payment_is_taken() {
+    let catalog = shop::read_catalog(&fixture_dir()).unwrap();
+    let scratch = tempfile::tempdir().unwrap();
+    let path = scratch.path().join("orders.yaml");
+    let orders = Orders::new(&path);
+    let mut cart = Cart::default();
+    cart.set(key("signed-headshot", ""), 2);
+
+    // The second checkout runs inside the first one's payment, so this
+    // would deadlock if the orders lock were held around it
+    let payments = RacingPayments { orders: &orders, catalog: &catalog, other: Mutex::new(None) };
+    let order = orders.place(&catalog, &cart, CUSTOMER, &payments, Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap()).unwrap();
+    assert_eq!(payments.other.into_inner().unwrap(), Some(Err(CheckoutError::Rejected("Signed Headshot is sold out.".to_string()))));
+    assert_eq!(orders::read_orders(&path).unwrap(), [order]);
+}
+
+async fn post_with_cookie(app: &TestApp, uri: &str, cookie: &str, form: &str) -> (StatusCode, axum::http::HeaderMap, String) {
+    let request = Request::post(uri)
+        .header("content-type", "application/x-www-form-urlencoded")
+        .header(header::COOKIE, cookie)
+        .body(Body::from(form.to_string()))
+        .unwrap();
+    app.request(request).await
+}
+
+async fn get_with_cookie(app: &TestApp, uri: &str, cookie: &str) -> String {
+    app.request(Request::get(uri).header(header::COOKIE, cookie).body(Body::empty()).unwrap()).await.2
+}
+
+#[tokio::test]
+async fn shop_page_lists_products_with_add_to_cart_forms() {
+    let app = TestApp::new();
+    let (status, _, body) = app.get("/shop/").await;
+    assert_eq!(status, StatusCode::OK);
+    let main = main_content(&body);
+    assert!(main.contains(r#"<img src="/templates/global-images/1.png" alt="Signed Headshot" class="product-image" loading="lazy">"#));
+    assert!(main.contains("An 8x10 headshot &lt;signed&gt; by hand."));
+    assert!(main.contains(r#"<p class="product-price">From $20.00</p>"#));
+    assert!(main.contains(r#"<option value="xl" disabled>XL - $22.50 (sold out)</option>"#));
+    assert!(main.contains(r#"<form class="product-form" method="post" action="/cart/add">"#));
+    assert!(body.contains(r#"<a href="/cart/" class="cart-indicator">CART (<span class="cart-count" data-count-url="/cart/count">0</span>)</a>"#));
+}
+
+#[tokio::test]
+async fn carts_are_kept_per_session_and_checked_out() {
+    let app = TestApp::new();
+    let (_, _, count) = app.get("/cart/count").await;
+    assert_eq!(count, r#"{"count":0}"#);
+
+    let (status, headers, _) = app.post_form("/cart/add", "product=signed-headshot&quantity=1").await;
+    assert_eq!(status, StatusCode::SEE_OTHER);
+    assert_eq!(headers[header::LOCATION], "/cart/");
+    let set_cookie = headers[header::SET_COOKIE].to_str().unwrap();
+    assert!(set_cookie.contains("HttpOnly") && set_cookie.contains("SameSite=Lax"), "{}", set_cookie);
+    let cookie = set_cookie.split(';').next().unwrap().to_string();
+
+    let (status, headers, _) = post_with_cookie(&app, "/cart/add", &cookie, "product=tour-t-shirt&variant=small&quantity=2").await;
+    assert_eq!(status, StatusCode::SEE_OTHER);
+    assert!(!headers.contains_key(header::SET_COOKIE), "the session is kept");
+    assert_eq!(get_with_cookie(&app, "/cart/count", &cookie).await, r#"{"count":3}"#);
+    assert_eq!(app.get("/cart/count").await.2, r#"{"count":0}"#, "other visitors have their own carts");
+
+    // Adding more than is left, or a sold out variant, leaves the cart alone
+    let (status, _, body) = post_with_cookie(&app, "/cart/add", &cookie, "product=signed-headshot&quantity=2").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("Only 2 of Signed Headshot left."));
+    let (status, _, _) = post_with_cookie(&app, "/cart/add", &cookie, "product=tour-t-shirt&variant=xl").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let cart = get_with_cookie(&app, "/cart/", &cookie).await;
+    assert!(cart.contains("<td>Tour T-Shirt (Small)</td>"));
+    assert!(cart.contains(r#"<tr><th colspan="3">Total</th><td>$65.00</td></tr>"#));
+    assert!(cart.contains(r#"<meta name="robots" content="noindex">"#));
+
+    let (status, _, _) = post_with_cookie(&app, "/cart/update", &cookie, "product=tour-t-shirt&variant=small&quantity=0").await;
+    assert_eq!(status, StatusCode::SEE_OTHER);
+    assert_eq!(get_with_cookie(&app, "/cart/count", &cookie).await, r#"{"count":1}"#);
+
+    let (status, _, body) = post_with_cookie(&app, "/cart/checkout", &cookie, "name=Jo+Fan&email=jo%40example.com&address=1+Main+St").await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("Thank You for Your Order!") && body.contains("$25.00 has been charged"), "{}", body);
+    assert_eq!(get_with_cookie(&app, "/cart/count", &cookie).await, r#"{"count":0}"#, "the cart is emptied");
+
+    let orders = orders::read_orders(&app.state.config.orders_file).unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!((orders[0].name.as_str(), orders[0].items[0].name.as_str(), orders[0].provider.as_str()), ("Jo Fan", "Signed Headshot", "fake"));
+    assert!(app.get("/metrics").await.2.contains("orders_placed_total 1"));
+
+    // What was ordered is no longer available to others
+    let (_, _, body) = app.post_form("/cart/add", "product=signed-headshot&quantity=2").await;
+    assert!(body.contains("Only 1 of Signed Headshot left."), "{}", body);
+}
+
+#[tokio::test]
+async fn only_sessions_the_server_started_have_carts() {
+    let app = TestApp::new();
+    for n in 0..100 {
+        let cookie = format!("cart=forged{}", n);
+        let (status, _, _) = post_with_cookie(&app, "/cart/update", &cookie, "product=album&quantity=1").await;
+        assert_eq!(status, StatusCode::SEE_OTHER);
+        assert_eq!(get_with_cookie(&app, "/cart/count", &cookie).await, r#"{"count":0}"#);
+    }
+    assert!(app.state.carts.is_empty(), "unknown sessions do not add carts");
+
+    // Adding to a cart under an unknown session starts a new one
+    let (_, headers, _) = post_with_cookie(&app, "/cart/add", "cart=forged", "product=album").await;
+    let cookie = headers[header::SET_COOKIE].to_str().unwrap().split(';').next().unwrap().to_string();
+    assert_ne!(cookie, "cart=forged");
+    assert_eq!(get_with_cookie(&app, "/cart/count", &cookie).await, r#"{"count":1}"#);
+    assert_eq!(app.state.carts.len(), 1);
+}
+
+#[test]
+fn carts_are_capped_dropping_the_oldest() {
+    let carts = Carts::default();
+    let mut cart = Cart::default();
+    cart.set(key("album", ""), 1);
+    let first = carts.create(cart.clone());
+    let second = carts.create(cart.clone());
+    std::thread::sleep(std::time::Duration::from_millis(1));
+    assert_eq!(carts.update(&first, |cart| cart.count()), Some(1), "changing a cart makes it the newest");
+    for _ in 2..MAX_CARTS {
+        carts.create(Cart::default());
+    }
+    assert_eq!(carts.len(), MAX_CARTS);
+
+    carts.create(Cart::default());
+    assert_eq!(carts.len(), MAX_CARTS);
+    assert_eq!(carts.get(&second).count(), 0, "the oldest cart is dropped");
+    assert_eq!(carts.get(&first).count(), 1);
+    assert_eq!(carts.update("unknown", |cart| cart.set(key("album", ""), 1)), None);
+    assert_eq!(carts.len(), MAX_CARTS);
+}
+
+#[tokio::test]
+async fn declined_payments_keep_the_cart() {
+    let app = TestApp::new();
+    let app = TestApp { router: website_test::server::router(app.state.clone().with_payments(FakePayments { decline: true })), ..app };
+    let (_, headers, _) = app.post_form("/cart/add", "product=album").await;
+    let cookie = headers[header::SET_COOKIE].to_str().unwrap().split(';').next().unwrap().to_string();
+
+    let (status, _, body) = post_with_cookie(&app, "/cart/checkout", &cookie, "name=Jo&email=jo%40example.com&address=Here").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.contains("Your payment was declined"));
+    assert_eq!(get_with_cookie(&app, "/cart/count", &cookie).await, r#"{"count":1}"#);
+    assert!(orders::read_orders(&app.state.config.orders_file).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn checkout_is_rate_limited() {
+    let app = TestApp::new();
+    for _ in 0..5 {
+        let (status, _, _) = app.post_form("/cart/checkout", "name=Jo&email=jo%40example.com&address=Here").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "an empty cart");
+    }
+    let (status, headers, _) = app.post_form("/cart/checkout", "name=Jo&email=jo%40example.com&address=Here").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(headers[header::RETRY_AFTER], "600");
+    let (status, _, _) = app.post_form("/contact/", "name=Sam&subject=Hi&message=Hello").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS, "the limit is shared with the other forms");
+
+    let (_, _, metrics) = app.get("/metrics").await;
+    assert!(metrics.contains(r#"form_spam_rejections_total{reason="rate_limit"} 2"#), "{}", metrics);
+}
+
+#[test]
+fn static_shop_links_to_the_shop_server_or_disables_the_cart() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+
+    let out = tempfile::tempdir().unwrap();
+    assert!(static_site::build(&build_options(content.path(), out.path())).is_publishable());
+    let shop = fs::read_to_string(out.path().join("shop/index.html")).unwrap();
+    assert!(shop.contains(r#"<button type="button" class="product-add" disabled>Not Available Online</button>"#));
+    assert!(shop.contains(r#"<img src="/Website-test/global-images/1.png""#));
+    assert!(!shop.contains("cart-indicator") && !shop.contains("/cart/"), "without a shop server there is no cart");
+
+    let mut settings = fs::read_to_string(content.path().join("site.yaml")).unwrap();
+    settings.push_str("shop_url: https://shop.example.com/\n");
+    fs::write(content.path().join("site.yaml"), settings).unwrap();
+    assert!(static_site::build(&build_options(content.path(), out.path())).is_publishable());
+    let shop = fs::read_to_string(out.path().join("shop/index.html")).unwrap();
+    assert!(shop.contains(r##"<a href="https://shop.example.com/shop/#signed-headshot" class="product-add">Buy in the Shop</a>"##));
+    assert!(shop.contains(r#"<a href="https://shop.example.com/cart/" class="cart-indicator">CART</a>"#));
+    assert!(!shop.contains("product-form"), "carts are only kept by the shop server");
+}
+
+#[test]
+fn slugs_are_escaped_in_attributes() {
+    let slug = r#"x"><script>"#;
+    let product = Product {
+        name: "Tee".to_string(),
+        slug: slug.to_string(),
+        kind: ProductKind::Merch,
+        description: None,
+        image: None,
+        price: Some(Price(1000)),
+        stock: None,
+        variants: vec![Variant { name: "Large".to_string(), slug: slug.to_string(), price: None, stock: None }],
+    };
+    let catalog = Catalog { currency: "USD".to_string(), products: vec![product] };
+    let mut cart = Cart::default();
+    cart.set(key(slug, slug), 1);
+
+    for html in [
+        render::generate_shop_html(&catalog, &HashMap::new(), ShopActions::Cart("/cart/add"), "/templates"),
+        render::generate_shop_html(&catalog, &HashMap::new(), ShopActions::Link("https://shop.example.com/shop/"), "/templates"),
+        render::generate_cart_html(&cart, &catalog, "/cart", "/shop/"),
+    ] {
+        assert!(!html.contains("<script>"), "{}", html);
+        assert!(html.contains("x&quot;&gt;&lt;script&gt;"), "{}", html);
+    }
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use chrono::{DateTime, TimeZone, Utc};
+use common::{copy_fixture, fixture_dir};
+use std::fs::File;
+use std::path::Path;
+use website_test::feeds::SiteUrls;
+use website_test::sitemap;
+
+fn utc(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+}
+
+fn touch(path: &Path, time: DateTime<Utc>) {
+    File::options().write(true).open(path).unwrap().set_modified(time.into()).unwrap();
+}
+
+fn routes(pages: &[sitemap::SitemapUrl]) -> Vec<&str> {
+    pages.iter().map(|page| page.route.as_str()).collect()
+}
+
+#[test]
+fn sitemap_lists_every_page() {
+    let urls = SiteUrls::for_static_site("https://fixture.example/site");
+    let pages = sitemap::collect_urls(&fixture_dir(), &urls, &[]).unwrap();
+    assert_eq!(
+        routes(&pages),
+        [
+            "/",
+            "/contact/",
+            "/modeling/",
+            "/bio/",
+            "/music/",
+            "/acting/",
+            "/reviews/",
+            "/events/",
+            "/news/",
+            "/behind-the-scenes/",
+            "/shop/",
+            "/modeling/glamour/",
+            "/music/releases/night-day/",
+            "/music/releases/first-steps/",
+            "/news/summer-tour/",
+            "/news/album-launch/",
+            "/news/tags/music/",
+            "/news/tags/tour/",
+            "/news/tags/announcements/",
+        ]
+    );
+
+    let glamour = pages.iter().find(|page| page.route == "/modeling/glamour/").unwrap();
+    assert_eq!(
+        glamour.images,
+        [
+            "https://fixture.example/site/modeling/Glamour/images/a.png",
+            "https://fixture.example/site/modeling/Glamour/images/b%20shot.jpg",
+        ]
+    );
+    let bts = pages.iter().find(|page| page.route == "/behind-the-scenes/").unwrap();
+    assert_eq!(bts.images, ["https://fixture.example/site/behind-the-scenes/images/set%20day.jpg"]);
+    let post = pages.iter().find(|page| page.route == "/news/album-launch/").unwrap();
+    assert_eq!(post.images, ["https://fixture.example/site/global-images/1.png"]);
+}
+
+#[test]
+fn lastmod_comes_from_the_newest_source_file() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    for path in ["modeling/modeling.html", "modeling/Glamour/images/a.png", "modeling/Glamour/images/b shot.jpg", "reviews/reviews.html"] {
+        touch(&content.path().join(path), utc(2020, 1, 1));
+    }
+    touch(&content.path().join("modeling/Glamour/images/b shot.jpg"), utc(2023, 5, 1));
+    touch(&content.path().join("reviews/reviews.yaml"), utc(2024, 2, 3));
+    touch(&content.path().join("posts/summer-tour.md"), utc(2024, 6, 11));
+    touch(&content.path().join("posts/album-launch.md"), utc(2024, 3, 2));
+
+    let urls = SiteUrls::for_static_site("https://fixture.example/site");
+    let pages = sitemap::collect_urls(content.path(), &urls, &[]).unwrap();
+    let lastmod = |route: &str| pages.iter().find(|page| page.route == route).unwrap().lastmod;
+
+    assert_eq!(lastmod("/modeling/glamour/"), Some(utc(2023, 5, 1)));
+    assert_eq!(lastmod("/modeling/"), Some(utc(2023, 5, 1)));
+    assert_eq!(lastmod("/reviews/"), Some(utc(2024, 2, 3)));
+    assert_eq!(lastmod("/news/album-launch/"), Some(utc(2024, 3, 2)));
+    assert_eq!(lastmod("/news/tags/music/"), Some(utc(2024, 6, 11)));
+    assert_eq!(lastmod("/news/tags/announcements/"), Some(utc(2024, 3, 2)));
+}
+
+#[test]
+fn disallowed_routes_are_left_out() {
+    let urls = SiteUrls::for_static_site("https://fixture.example/site");
+    let disallow = vec!["/modeling/glamour/".to_string(), "/news/tags/".to_string()];
+    let pages = sitemap::collect_urls(&fixture_dir(), &urls, &disallow).unwrap();
+    let routes = routes(&pages);
+    assert!(routes.contains(&"/modeling/"));
+    assert!(!routes.contains(&"/modeling/glamour/"));
+    assert!(!routes.iter().any(|route| route.starts_with("/news/tags/")));
+    assert!(sitemap::is_disallowed("/modeling/glamour/", &disallow));
+    assert!(!sitemap::is_disallowed("/modeling/", &disallow));
+}
+
+#[test]
+fn sitemap_xml_has_image_entries() {
+    let urls = SiteUrls::for_static_site("https://fixture.example/site");
+    let pages = vec![sitemap::SitemapUrl {
+        route: "/modeling/glamour/".to_string(),
+        lastmod: Some(utc(2024, 2, 3)),
+        images: vec!["https://fixture.example/site/a.png?x=1&y=2".to_string()],
+    }];
+    assert_eq!(
+        sitemap::generate_sitemap(&pages, &urls),
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\" xmlns:image=\"http://www.google.com/schemas/sitemap-image/1.1\">\n  \
+         <url>\n    \
+         <loc>https://fixture.example/site/modeling/glamour/</loc>\n    \
+         <lastmod>2024-02-03T00:00:00Z</lastmod>\n    \
+         <image:image><image:loc>https://fixture.example/site/a.png?x=1&amp;y=2</image:loc></image:image>\n  \
+         </url>\n\
+         </urlset>\n"
+    );
+}
+
+#[test]
+fn robots_txt_points_at_the_sitemap() {
+    let disallow = vec!["/modeling/boudoir/".to_string()];
+    assert_eq!(
+        sitemap::generate_robots(&disallow, "/Website-test", Some("https://fixture.example/site/sitemap.xml")),
+        "User-agent: *\nDisallow: /Website-test/modeling/boudoir/\n\nSitemap: https://fixture.example/site/sitemap.xml\n"
+    );
+    assert_eq!(sitemap::generate_robots(&[], "", None), "User-agent: *\nDisallow:\n");
+}
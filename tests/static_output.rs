@@ -0,0 +1,394 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{build_options, copy_fixture, fixture_dir, main_content};
+use std::fs;
+use std::path::Path;
+use website_test::{output, static_site};
+
+fn build_site(content_dir: &Path, out_dir: &Path) {
+    let report = static_site::build(&build_options(content_dir, out_dir));
+    assert!(report.is_publishable(), "build failed: {:?}", report.errors);
+}
+
+fn build_fixture_site(out_dir: &Path) {
+    build_site(&fixture_dir(), out_dir);
+}
+
+fn list_files(root: &Path) -> Vec<String> {
+    fn walk(root: &Path, dir: &Path, files: &mut Vec<String>) {
+        for entry in fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(root, &path, files);
+            } else {
+                files.push(path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    walk(root, root, &mut files);
+    files.sort();
+    files
+}
+
+const PAGES: &[&str] = &[
+    "index.html",
+    "404.html",
+    "bio/index.html",
+    "music/index.html",
+    "music/releases/night-day/index.html",
+    "acting/index.html",
+    "modeling/index.html",
+    "modeling/glamour/index.html",
+    "reviews/index.html",
+    "events/index.html",
+    "news/index.html",
+    "news/album-launch/index.html",
+    "news/tags/music/index.html",
+    "behind-the-scenes/index.html",
+    "contact/index.html",
+];
+
+#[test]
+fn static_build_writes_expected_files() {
+    let out = tempfile::tempdir().unwrap();
+    build_fixture_site(out.path());
+    insta::assert_snapshot!("static_files", list_files(out.path()).join("\n"));
+}
+
+#[test]
+fn static_pages_match_snapshots() {
+    let out = tempfile::tempdir().unwrap();
+    build_fixture_site(out.path());
+
+    for page in PAGES {
+        let html = fs::read_to_string(out.path().join(page)).unwrap();
+        let name = format!("page_{}", page.replace(['/', '.'], "_"));
+        insta::assert_snapshot!(name, main_content(&html));
+    }
+}
+
+#[test]
+fn static_pages_use_github_pages_urls() {
+    let out = tempfile::tempdir().unwrap();
+    build_fixture_site(out.path());
+
+    for page in PAGES {
+        let html = fs::read_to_string(out.path().join(page)).unwrap();
+        assert!(
+            html.contains(r#"<a href="/Website-test/bio/index.html" class="nav-item">Bio</a>"#),
+            "{} has rewritten nav",
+            page
+        );
+        assert!(html.contains(r#"<link rel="stylesheet" href="/Website-test/css/shared."#), "{} links the shared styles", page);
+        assert!(!html.contains("<style>"), "{} has no inline styles", page);
+        assert!(html.contains(r#"href="/Website-test/feed.xml""#), "{} links the feed", page);
+        assert!(!html.contains(r#"href="/bio/""#), "{} has no server-only nav links", page);
+    }
+
+    let not_found = fs::read_to_string(out.path().join("404.html")).unwrap();
+    assert!(not_found.contains(r#"<a href="/Website-test/index.html" class="error-home-link">"#));
+}
+
+#[test]
+fn page_styles_are_bundled_with_absolute_urls() {
+    let out = tempfile::tempdir().unwrap();
+    build_fixture_site(out.path());
+
+    let bio = fs::read_to_string(out.path().join("bio/index.html")).unwrap();
+    let prefix = r#"<link rel="stylesheet" href="/Website-test/css/page."#;
+    let start = bio.find(prefix).expect("bio links its own bundle") + prefix.len() - "page.".len();
+    let name = &bio[start..start + bio[start..].find('"').unwrap()];
+    let css = fs::read_to_string(out.path().join("css").join(name)).unwrap();
+    assert_eq!(css, ".main-content{background-image:url('/Website-test/bio/background/bkgrnd.png')}");
+}
+
+#[test]
+fn static_pages_have_canonical_urls_and_structured_data() {
+    let out = tempfile::tempdir().unwrap();
+    build_fixture_site(out.path());
+
+    for page in PAGES.iter().filter(|page| **page != "404.html") {
+        let html = fs::read_to_string(out.path().join(page)).unwrap();
+        assert!(html.contains(r#"<link rel="canonical" href="https://fixture.example/site/"#), "{} has a canonical URL", page);
+        assert!(html.contains(r#"<meta property="og:title""#), "{} has Open Graph tags", page);
+    }
+
+    let release = fs::read_to_string(out.path().join("music/releases/night-day/index.html")).unwrap();
+    assert!(release.contains(r#"<meta property="og:type" content="music.album">"#));
+    assert!(release.contains(r#""@type":"MusicAlbum""#));
+    let reviews = fs::read_to_string(out.path().join("reviews/index.html")).unwrap();
+    assert_eq!(reviews.matches(r#""@type":"Review""#).count(), 2);
+    let not_found = fs::read_to_string(out.path().join("404.html")).unwrap();
+    assert!(not_found.contains(r#"<meta name="robots" content="noindex">"#));
+}
+
+#[test]
+fn static_build_replaces_previous_output() {
+    let out = tempfile::tempdir().unwrap();
+    fs::write(out.path().join("stale.html"), "old").unwrap();
+    build_fixture_site(out.path());
+    assert!(!out.path().join("stale.html").exists());
+}
+
+#[test]
+fn acting_resume_pdf_is_built() {
+    let out = tempfile::tempdir().unwrap();
+    build_fixture_site(out.path());
+    assert!(fs::read(out.path().join("acting/resume.pdf")).unwrap().starts_with(b"%PDF"));
+    let acting = fs::read_to_string(out.path().join("acting/index.html")).unwrap();
+    assert!(acting.contains(r#"<a href="/Website-test/acting/resume.pdf" class="resume-download" download>"#));
+}
+
+#[test]
+fn acting_page_has_no_resume_without_credits() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    fs::remove_file(content.path().join("acting/credits.yaml")).unwrap();
+    let out = tempfile::tempdir().unwrap();
+    build_site(content.path(), out.path());
+    assert!(!out.path().join("acting/resume.pdf").exists());
+    let acting = fs::read_to_string(out.path().join("acting/index.html")).unwrap();
+    assert!(!acting.contains("resume.pdf"));
+    assert!(!acting.contains("{{ACTING_RESUME}}"));
+}
+
+#[test]
+fn events_are_archived_by_build_time() {
+    let out = tempfile::tempdir().unwrap();
+    let mut options = build_options(&fixture_dir(), out.path());
+    options.build_time = NaiveDate::from_ymd_opt(2099, 5, 2).unwrap().and_hms_opt(22, 0, 0).unwrap();
+    static_site::build(&options);
+
+    let events = fs::read_to_string(out.path().join("events/index.html")).unwrap();
+    let archive = &events[events.find("events-archive").unwrap()..];
+    assert!(archive.contains("Release Show"));
+    assert!(!archive.contains("Comic Con"));
+    assert!(events.contains(r#"<a href="/Website-test/events.ics" class="events-subscribe">"#));
+
+    let home = fs::read_to_string(out.path().join("index.html")).unwrap();
+    assert!(home.contains(r#"<a href="/Website-test/events/index.html" class="next-event">"#));
+    assert!(home.contains("Comic Con, Day One"));
+
+    let ics = fs::read_to_string(out.path().join("events.ics")).unwrap();
+    assert_eq!(ics.matches("BEGIN:VEVENT").count(), 4);
+}
+
+#[test]
+fn news_drafts_are_only_built_on_request() {
+    let out = tempfile::tempdir().unwrap();
+    build_fixture_site(out.path());
+    assert!(!out.path().join("news/secret-project").exists());
+    assert!(!fs::read_to_string(out.path().join("news/index.html")).unwrap().contains("Secret Project"));
+    assert!(!out.path().join("news/tags/acting").exists());
+    assert!(out.path().join("posts/stage.png").exists());
+
+    let drafts = tempfile::tempdir().unwrap();
+    let mut options = build_options(&fixture_dir(), drafts.path());
+    options.drafts = true;
+    static_site::build(&options);
+    assert!(drafts.path().join("news/secret-project/index.html").exists());
+}
+
+#[test]
+fn feeds_use_the_configured_site_url() {
+    let out = tempfile::tempdir().unwrap();
+    build_fixture_site(out.path());
+    let atom = fs::read_to_string(out.path().join("feed.xml")).unwrap();
+    assert!(atom.contains("<id>https://fixture.example/site/news/album-launch/</id>"));
+    assert!(atom.contains("<id>https://fixture.example/site/modeling/Glamour/images/a.png</id>"));
+    assert!(!atom.contains("secret-project"));
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(out.path().join("feed.json")).unwrap()).unwrap();
+    assert_eq!(json["feed_url"], "https://fixture.example/site/feed.json");
+
+    // Without a site URL there is nothing to make the links absolute with
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    fs::remove_file(content.path().join("site.yaml")).unwrap();
+    let out = tempfile::tempdir().unwrap();
+    build_site(content.path(), out.path());
+    assert!(!out.path().join("feed.xml").exists());
+    assert!(!out.path().join("feed.json").exists());
+    assert!(!out.path().join("sitemap.xml").exists());
+    assert_eq!(fs::read_to_string(out.path().join("robots.txt")).unwrap(), "User-agent: *\nDisallow:\n");
+}
+
+#[test]
+fn sitemap_and_robots_are_built() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    fs::write(
+        content.path().join("site.yaml"),
+        "site_url: https://fixture.example/site\nrobots_disallow: [/news/tags/]\n",
+    )
+    .unwrap();
+    let out = tempfile::tempdir().unwrap();
+    build_site(content.path(), out.path());
+
+    let sitemap = fs::read_to_string(out.path().join("sitemap.xml")).unwrap();
+    assert!(sitemap.contains("<loc>https://fixture.example/site/modeling/glamour/</loc>"));
+    assert!(sitemap.contains("<image:loc>https://fixture.example/site/modeling/Glamour/images/a.png</image:loc>"));
+    assert!(!sitemap.contains("/news/tags/"));
+    let robots = fs::read_to_string(out.path().join("robots.txt")).unwrap();
+    assert_eq!(robots, "User-agent: *\nDisallow: /Website-test/news/tags/\n\nSitemap: https://fixture.example/site/sitemap.xml\n");
+
+    let category = fs::read_to_string(out.path().join("modeling/glamour/index.html")).unwrap();
+    assert!(category.contains(r#""images": ["../Glamour/images/a.png", "../Glamour/images/b%20shot.jpg"]"#));
+}
+
+#[test]
+fn lite_embeds_use_cached_thumbnails_and_placeholder() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    fs::write(content.path().join("site.yaml"), "video_embeds: lite\n").unwrap();
+    let cache = content.path().join(".thumbnail-cache");
+    fs::create_dir_all(&cache).unwrap();
+    fs::write(cache.join("actingCCCC3.jpg"), "cached jpeg").unwrap();
+
+    let out = tempfile::tempdir().unwrap();
+    build_site(content.path(), out.path());
+
+    assert_eq!(fs::read_to_string(out.path().join("thumbnails/actingCCCC3.jpg")).unwrap(), "cached jpeg");
+    assert!(out.path().join("thumbnails/placeholder.svg").exists());
+    assert!(!out.path().join("thumbnails/shortDDDD44.jpg").exists());
+
+    let acting = fs::read_to_string(out.path().join("acting/index.html")).unwrap();
+    assert!(acting.contains(r#"<img src="/Website-test/thumbnails/actingCCCC3.jpg""#));
+    assert!(acting.contains(r#"<img src="/Website-test/thumbnails/placeholder.svg""#));
+    assert!(acting.contains(r#"data-embed="https://www.youtube-nocookie.com/embed/actingCCCC3?start=65&amp;autoplay=1""#));
+    assert!(acting.contains(r#"href="https://www.youtube.com/watch?v=actingCCCC3&amp;t=65s""#));
+    assert!(!acting.contains("<iframe"));
+    assert_eq!(acting.matches("<script>").count(), 1, "facade script is included once");
+}
+
+#[test]
+fn rebuilds_only_touch_changed_files() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let out = tempfile::tempdir().unwrap();
+    let options = build_options(content.path(), out.path());
+
+    let first = static_site::build(&options).stats;
+    assert_eq!((first.unchanged, first.removed), (0, 0));
+    assert!(first.written > 0 && first.copied > 0);
+
+    let second = static_site::build(&options).stats;
+    assert_eq!((second.written, second.copied, second.removed), (0, 0, 0));
+    assert_eq!(second.unchanged, first.written + first.copied);
+
+    // A changed image is copied again and a deleted one removed
+    fs::write(content.path().join("global-images/1.png"), "new").unwrap();
+    fs::remove_file(content.path().join("modeling/Glamour/images/b shot.jpg")).unwrap();
+    let third = static_site::build(&options).stats;
+    assert_eq!((third.copied, third.removed), (1, 1));
+    assert_eq!(fs::read_to_string(out.path().join("global-images/1.png")).unwrap(), "new");
+    assert!(!out.path().join("modeling/Glamour/images/b shot.jpg").exists());
+}
+
+#[test]
+fn noop_rebuilds_write_nothing() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let out = tempfile::tempdir().unwrap();
+    let docs = out.path().join("docs");
+    build_site(content.path(), &docs);
+
+    let modified = |path: &Path| fs::metadata(path).unwrap().modified().unwrap();
+    let stamps = |root: &Path| -> Vec<_> {
+        let mut files = list_files(root);
+        files.push(String::new());
+        files.into_iter().map(|file| (modified(&root.join(&file)), file)).collect()
+    };
+    let before = stamps(&docs);
+    assert!(before.iter().any(|(_, file)| file == ".build-manifest.json"));
+
+    let report = static_site::build(&build_options(content.path(), &docs));
+    assert!(report.is_publishable(), "build failed: {:?}", report.errors);
+    assert_eq!((report.stats.written, report.stats.copied, report.stats.removed), (0, 0, 0));
+    assert_eq!(stamps(&docs), before, "the output directory and its files are left alone");
+    assert_eq!(fs::read_dir(out.path()).unwrap().count(), 1, "staging directory is cleaned up");
+}
+
+#[test]
+fn clean_only_removes_build_output() {
+    let parent = tempfile::tempdir().unwrap();
+    let out = parent.path().join("docs");
+    build_fixture_site(&out);
+    fs::create_dir(parent.path().join(".docs-staging")).unwrap();
+    assert_eq!(output::clean(&out).unwrap(), [out.clone(), parent.path().join(".docs-staging")]);
+    assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 0);
+
+    // A directory no build wrote, e.g. a mistyped --out
+    fs::create_dir(&out).unwrap();
+    fs::write(out.join("notes.txt"), "keep me").unwrap();
+    fs::create_dir(parent.path().join(".docs-previous")).unwrap();
+    let error = output::clean(&out).unwrap_err();
+    assert!(error.contains("refusing to remove") && error.contains(".build-manifest.json"), "{}", error);
+    assert!(out.join("notes.txt").exists() && parent.path().join(".docs-previous").exists());
+}
+
+#[test]
+fn failed_builds_leave_the_previous_output() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let parent = tempfile::tempdir().unwrap();
+    let out = parent.path().join("docs");
+    build_site(content.path(), &out);
+    let home = fs::read_to_string(out.join("index.html")).unwrap();
+
+    fs::write(content.path().join("index.html"), "<h1>Changed</h1>").unwrap();
+    fs::write(content.path().join("reviews/reviews.yaml"), "testimonials: [").unwrap();
+    let report = static_site::build(&build_options(content.path(), &out));
+    assert!(!report.is_publishable());
+    assert!(report.errors.iter().any(|error| error.contains("testimonials")), "{:?}", report.errors);
+    assert_eq!(fs::read_to_string(out.join("index.html")).unwrap(), home);
+    assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 1, "staging directory is cleaned up");
+}
+
+#[test]
+fn interrupted_swaps_are_restored_by_the_next_build() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let parent = tempfile::tempdir().unwrap();
+    let out = parent.path().join("docs");
+    build_site(content.path(), &out);
+    let home = fs::read_to_string(out.join("index.html")).unwrap();
+
+    // As if the build crashed after moving the old output aside
+    fs::rename(&out, parent.path().join(".docs-previous")).unwrap();
+    fs::write(content.path().join("reviews/reviews.yaml"), "testimonials: [").unwrap();
+    let report = static_site::build(&build_options(content.path(), &out));
+    assert!(!report.is_publishable());
+    assert_eq!(fs::read_to_string(out.join("index.html")).unwrap(), home);
+    assert!(!parent.path().join(".docs-previous").exists());
+}
+
+#[test]
+fn broken_media_list_fails_the_build() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    fs::write(content.path().join("acting/media.yaml"), "media: [").unwrap();
+    let out = tempfile::tempdir().unwrap();
+    let report = static_site::build(&build_options(content.path(), out.path()));
+    assert!(!report.is_publishable());
+    assert!(report.errors.iter().any(|error| error.contains("acting media")), "{:?}", report.errors);
+}
+
+#[test]
+fn base_url_option() {
+    let out = tempfile::tempdir().unwrap();
+    let mut options = build_options(&fixture_dir(), out.path());
+    options.base_path = String::new();
+    options.site_url = Some("https://example.com".to_string());
+    assert!(static_site::build(&options).is_publishable());
+
+    let home = fs::read_to_string(out.path().join("index.html")).unwrap();
+    assert!(home.contains(r#"<a href="/bio/index.html" class="nav-item">Bio</a>"#));
+    assert!(!home.contains("/Website-test"));
+    assert!(home.contains(r#"<link rel="canonical" href="https://example.com/">"#), "{}", home);
+
+    let robots = fs::read_to_string(out.path().join("robots.txt")).unwrap();
+    assert!(robots.contains("Sitemap: https://example.com/sitemap.xml"));
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{copy_fixture, fixture_dir};
+use website_test::content;
+
+#[test]
+fn testimonials_are_sorted_by_order() {
+    let testimonials = content::read_testimonials(&fixture_dir()).unwrap();
+    let authors: Vec<&str> = testimonials.iter().map(|testimonial| testimonial.author.as_str()).collect();
+    assert_eq!(authors, ["Tom & Jerry", "Pat"]);
+
+    let pat = &testimonials[1];
+    assert_eq!(pat.date, NaiveDate::from_ymd_opt(2020, 6, 1));
+    assert_eq!(pat.rating, Some(5));
+    assert_eq!(pat.photo.as_deref(), Some("global-images/1.png"));
+    assert!(pat.featured);
+    assert_eq!(pat.anchor(), "pat-photographer");
+
+    assert_eq!(content::aggregate_rating(&testimonials), Some((4.5, 2)));
+}
+
+#[test]
+fn testimonials_are_grouped_by_category() {
+    let yaml = "testimonials:
+  - { quote: a, author: A, title: T }
+  - { quote: b, author: B, title: T, category: Director }
+  - { quote: c, author: C, title: T, category: Venue }
+  - { quote: d, author: D, title: T, category: Director }
+";
+    let content = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(content.path().join("reviews")).unwrap();
+    std::fs::write(content.path().join("reviews/reviews.yaml"), yaml).unwrap();
+    let testimonials = content::read_testimonials(content.path()).unwrap();
+
+    let groups: Vec<(Option<&str>, Vec<&str>)> = content::group_testimonials(&testimonials)
+        .into_iter()
+        .map(|(category, members)| (category, members.iter().map(|testimonial| testimonial.author.as_str()).collect()))
+        .collect();
+    // Uncategorized testimonials come last
+    assert_eq!(groups, [(Some("Director"), vec!["B", "D"]), (Some("Venue"), vec!["C"]), (None, vec!["A"])]);
+    assert_eq!(content::aggregate_rating(&testimonials), None);
+}
+
+#[test]
+fn missing_reviews_file_means_no_testimonials() {
+    let content = tempfile::tempdir().unwrap();
+    assert!(content::read_testimonials(content.path()).unwrap().is_empty());
+}
+
+#[test]
+fn invalid_testimonials_are_reported() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    let path = content.path().join("reviews/reviews.yaml");
+
+    for (yaml, expected) in [
+        ("testimonials:\n  - { quote: a, author: A, title: T, date: last week }\n", "invalid date \"last week\""),
+        ("testimonials:\n  - { quote: a, author: A, title: T, rating: 6 }\n", "rating of \"A\" must be between 1 and 5"),
+        ("testimonials:\n  - { quote: a, author: A, title: T, stars: 5 }\n", "unknown field `stars`"),
+        ("testimonials:\n  - { quote: a, title: T }\n", "missing field `author`"),
+    ] {
+        std::fs::write(&path, yaml).unwrap();
+        let error = content::read_testimonials(content.path()).unwrap_err();
+        assert!(error.contains(expected), "{:?} should mention {:?}", error, expected);
+    }
+}
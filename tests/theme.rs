@@ -0,0 +1,115 @@
+mod common;
+
+use common::{TestApp, build_options, copy_fixture};
+use std::fs;
+use std::path::Path;
+use website_test::static_site;
+use website_test::theme::{self, ThemeSettings};
+
+const THEMES: &str = r##"
+theme: night
+themes:
+  day:
+    colors:
+      primary: "#6b73ff"
+      text: "#333"
+    radii:
+      card: 15px
+  night:
+    extends: day
+    colors:
+      text: "#111"
+    dark:
+      colors:
+        text: "#eee"
+pages:
+  /bio/:
+    extends: day
+    backgrounds:
+      page: "linear-gradient(45deg, #c44faf, var(--color-primary))"
+"##;
+
+fn settings(yaml: &str) -> ThemeSettings {
+    serde_yaml::from_str(yaml).unwrap()
+}
+
+#[test]
+fn themes_compile_to_custom_properties() {
+    let styles = settings(THEMES).stylesheets().unwrap();
+    assert_eq!(
+        styles.site,
+        ":root {\n    --color-primary: #6b73ff;\n    --color-text: #111;\n    --radius-card: 15px;\n}\n\
+         @media (prefers-color-scheme: dark) {\n    :root {\n        --color-text: #eee;\n    }\n}\n"
+    );
+    assert_eq!(
+        styles.pages["/bio/"],
+        ":root {\n    --color-primary: #6b73ff;\n    --color-text: #333;\n    --radius-card: 15px;\n    \
+         --background-page: linear-gradient(45deg, #c44faf, var(--color-primary));\n}\n"
+    );
+}
+
+#[test]
+fn broken_themes_are_errors() {
+    for (yaml, error) in [
+        ("theme: missing\nthemes:\n  day: {}\n", "unknown theme \"missing\""),
+        ("theme: a\nthemes:\n  a:\n    extends: b\n  b:\n    extends: a\n", "theme \"a\" extends itself"),
+        ("theme: a\nthemes:\n  a:\n    colors:\n      text: \"red; x: y\"\n", "invalid value \"red; x: y\" for color token \"text\""),
+        ("theme: a\nthemes:\n  a:\n    colors:\n      \"bad name\": red\n", "invalid color token name \"bad name\""),
+        ("theme: a\nthemes:\n  a:\n    backgrounds:\n      page: linear-gradient(red, #fff)\n", "invalid value \"linear-gradient(red,\" for background token \"page\""),
+        ("theme: a\nthemes:\n  a: {}\npages:\n  /bio/:\n    extends: nope\n", "/bio/: unknown theme \"nope\""),
+    ] {
+        assert_eq!(settings(yaml).stylesheets().unwrap_err(), error, "{}", yaml);
+    }
+    let unknown_group = serde_yaml::from_str::<ThemeSettings>("themes:\n  a:\n    colours:\n      text: red\n").unwrap_err();
+    assert!(unknown_group.to_string().contains("unknown field `colours`"), "{}", unknown_group);
+}
+
+#[test]
+fn content_without_a_theme_uses_the_built_in_one() {
+    let content = tempfile::tempdir().unwrap();
+    let styles = theme::read_theme(content.path()).unwrap().stylesheets().unwrap();
+    assert!(styles.site.contains("--color-primary: #6b73ff;"));
+    assert!(styles.site.contains("--background-brand: linear-gradient(45deg, var(--color-accent), var(--color-primary));"));
+    assert!(styles.pages.is_empty());
+}
+
+/// The CSS of the stylesheets `html` links, in order.
+fn linked_css(out_dir: &Path, html: &str) -> Vec<String> {
+    let prefix = r#"<link rel="stylesheet" href="/Website-test/css/"#;
+    html.match_indices(prefix)
+        .map(|(index, _)| {
+            let rest = &html[index + prefix.len()..];
+            fs::read_to_string(out_dir.join("css").join(&rest[..rest.find('"').unwrap()])).unwrap()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn pages_and_categories_can_override_the_theme() {
+    let content = tempfile::tempdir().unwrap();
+    copy_fixture(content.path());
+    fs::write(content.path().join("theme.yaml"), THEMES).unwrap();
+    fs::write(content.path().join("modeling/Glamour/theme.yaml"), "colors:\n  primary: \"#c44faf\"\n").unwrap();
+
+    let out = tempfile::tempdir().unwrap();
+    let report = static_site::build(&build_options(content.path(), out.path()));
+    assert!(report.is_publishable(), "{:?}", report.errors);
+
+    let home = linked_css(out.path(), &fs::read_to_string(out.path().join("index.html")).unwrap());
+    assert!(home[0].starts_with(":root{--color-primary:#6b73ff;--color-text:#111;--radius-card:15px}\n@media (prefers-color-scheme:dark){:root{--color-text:#eee}}\n"));
+    assert_eq!(home.len(), 1, "the home page has no override");
+
+    let bio = linked_css(out.path(), &fs::read_to_string(out.path().join("bio/index.html")).unwrap());
+    assert!(bio[1].starts_with(":root{--color-primary:#6b73ff;--color-text:#333;--radius-card:15px;--background-page:linear-gradient(45deg,#c44faf,var(--color-primary))}\n"));
+
+    // The category's override starts from the site theme, dark tokens included
+    let glamour = linked_css(out.path(), &fs::read_to_string(out.path().join("modeling/glamour/index.html")).unwrap());
+    assert_eq!(glamour[1], ":root{--color-primary:#c44faf;--color-text:#111;--radius-card:15px}\n@media (prefers-color-scheme:dark){:root{--color-text:#eee}}");
+
+    // The server links the same overrides
+    let app = TestApp::with_content(content.path().to_path_buf());
+    let (_, _, body) = app.get("/modeling/glamour/").await;
+    let start = body.rfind(r#"href="/css/page."#).unwrap() + r#"href="/css/"#.len();
+    let (_, _, css) = app.get(&format!("/css/{}", &body[start..start + body[start..].find('"').unwrap()])).await;
+    assert_eq!(css, glamour[1]);
+}
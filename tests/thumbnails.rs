@@ -0,0 +1,13 @@
+use website_test::thumbnails::{MAX_THUMBNAIL_BYTES, read_thumbnail};
+
+#[test]
+fn only_complete_images_are_accepted() {
+    assert_eq!(read_thumbnail(200, "image/jpeg", &b"jpeg bytes"[..]).unwrap(), b"jpeg bytes");
+    assert!(read_thumbnail(404, "image/jpeg", &b"jpeg bytes"[..]).unwrap_err().contains("status 404"));
+    assert!(read_thumbnail(200, "text/html; charset=utf-8", &b"<html>"[..]).unwrap_err().contains("not an image"));
+
+    let limit = MAX_THUMBNAIL_BYTES as usize;
+    assert_eq!(read_thumbnail(200, "image/jpeg", &vec![0; limit][..]).unwrap().len(), limit);
+    let oversized = read_thumbnail(200, "image/jpeg", &vec![0; limit + 1][..]).unwrap_err();
+    assert!(oversized.contains("larger than"), "{}", oversized);
+}